@@ -0,0 +1,90 @@
+//! Day 11 again, using a grid and the cascade of flashes from the shared automaton module.
+
+use aoc_common::automaton::cascade;
+use aoc_common::grid::Grid;
+use std::fs;
+
+fn main() {
+    let contents = fs::read_to_string("data/day11_input.txt").unwrap();
+    let mut energy_levels = parse(&contents);
+    let mut part1_total_flash_count = 0;
+    let mut part2_first_synchronized_step = 0;
+    for step in 1.. {
+        let flash_count = step_and_count_flashes(&mut energy_levels);
+        if step <= 100 {
+            part1_total_flash_count += flash_count;
+        }
+        if flash_count == energy_levels.width() * energy_levels.height() {
+            part2_first_synchronized_step = step;
+            if step >= 100 {
+                break;
+            }
+        }
+    }
+    println!("Part 1: flashed {} times", part1_total_flash_count);
+    println!(
+        "Part 2: first synchronized on step {}",
+        part2_first_synchronized_step
+    );
+}
+
+fn parse(contents: &str) -> Grid<u32> {
+    Grid::parse_with(contents, |_, ch| ch.to_digit(10).unwrap())
+}
+
+fn step_and_count_flashes(energy_levels: &mut Grid<u32>) -> usize {
+    for (_, energy) in energy_levels.iter_mut() {
+        *energy += 1;
+    }
+    let flashed = cascade(energy_levels, |&energy| energy > 9, |energy| *energy += 1);
+    for &pos in &flashed {
+        energy_levels[pos] = 0;
+    }
+    flashed.len()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const EXAMPLE: &str = "5483143223\n\
+         2745854711\n\
+         5264556173\n\
+         6141336146\n\
+         6357385478\n\
+         4167524645\n\
+         2176841721\n\
+         6882881134\n\
+         4846848554\n\
+         5283751526\n";
+
+    const STEP10: &str = "0481112976\n\
+         0031112009\n\
+         0041112504\n\
+         0081111406\n\
+         0099111306\n\
+         0093511233\n\
+         0442361130\n\
+         5532252350\n\
+         0532250600\n\
+         0032240000\n";
+
+    #[test]
+    fn test_ten_steps() {
+        let mut energy_levels = parse(EXAMPLE);
+        let flash_count: usize = (0..10)
+            .map(|_| step_and_count_flashes(&mut energy_levels))
+            .sum();
+        assert_eq!(flash_count, 204);
+        assert_eq!(energy_levels, parse(STEP10));
+    }
+
+    #[test]
+    fn test_first_synchronized_step() {
+        let mut energy_levels = parse(EXAMPLE);
+        let first_synchronized_step = (1..)
+            .find(|_| step_and_count_flashes(&mut energy_levels) == 100)
+            .unwrap();
+        assert_eq!(first_synchronized_step, 195);
+    }
+}
@@ -1,3 +1,4 @@
+use aoc_common::ocr::read_letters_from_points;
 use std::collections::HashSet;
 use std::fs;
 use std::time::Instant;
@@ -34,6 +35,11 @@ fn main() {
         .fold((0_u64, 0_u64), |(max_x, max_y), &(x, y)| {
             (max_x.max(x), max_y.max(y))
         });
+    let letters = read_letters_from_points(
+        part2_transformed_coordinates
+            .iter()
+            .map(|&(x, y)| (x as usize, y as usize)),
+    );
     let mut grid: Vec<Vec<u8>> = vec![vec![b' '; (max_x + 1) as usize]; (max_y + 1) as usize];
     for pos in part2_transformed_coordinates {
         grid[pos.1 as usize][pos.0 as usize] = b'#';
@@ -44,6 +50,10 @@ fn main() {
     for row in grid {
         println!("{}", String::from_utf8_lossy(row.as_slice()));
     }
+    match letters {
+        Ok(letters) => println!("Part 2 answer: {}", letters),
+        Err(err) => println!("Part 2 letters could not be read: {}", err),
+    }
 
     let duration = start_time.elapsed();
     println!("Duration: {:?}\n", duration); // 343.424µs
@@ -0,0 +1,74 @@
+//! Day 14 again, using the polymer library, which only tracks pairs of elements present in the input.
+//!
+//! Usage:
+//!     day14_problem1and2_matrix        Solve both parts
+//!     day14_problem1and2_matrix STEPS  Also print the number of each element after STEPS steps
+//!
+//! The counts are exact for up to 1000 steps. Beyond that their digits grow too numerous to compute,
+//! so only their last 18 digits are printed, for any number of steps up to 2^64 - 1.
+
+use aoc2021::polymer::{Count, ModularCount, Polymer, PolymerError};
+use num::BigUint;
+use std::collections::BTreeMap;
+use std::fs;
+use std::process::exit;
+use std::time::Instant;
+
+const MAX_EXACT_STEPS: u64 = 1000;
+const LAST_DIGITS_MODULUS: u64 = 1_000_000_000_000_000_000;
+
+fn main() {
+    let extra_steps: Option<u64> = std::env::args().nth(1).map(|arg| {
+        arg.parse().unwrap_or_else(|_| {
+            eprintln!("Invalid number of steps {arg:?}");
+            exit(1);
+        })
+    });
+
+    let start_time = Instant::now();
+    let contents = fs::read_to_string("data/day14_input.txt").unwrap();
+    let polymer: Polymer = contents.parse().unwrap_or_else(|err| {
+        eprintln!("Invalid input: {err}");
+        exit(1);
+    });
+    let part1_answer = most_minus_least_common::<u128>(&polymer, 10);
+    let part2_answer = most_minus_least_common::<u128>(&polymer, 40);
+    let duration = start_time.elapsed();
+    println!("Part 1 answer: {}", part1_answer.unwrap());
+    println!("Part 2 answer: {}", part2_answer.unwrap());
+    println!("Duration (including I/O): {:?}", duration);
+
+    if let Some(steps) = extra_steps {
+        println!();
+        println!("After {steps} steps:");
+        if steps <= MAX_EXACT_STEPS {
+            match polymer.histogram::<BigUint>(steps) {
+                Ok(histogram) => {
+                    for (element, count) in histogram {
+                        println!("{element}: {count}");
+                    }
+                }
+                Err(err) => println!("{err}"),
+            }
+        } else {
+            match polymer.histogram::<ModularCount<LAST_DIGITS_MODULUS>>(steps) {
+                Ok(histogram) => {
+                    for (element, count) in histogram {
+                        println!("{element}: ...{:018}", count.0);
+                    }
+                }
+                Err(err) => println!("{err}"),
+            }
+        }
+    }
+}
+
+fn most_minus_least_common<T>(polymer: &Polymer, steps: u64) -> Result<T, PolymerError>
+where
+    T: Count + Ord + std::ops::Sub<Output = T>,
+{
+    let histogram: BTreeMap<char, T> = polymer.histogram(steps)?;
+    let most_common = histogram.values().max().unwrap().clone();
+    let least_common = histogram.values().min().unwrap().clone();
+    Ok(most_common - least_common)
+}
@@ -0,0 +1,47 @@
+//! Day 16 again, using the BITS library.
+//!
+//! Usage:
+//!     day16_problem1and2_bits              Solve both parts, and print the packet as an S-expression
+//!     day16_problem1and2_bits encode EXPR  Encode an expression such as "sum(1, max(2, 3))" in hex
+
+use aoc2021::bits::Packet;
+use std::fs;
+use std::process::exit;
+use std::time::Instant;
+
+fn main() {
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    if let [command, expression] = args.as_slice() {
+        if command == "encode" {
+            match expression
+                .parse::<Packet>()
+                .and_then(|packet| packet.encode())
+            {
+                Ok(hex) => println!("{hex}"),
+                Err(err) => {
+                    eprintln!("{err}");
+                    exit(1);
+                }
+            }
+            return;
+        }
+    }
+
+    let start_time = Instant::now();
+    let contents = fs::read_to_string("data/day16_input.txt").unwrap();
+    let packet = Packet::decode(&contents).unwrap_or_else(|err| {
+        eprintln!("Invalid transmission: {err}");
+        exit(1);
+    });
+    let part1_answer = packet.version_sum();
+    let part2_answer = packet.evaluate().unwrap_or_else(|err| {
+        eprintln!("Invalid transmission: {err}");
+        exit(1);
+    });
+    let duration = start_time.elapsed();
+    println!("Part 1 answer: {}", part1_answer);
+    println!("Part 2 answer: {}", part2_answer);
+    println!("Duration (including I/O): {:?}", duration);
+    println!();
+    println!("{packet:#}");
+}
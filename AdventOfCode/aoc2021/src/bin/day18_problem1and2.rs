@@ -1,7 +1,11 @@
+use aoc2021::snailfish::{self, SnailfishNumber as FlatSnailfishNumber};
 use std::fs;
+use std::hint::black_box;
 use std::ops::Add;
 use std::time::Instant;
 
+const NUM_REPETITIONS: u32 = 10;
+
 #[derive(Clone, Debug, PartialEq)]
 struct SnailFishNumber {
     pair: (Element, Element),
@@ -278,10 +282,20 @@ fn main() {
 
     println!("Part 1 answer: {}", magnitude_of_sum);
 
+    let max_magnitude_of_sum_of_pairs = largest_magnitude_of_pair_sum(&snailfish_numbers);
+
+    println!("Part 2 answer: {}", max_magnitude_of_sum_of_pairs);
+
+    let duration = start_time.elapsed();
+    println!("Duration: {:?}", duration);
+
+    compare_part2_with_flat_numbers(&snailfish_numbers, &contents);
+}
+
+fn largest_magnitude_of_pair_sum(snailfish_numbers: &[SnailFishNumber]) -> usize {
     let count = snailfish_numbers.len();
-    let max_magnitude_of_sum_of_pairs: usize = (0..count)
-        .into_iter()
-        .flat_map(|i| (0..count).into_iter().map(move |j| (i, j)))
+    (0..count)
+        .flat_map(|i| (0..count).map(move |j| (i, j)))
         .filter(|(i, j)| *i != *j)
         .map(|(i, j)| {
             let sum_of_pair: SnailFishNumber =
@@ -289,12 +303,33 @@ fn main() {
             sum_of_pair.magnitude()
         })
         .max()
-        .unwrap();
+        .unwrap()
+}
 
-    println!("Part 2 answer: {}", max_magnitude_of_sum_of_pairs);
+/// Time the all-pairs search for part 2 with these boxed trees,
+/// and with the flat snailfish numbers in the library
+fn compare_part2_with_flat_numbers(snailfish_numbers: &[SnailFishNumber], contents: &str) {
+    let flat_numbers: Vec<FlatSnailfishNumber> =
+        contents.lines().map(|line| line.parse().unwrap()).collect();
 
-    let duration = start_time.elapsed();
-    println!("Duration: {:?}", duration);
+    let start_time = Instant::now();
+    for _ in 0..NUM_REPETITIONS {
+        black_box(largest_magnitude_of_pair_sum(black_box(snailfish_numbers)));
+    }
+    let tree_duration = start_time.elapsed() / NUM_REPETITIONS;
+
+    let start_time = Instant::now();
+    let mut flat_answer = 0;
+    for _ in 0..NUM_REPETITIONS {
+        flat_answer =
+            black_box(snailfish::largest_magnitude_of_pair_sum(black_box(&flat_numbers)).unwrap());
+    }
+    let flat_duration = start_time.elapsed() / NUM_REPETITIONS;
+
+    println!();
+    println!("Part 2 all-pairs search (average of {NUM_REPETITIONS} runs):");
+    println!("Boxed tree: {tree_duration:?}");
+    println!("Flat:       {flat_duration:?} (answer {flat_answer})");
 }
 
 #[cfg(test)]
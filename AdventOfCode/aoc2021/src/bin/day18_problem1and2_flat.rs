@@ -0,0 +1,42 @@
+//! Day 18 again, using the flat snailfish numbers in the library.
+//! Run day18_problem1and2 to compare its part 2 search with the tree version.
+
+use aoc2021::snailfish::SnailfishNumber;
+use std::fs;
+use std::process::exit;
+use std::time::Instant;
+
+fn main() {
+    let start_time = Instant::now();
+    let contents = fs::read_to_string("data/day18_input.txt").unwrap();
+    let numbers: Vec<SnailfishNumber> = contents
+        .lines()
+        .map(str::parse)
+        .collect::<Result<_, _>>()
+        .unwrap_or_else(|err| {
+            eprintln!("Invalid snailfish number: {err}");
+            exit(1);
+        });
+    let sum: Option<SnailfishNumber> = numbers.iter().sum();
+    let part1_answer = sum.map_or(0, |sum| sum.magnitude());
+    let part2_answer = largest_magnitude_of_pair_sum(&numbers);
+    let duration = start_time.elapsed();
+    println!("Part 1 answer: {}", part1_answer);
+    println!("Part 2 answer: {}", part2_answer);
+    println!("Duration (including I/O): {:?}", duration);
+}
+
+fn largest_magnitude_of_pair_sum(numbers: &[SnailfishNumber]) -> u64 {
+    numbers
+        .iter()
+        .enumerate()
+        .flat_map(|(i, a)| {
+            numbers
+                .iter()
+                .enumerate()
+                .filter(move |(j, _)| i != *j)
+                .map(move |(_, b)| (a + b).magnitude())
+        })
+        .max()
+        .unwrap_or(0)
+}
@@ -0,0 +1,33 @@
+//! Day 19 again, only aligning scanners which share enough pairwise beacon distances.
+
+use aoc2021::scanners::{all_beacons, locate_scanners, manhattan_distance, parse_scanners};
+use std::fs;
+use std::process::exit;
+use std::time::Instant;
+
+fn main() {
+    let start_time = Instant::now();
+    let contents = fs::read_to_string("data/day19_input.txt").unwrap();
+    let scanners = parse_scanners(&contents).unwrap_or_else(|err| {
+        eprintln!("{err}");
+        exit(1);
+    });
+    let placements = locate_scanners(&scanners).unwrap_or_else(|err| {
+        eprintln!("{err}");
+        exit(1);
+    });
+    let part1_answer = all_beacons(&scanners, &placements).len();
+    let part2_answer = placements
+        .iter()
+        .flat_map(|a| {
+            placements
+                .iter()
+                .map(|b| manhattan_distance(a.position, b.position))
+        })
+        .max()
+        .unwrap_or(0);
+    let duration = start_time.elapsed();
+    println!("Part 1 answer: {}", part1_answer);
+    println!("Part 2 answer: {}", part2_answer);
+    println!("Duration (including I/O): {:?}", duration);
+}
@@ -0,0 +1,69 @@
+//! Day 20 again, using the shared cellular automaton.
+//! It tracks the infinite background, which flips between lit and unlit
+//! when the first bit of the algorithm is lit.
+
+use aoc_common::automaton::{Automaton, SparseGrid, Window};
+use std::fs;
+use std::time::Instant;
+
+fn main() {
+    let start_time = Instant::now();
+    let contents = fs::read_to_string("data/day20_input.txt").unwrap();
+    let (part1, part2) = solve(&contents);
+    println!("Part 1: number of lit pixels after 2 rounds: {}", part1);
+    println!("Part 2: number of lit pixels after 50 rounds: {}", part2);
+    println!("Duration: {:?}", start_time.elapsed());
+}
+
+fn solve(contents: &str) -> (usize, usize) {
+    let (alg_str, input_image_str) = contents.split_once("\n\n").unwrap();
+    let alg: Vec<bool> = alg_str.trim().bytes().map(|byte| byte == b'#').collect();
+    assert_eq!(alg.len(), 512);
+
+    let image = SparseGrid::parse_with(input_image_str, false, |_, ch| ch == '#');
+    let enhance = |window: &Window<bool>| {
+        let nine_pixel_code = window
+            .iter()
+            .fold(0, |code, &is_lit| 2 * code + is_lit as usize);
+        alg[nine_pixel_code]
+    };
+    let mut automaton = Automaton::new(image, enhance);
+    automaton.run(2);
+    let part1 = count_lit_pixels(automaton.grid());
+    automaton.run(48);
+    let part2 = count_lit_pixels(automaton.grid());
+    (part1, part2)
+}
+
+fn count_lit_pixels(image: &SparseGrid<bool>) -> usize {
+    assert!(
+        !image.background(),
+        "Infinitely many pixels are lit after an odd number of rounds"
+    );
+    image.len()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const EXAMPLE: &str =
+        "..#.#..#####.#.#.#.###.##.....###.##.#..###.####..#####..#....#..#..##..##\
+        #..######.###...####..#..#####..##..#.#####...##.#.#..#.##..#.#......#.###\
+        .######.###.####...#.##.##..#..#..#####.....#.#....###..#.##......#.....#.\
+        .#..#..##..#...##.######.####.####.#.#...#.......#..#.#.#...####.##.#.....\
+        .#..#...##.#.##..#...##.#.##..###.#......#.#.......#.#.#.####.###.##...#..\
+        ...####.#..#..#.##.#....##..#.####....##...##..#...#......#.#.......#.....\
+        ..##..####..#...#.#.#...##..#.#..###..#####........#..####......#..#\n\
+        \n\
+        #..#.\n\
+        #....\n\
+        ##..#\n\
+        ..#..\n\
+        ..###\n";
+
+    #[test]
+    fn test_example() {
+        assert_eq!(solve(EXAMPLE), (35, 3351));
+    }
+}
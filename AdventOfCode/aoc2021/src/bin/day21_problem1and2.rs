@@ -0,0 +1,64 @@
+//! Day 21 again, using the dice game engine with the rules as parameters.
+//!
+//! Usage:
+//!     day21_problem1and2                                                  Solve both parts
+//!     day21_problem1and2 BOARD_SIZE TARGET_SCORE DIE_FACES ROLLS_PER_TURN  Play part 2 with other rules
+
+use aoc2021::dice::GameRules;
+use std::fs;
+use std::process::exit;
+use std::time::Instant;
+
+fn main() {
+    let args: Vec<u32> = std::env::args()
+        .skip(1)
+        .map(|arg| {
+            arg.parse()
+                .unwrap_or_else(|_| exit_with_error(&format!("Invalid number {arg:?}")))
+        })
+        .collect();
+    let dirac_rules = match args.as_slice() {
+        [] => GameRules::DIRAC,
+        &[board_size, target_score, die_faces, rolls_per_turn] => {
+            GameRules::new(board_size, target_score, die_faces, rolls_per_turn)
+                .unwrap_or_else(|err| exit_with_error(&err.to_string()))
+        }
+        _ => exit_with_error("Expected no arguments, or 4 rules"),
+    };
+
+    let start_time = Instant::now();
+    let contents = fs::read_to_string("data/day21_input.txt").unwrap();
+    let start_positions = parse_start_positions(&contents);
+    let outcome = GameRules::PRACTICE
+        .play_deterministic(start_positions)
+        .unwrap_or_else(|err| exit_with_error(&err.to_string()));
+    let wins = dirac_rules
+        .count_wins(start_positions)
+        .unwrap_or_else(|err| exit_with_error(&err.to_string()));
+    let duration = start_time.elapsed();
+    println!("Part 1 answer: {}", outcome.losing_score_times_rolls());
+    println!("Part 2 answer: {}", wins.iter().max().unwrap());
+    println!("Universes won by each player: {wins:?}");
+    println!("Duration (including I/O): {:?}", duration);
+}
+
+fn parse_start_positions(contents: &str) -> [u32; 2] {
+    let positions: Vec<u32> = contents
+        .lines()
+        .map(|line| {
+            let (_, position_str) = line.rsplit_once(' ').unwrap_or(("", line));
+            position_str
+                .trim()
+                .parse()
+                .unwrap_or_else(|_| exit_with_error(&format!("Invalid line {line:?}")))
+        })
+        .collect();
+    positions
+        .try_into()
+        .unwrap_or_else(|_| exit_with_error("Expected 2 starting positions"))
+}
+
+fn exit_with_error(message: &str) -> ! {
+    eprintln!("{message}");
+    exit(1);
+}
@@ -0,0 +1,302 @@
+//! Day 22 again, comparing three ways of simulating the reactor, using the shared cuboid algebra:
+//! - coordinate compression, as in day22_problem1and2.rs
+//! - inclusion-exclusion, which counts cuboids and their overlaps with signed counts
+//! - splitting cuboids into disjoint pieces
+
+use aoc_common::cuboid::{Cuboid, DisjointCuboids, SignedCuboids};
+use std::fs;
+use std::mem::size_of;
+use std::time::{Duration, Instant};
+
+const NUM_REPETITIONS: u32 = 10;
+
+#[derive(Debug, Clone)]
+struct RebootStep {
+    is_on: bool,
+    cuboid: Cuboid<3>,
+}
+
+trait Reactor {
+    const NAME: &'static str;
+
+    /// Some strategies need to see all the steps before they start
+    fn new(steps: &[RebootStep]) -> Self;
+
+    fn switch(&mut self, step: &RebootStep);
+
+    fn count_on(&self) -> i64;
+
+    /// An estimate of the memory used to store the state of the reactor
+    fn memory_used(&self) -> usize;
+}
+
+/// Switches blocks of cubes on and off, where the blocks are found by splitting every axis
+/// at the start and end of each cuboid
+struct CompressedReactor {
+    boundaries: [Vec<i64>; 3],
+    is_block_on: Vec<bool>,
+}
+
+impl CompressedReactor {
+    fn block_indices(&self, cuboid: &Cuboid<3>, dim: usize) -> std::ops::Range<usize> {
+        let boundaries = &self.boundaries[dim];
+        let start = boundaries.binary_search(&cuboid.min[dim]).unwrap();
+        let end = boundaries.binary_search(&(cuboid.max[dim] + 1)).unwrap();
+        start..end
+    }
+}
+
+impl Reactor for CompressedReactor {
+    const NAME: &'static str = "Coordinate compression";
+
+    fn new(steps: &[RebootStep]) -> Self {
+        let boundaries = std::array::from_fn(|dim| {
+            let mut values: Vec<i64> = steps
+                .iter()
+                .flat_map(|step| [step.cuboid.min[dim], step.cuboid.max[dim] + 1])
+                .collect();
+            values.sort_unstable();
+            values.dedup();
+            values
+        });
+        let block_count = boundaries
+            .iter()
+            .map(|values: &Vec<i64>| values.len().saturating_sub(1))
+            .product();
+        Self {
+            boundaries,
+            is_block_on: vec![false; block_count],
+        }
+    }
+
+    fn switch(&mut self, step: &RebootStep) {
+        let y_count = self.boundaries[1].len() - 1;
+        let z_count = self.boundaries[2].len() - 1;
+        for x in self.block_indices(&step.cuboid, 0) {
+            for y in self.block_indices(&step.cuboid, 1) {
+                let row_start = (x * y_count + y) * z_count;
+                let z_indices = self.block_indices(&step.cuboid, 2);
+                self.is_block_on[row_start + z_indices.start..row_start + z_indices.end]
+                    .fill(step.is_on);
+            }
+        }
+    }
+
+    fn count_on(&self) -> i64 {
+        let widths: Vec<Vec<i64>> = self
+            .boundaries
+            .iter()
+            .map(|values| values.windows(2).map(|pair| pair[1] - pair[0]).collect())
+            .collect();
+        let mut is_block_on = self.is_block_on.iter();
+        let mut total_on = 0;
+        for x_width in &widths[0] {
+            for y_width in &widths[1] {
+                for z_width in &widths[2] {
+                    if *is_block_on.next().unwrap() {
+                        total_on += x_width * y_width * z_width;
+                    }
+                }
+            }
+        }
+        total_on
+    }
+
+    fn memory_used(&self) -> usize {
+        let boundary_count: usize = self.boundaries.iter().map(Vec::len).sum();
+        boundary_count * size_of::<i64>() + self.is_block_on.len() * size_of::<bool>()
+    }
+}
+
+struct InclusionExclusionReactor(SignedCuboids<3>);
+
+impl Reactor for InclusionExclusionReactor {
+    const NAME: &'static str = "Inclusion-exclusion";
+
+    fn new(_steps: &[RebootStep]) -> Self {
+        Self(SignedCuboids::new())
+    }
+
+    fn switch(&mut self, step: &RebootStep) {
+        if step.is_on {
+            self.0.add(step.cuboid);
+        } else {
+            self.0.remove(step.cuboid);
+        }
+    }
+
+    fn count_on(&self) -> i64 {
+        self.0.volume()
+    }
+
+    fn memory_used(&self) -> usize {
+        self.0.len() * size_of::<(Cuboid<3>, i64)>()
+    }
+}
+
+struct SplittingReactor(DisjointCuboids<3>);
+
+impl Reactor for SplittingReactor {
+    const NAME: &'static str = "Splitting into disjoint cuboids";
+
+    fn new(_steps: &[RebootStep]) -> Self {
+        Self(DisjointCuboids::new())
+    }
+
+    fn switch(&mut self, step: &RebootStep) {
+        if step.is_on {
+            self.0.add(step.cuboid);
+        } else {
+            self.0.remove(step.cuboid);
+        }
+    }
+
+    fn count_on(&self) -> i64 {
+        self.0.volume()
+    }
+
+    fn memory_used(&self) -> usize {
+        self.0.len() * size_of::<Cuboid<3>>()
+    }
+}
+
+fn main() {
+    let contents = fs::read_to_string("data/day22_input.txt").unwrap();
+    let steps = parse(&contents);
+    let init_steps = clip_to_initialization_region(&steps);
+    compare::<CompressedReactor>(&init_steps, &steps);
+    compare::<InclusionExclusionReactor>(&init_steps, &steps);
+    compare::<SplittingReactor>(&init_steps, &steps);
+}
+
+fn compare<R: Reactor>(init_steps: &[RebootStep], steps: &[RebootStep]) {
+    println!("{}", R::NAME);
+    println!("{}", "-".repeat(R::NAME.len()));
+    for (part, steps) in [("Part 1", init_steps), ("Part 2", steps)] {
+        let (reactor, duration) = time_reboot::<R>(steps);
+        println!(
+            "{part} answer: {} (avg duration {duration:?}, using about {} KiB)",
+            reactor.count_on(),
+            reactor.memory_used() / 1024
+        );
+    }
+    println!();
+}
+
+/// Reboot the reactor several times, and return it with the average duration
+fn time_reboot<R: Reactor>(steps: &[RebootStep]) -> (R, Duration) {
+    let start_time = Instant::now();
+    for _ in 1..NUM_REPETITIONS {
+        reboot::<R>(steps);
+    }
+    let reactor = reboot::<R>(steps);
+    (reactor, start_time.elapsed() / NUM_REPETITIONS)
+}
+
+fn reboot<R: Reactor>(steps: &[RebootStep]) -> R {
+    let mut reactor = R::new(steps);
+    for step in steps {
+        reactor.switch(step);
+    }
+    reactor
+}
+
+fn clip_to_initialization_region(steps: &[RebootStep]) -> Vec<RebootStep> {
+    let region = Cuboid::new([-50; 3], [50; 3]).unwrap();
+    steps
+        .iter()
+        .filter_map(|step| {
+            Some(RebootStep {
+                is_on: step.is_on,
+                cuboid: step.cuboid.intersection(&region)?,
+            })
+        })
+        .collect()
+}
+
+fn parse(contents: &str) -> Vec<RebootStep> {
+    contents
+        .lines()
+        .map(|line| {
+            let (instruction_str, coords_str) = line.split_once(' ').unwrap();
+            let mut ranges = coords_str.split(',').map(|part_str| {
+                let (start_str, end_str) = part_str
+                    .split_once('=')
+                    .unwrap()
+                    .1
+                    .split_once("..")
+                    .unwrap();
+                start_str.parse::<i64>().unwrap()..=end_str.parse::<i64>().unwrap()
+            });
+            let ranges = [(); 3].map(|_| ranges.next().unwrap());
+            RebootStep {
+                is_on: instruction_str == "on",
+                cuboid: Cuboid::from_ranges(ranges).unwrap(),
+            }
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SMALL_EXAMPLE: &str = "on x=10..12,y=10..12,z=10..12\n\
+        on x=11..13,y=11..13,z=11..13\n\
+        off x=9..11,y=9..11,z=9..11\n\
+        on x=10..10,y=10..10,z=10..10";
+
+    fn count_on_with_each_reactor(steps: &[RebootStep]) -> [i64; 3] {
+        [
+            reboot::<CompressedReactor>(steps).count_on(),
+            reboot::<InclusionExclusionReactor>(steps).count_on(),
+            reboot::<SplittingReactor>(steps).count_on(),
+        ]
+    }
+
+    #[test]
+    fn test_small_example() {
+        let steps = parse(SMALL_EXAMPLE);
+        assert_eq!(count_on_with_each_reactor(&steps), [39; 3]);
+    }
+
+    mod prop_tests {
+        use super::*;
+        use proptest::collection;
+        use proptest::prelude::*;
+
+        fn reboot_steps() -> impl Strategy<Value = Vec<RebootStep>> {
+            let step = (
+                any::<bool>(),
+                [-100_i64..100, -100..100, -100..100],
+                [0_i64..80, 0..80, 0..80],
+            )
+                .prop_map(|(is_on, min, size)| RebootStep {
+                    is_on,
+                    cuboid: Cuboid::new(min, std::array::from_fn(|i| min[i] + size[i])).unwrap(),
+                });
+            collection::vec(step, 1..40)
+        }
+
+        proptest! {
+            #![proptest_config(ProptestConfig::with_cases(32))]
+
+            #[test]
+            fn reactors_agree(steps in reboot_steps()) {
+                let [compressed, inclusion_exclusion, splitting] = count_on_with_each_reactor(&steps);
+                prop_assert_eq!(inclusion_exclusion, compressed);
+                prop_assert_eq!(splitting, compressed);
+            }
+
+            #[test]
+            fn reactors_agree_in_initialization_region(steps in reboot_steps()) {
+                let init_steps = clip_to_initialization_region(&steps);
+                let [compressed, inclusion_exclusion, splitting] =
+                    count_on_with_each_reactor(&init_steps);
+                prop_assert!(compressed <= 101 * 101 * 101);
+                prop_assert_eq!(inclusion_exclusion, compressed);
+                prop_assert_eq!(splitting, compressed);
+            }
+        }
+    }
+}
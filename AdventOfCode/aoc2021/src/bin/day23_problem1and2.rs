@@ -0,0 +1,561 @@
+//! Day 23, generalising day23_problem1.rs to side rooms of any depth up to 6,
+//! so that the unfolded diagram in part 2 is solved by the same A* search.
+//!
+//! Usage:
+//!     day23_problem1and2          Solve both parts
+//!     day23_problem1and2 --moves  Also print the cheapest sequence of moves for each part
+
+use pathfinding::prelude::astar;
+use petgraph::algo::bellman_ford;
+use petgraph::graph::{NodeIndex, UnGraph};
+use std::fmt::{Display, Formatter};
+use std::fs;
+use std::process::exit;
+use std::time::Instant;
+use thiserror::Error;
+
+fn main() {
+    let show_moves = std::env::args().any(|arg| arg == "--moves");
+    let contents = fs::read_to_string("data/day23_input.txt").unwrap();
+    let unfolded = unfold(&contents).unwrap_or_else(|err| exit_with_diagram_error(err));
+    for (part, diagram) in [("Part 1", contents), ("Part 2", unfolded)] {
+        let start_time = Instant::now();
+        let (burrow, initial_state) =
+            parse_diagram(&diagram).unwrap_or_else(|err| exit_with_diagram_error(err));
+        let Some(solution) = burrow.solve(initial_state) else {
+            println!("{part}: no solution could be found");
+            continue;
+        };
+        println!(
+            "{part} answer: {} (duration: {:?})",
+            solution.energy,
+            start_time.elapsed()
+        );
+        if show_moves {
+            println!();
+            println!("{}", burrow.render(&initial_state));
+            for (mv, state) in burrow
+                .moves(&solution.states)
+                .iter()
+                .zip(&solution.states[1..])
+            {
+                println!();
+                println!("{mv}");
+                println!("{}", burrow.render(state));
+            }
+            println!();
+        }
+    }
+}
+
+// ----------------------------------------------------------------------------
+// 1. Occupiable cells, numbered as in day23_problem1.rs, but with any number of rows of side rooms:
+//
+// #############
+// #..x.x.x.x..#   0..7        The x's are lobbies, which can be moved through, but not occupied.
+// ###.#.#.#.###   7..11       Row 0 of the side rooms
+//   #.#.#.#.#    11..15       Row 1
+//   #.#.#.#.#    15..19       ...
+//   #########
+//
+// The lobbies are numbered after the last row of side rooms.
+// Masks of cells are u32's, which limits the side rooms to a depth of 6.
+
+type Energy = u32;
+
+const HALLWAY_CELL_COUNT: usize = 7;
+const ROOM_COUNT: usize = 4;
+const MAX_CELL_COUNT: usize = 32;
+const MAX_DEPTH: usize = (MAX_CELL_COUNT - HALLWAY_CELL_COUNT) / ROOM_COUNT;
+const HALLWAY_COLUMNS: [usize; HALLWAY_CELL_COUNT] = [1, 2, 4, 6, 8, 10, 11];
+const STEP_ENERGIES: [Energy; ROOM_COUNT] = [1, 10, 100, 1000];
+const KIND_SYMBOLS: [char; ROOM_COUNT] = ['A', 'B', 'C', 'D'];
+
+/// The lines to insert into the diagram for part 2
+const UNFOLDED_LINES: [&str; 2] = ["  #D#C#B#A#", "  #D#B#A#C#"];
+
+fn room_cell(room: usize, row: usize) -> usize {
+    HALLWAY_CELL_COUNT + row * ROOM_COUNT + room
+}
+
+fn room_and_row(cell: usize) -> Option<(usize, usize)> {
+    let room_index = cell.checked_sub(HALLWAY_CELL_COUNT)?;
+    Some((room_index % ROOM_COUNT, room_index / ROOM_COUNT))
+}
+
+/// The line and column of the cell in the diagram
+fn diagram_position(cell: usize) -> (usize, usize) {
+    match room_and_row(cell) {
+        Some((room, row)) => (2 + row, 3 + 2 * room),
+        None => (1, HALLWAY_COLUMNS[cell]),
+    }
+}
+
+// ----------------------------------------------------------------------------
+// 2. For each source and destination cell, the distance between them,
+//    and a mask of the cells which must be empty to move between them
+//    (excluding the source, including the destination).
+
+#[derive(Copy, Clone, Default, Debug)]
+struct DistanceAndMask {
+    distance: Energy,
+    mask: u32,
+}
+
+type DistanceAndMaskLookup = Vec<Vec<DistanceAndMask>>;
+
+fn get_distances_and_mask_lookup(depth: usize) -> DistanceAndMaskLookup {
+    let cell_count = HALLWAY_CELL_COUNT + ROOM_COUNT * depth;
+    let mut lookup = vec![vec![DistanceAndMask::default(); cell_count]; cell_count];
+    let cell_graph = get_cell_graph(depth);
+
+    // use Bellman-Ford, not Floyd-Warshall algorithm, since we want to calculate masks, so need the path back
+    for (src, src_lookup) in lookup.iter_mut().enumerate() {
+        let paths = bellman_ford(&cell_graph, (src as u8).into())
+            .unwrap_or_else(|_| panic!("Bellman-Ford algorithm failed with node {}", src));
+        for (dst, (&weight, predecessor)) in
+            paths.distances.iter().zip(&paths.predecessors).enumerate()
+        {
+            if (dst == src) || (dst >= cell_count) {
+                continue;
+            }
+            src_lookup[dst].distance = weight as Energy;
+            let mut mask: u32 = 1 << dst;
+            let mut next_predecessor: &Option<NodeIndex<u8>> = predecessor;
+            while let Some(prev_node) = next_predecessor {
+                let prev_cell_ix = prev_node.index();
+                if prev_cell_ix == src {
+                    break;
+                }
+                if prev_cell_ix < cell_count {
+                    mask |= 1 << prev_cell_ix;
+                }
+                next_predecessor = &paths.predecessors[prev_cell_ix];
+            }
+            src_lookup[dst].mask = mask;
+        }
+    }
+
+    lookup
+}
+
+fn get_cell_graph(depth: usize) -> UnGraph<u8, f32, u8> {
+    let cell_count = HALLWAY_CELL_COUNT + ROOM_COUNT * depth;
+    let lobby = |room: usize| (cell_count + room) as u8;
+    let mut edges: Vec<(u8, u8, f32)> = vec![(0, 1, 1.0), (5, 6, 1.0)];
+    for room in 0..ROOM_COUNT {
+        // The lobby is between hallway cells room + 1 and room + 2
+        edges.push((room as u8 + 1, lobby(room), 1.0));
+        edges.push((lobby(room), room as u8 + 2, 1.0));
+        edges.push((lobby(room), room_cell(room, 0) as u8, 1.0));
+        for row in 1..depth {
+            edges.push((
+                room_cell(room, row - 1) as u8,
+                room_cell(room, row) as u8,
+                1.0,
+            ));
+        }
+    }
+
+    let node_count = cell_count + ROOM_COUNT;
+    let mut cell_graph = UnGraph::with_capacity(node_count, edges.len());
+    for cell_id in 0..node_count {
+        assert_eq!(cell_graph.add_node(1_u8), (cell_id as u8).into());
+    }
+    cell_graph.extend_with_edges(edges);
+    cell_graph
+}
+
+// ----------------------------------------------------------------------------
+// 3. A state is a mask of occupied cells, and 2 bits per cell for the type of amphipod in it.
+
+#[derive(Copy, Clone, Debug, Default, Ord, PartialOrd, Eq, PartialEq, Hash)]
+struct State {
+    occupied: u32,
+    kinds: u64,
+}
+
+impl State {
+    fn get(&self, cell: usize) -> Option<usize> {
+        (self.occupied & (1 << cell) != 0).then_some(((self.kinds >> (2 * cell)) & 3) as usize)
+    }
+
+    fn set(&mut self, cell: usize, kind: usize) {
+        self.occupied |= 1 << cell;
+        self.kinds = (self.kinds & !(3 << (2 * cell))) | ((kind as u64) << (2 * cell));
+    }
+
+    fn with_move(&self, src: usize, dst: usize) -> State {
+        let mut new_state = *self;
+        new_state.set(dst, self.get(src).unwrap());
+        // Clear the type too, so that equal states always have equal representations
+        new_state.occupied &= !(1 << src);
+        new_state.kinds &= !(3 << (2 * src));
+        new_state
+    }
+}
+
+#[derive(Debug, Error, PartialEq, Eq)]
+enum DiagramError {
+    #[error("the diagram has no side rooms")]
+    NoRooms,
+    #[error("side rooms deeper than {MAX_DEPTH} are not supported")]
+    TooDeep,
+    #[error("expected an amphipod or '.' at line {line}, column {column}")]
+    InvalidCell { line: usize, column: usize },
+    #[error("there are {count} amphipods of type {kind}, but {expected} are needed")]
+    WrongCount {
+        kind: char,
+        count: usize,
+        expected: usize,
+    },
+}
+
+fn exit_with_diagram_error(err: DiagramError) -> ! {
+    eprintln!("Invalid diagram: {err}");
+    exit(1);
+}
+
+/// Whether a line of the diagram has a side room cell, rather than the wall below the rooms
+fn is_side_room_line(line: &[u8]) -> bool {
+    line.get(3).is_some_and(|&symbol| symbol != b'#')
+}
+
+/// Parse the diagram into a burrow, whose depth is the number of lines of side rooms,
+/// and the initial state
+fn parse_diagram(diagram: &str) -> Result<(Burrow, State), DiagramError> {
+    let lines: Vec<&[u8]> = diagram.lines().map(|line| line.as_bytes()).collect();
+    let depth = lines
+        .iter()
+        .skip(2)
+        .take_while(|line| is_side_room_line(line))
+        .count();
+    if depth == 0 {
+        return Err(DiagramError::NoRooms);
+    }
+    if depth > MAX_DEPTH {
+        return Err(DiagramError::TooDeep);
+    }
+
+    let burrow = Burrow::new(depth);
+    let mut state = State::default();
+    let mut counts = [0; ROOM_COUNT];
+    for cell in 0..burrow.cell_count {
+        let (line, column) = diagram_position(cell);
+        match lines[line].get(column) {
+            Some(b'.') => {}
+            Some(&symbol @ b'A'..=b'D') => {
+                let kind = (symbol - b'A') as usize;
+                state.set(cell, kind);
+                counts[kind] += 1;
+            }
+            _ => return Err(DiagramError::InvalidCell { line, column }),
+        }
+    }
+    if let Some(kind) = (0..ROOM_COUNT).find(|&kind| counts[kind] != depth) {
+        return Err(DiagramError::WrongCount {
+            kind: KIND_SYMBOLS[kind],
+            count: counts[kind],
+            expected: depth,
+        });
+    }
+    Ok((burrow, state))
+}
+
+/// Insert the two extra lines of amphipods for part 2 after the first line of side rooms
+fn unfold(diagram: &str) -> Result<String, DiagramError> {
+    let mut lines: Vec<&str> = diagram.lines().collect();
+    if !lines
+        .get(2)
+        .is_some_and(|line| is_side_room_line(line.as_bytes()))
+    {
+        return Err(DiagramError::NoRooms);
+    }
+    lines.splice(3..3, UNFOLDED_LINES);
+    Ok(lines.join("\n"))
+}
+
+// ----------------------------------------------------------------------------
+// 4. The search, over states of a burrow with a particular depth of side rooms.
+
+struct Burrow {
+    depth: usize,
+    cell_count: usize,
+    distance_and_mask_lkp: DistanceAndMaskLookup,
+}
+
+struct Solution {
+    /// The states along the cheapest path, including the initial and target states
+    states: Vec<State>,
+    energy: Energy,
+}
+
+struct Move {
+    kind: usize,
+    src: usize,
+    dst: usize,
+    energy: Energy,
+}
+
+impl Display for Move {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        let describe = |cell: usize| match room_and_row(cell) {
+            Some((room, row)) => format!("room {} row {}", KIND_SYMBOLS[room], row + 1),
+            None => format!("hallway column {}", HALLWAY_COLUMNS[cell]),
+        };
+        write!(
+            f,
+            "{} moves from {} to {}, using {} energy",
+            KIND_SYMBOLS[self.kind],
+            describe(self.src),
+            describe(self.dst),
+            self.energy
+        )
+    }
+}
+
+impl Burrow {
+    fn new(depth: usize) -> Self {
+        Burrow {
+            depth,
+            cell_count: HALLWAY_CELL_COUNT + ROOM_COUNT * depth,
+            distance_and_mask_lkp: get_distances_and_mask_lookup(depth),
+        }
+    }
+
+    fn target_state(&self) -> State {
+        let mut state = State::default();
+        for room in 0..ROOM_COUNT {
+            for row in 0..self.depth {
+                state.set(room_cell(room, row), room);
+            }
+        }
+        state
+    }
+
+    fn solve(&self, initial_state: State) -> Option<Solution> {
+        let target_state = self.target_state();
+        astar(
+            &initial_state,
+            |state| self.get_successors(state),
+            |state| self.get_lower_bound_cost_to_reach_target_state(state),
+            |state| *state == target_state,
+        )
+        .map(|(states, energy)| Solution { states, energy })
+    }
+
+    /// An amphipod is settled if it is in its own side room,
+    /// and only amphipods of the same type are below it
+    fn is_settled(&self, state: &State, cell: usize, kind: usize) -> bool {
+        match room_and_row(cell) {
+            Some((room, row)) if room == kind => ((row + 1)..self.depth)
+                .all(|lower_row| state.get(room_cell(room, lower_row)) == Some(kind)),
+            _ => false,
+        }
+    }
+
+    /// The deepest empty cell in the amphipod's own side room,
+    /// if only amphipods of the same type are below it
+    fn get_vacant_home_cell(&self, state: &State, kind: usize) -> Option<usize> {
+        for row in (0..self.depth).rev() {
+            let cell = room_cell(kind, row);
+            match state.get(cell) {
+                None => return Some(cell),
+                Some(other_kind) if other_kind != kind => return None,
+                _ => {}
+            }
+        }
+        None
+    }
+
+    // Notes on the heuristic cost of each state for A* search:
+    //
+    //   i. Settled amphipods cost nothing.
+    //  ii. Other amphipods must at least move to the top cell of their side room.
+    // iii. An amphipod in its own side room, which isn't settled, must leave the side room
+    //      to let another amphipod out, then come back. This takes at least 4 steps more than its row.
+    //  iv. If k amphipods must still enter a side room, then they will also move 0 + 1 + ... + (k - 1)
+    //      steps further down the side room, after reaching its top cell.
+    fn get_lower_bound_cost_to_reach_target_state(&self, state: &State) -> Energy {
+        let mut energy: Energy = 0;
+        let mut entering_counts = [0; ROOM_COUNT];
+        for cell in 0..self.cell_count {
+            let Some(kind) = state.get(cell) else {
+                continue;
+            };
+            if self.is_settled(state, cell, kind) {
+                continue;
+            }
+            entering_counts[kind] += 1;
+            let distance = match room_and_row(cell) {
+                Some((room, row)) if room == kind => row as Energy + 4,
+                _ => self.distance_and_mask_lkp[cell][room_cell(kind, 0)].distance,
+            };
+            energy += distance * STEP_ENERGIES[kind];
+        }
+        for (kind, count) in entering_counts.iter().enumerate() {
+            if *count > 0 {
+                energy += (count * (count - 1) / 2) as Energy * STEP_ENERGIES[kind];
+            }
+        }
+        energy
+    }
+
+    fn get_successors(&self, state: &State) -> Vec<(State, Energy)> {
+        let mut moves = Vec::new();
+        for src in 0..self.cell_count {
+            let Some(kind) = state.get(src) else {
+                continue;
+            };
+            if self.is_settled(state, src, kind) {
+                continue;
+            }
+            if let Some(home) = self.get_vacant_home_cell(state, kind) {
+                if let Some(move_home) = self.try_move(state, src, home, kind) {
+                    // If we can move into the correct position directly, then do so as the only move,
+                    // since there is never a reason not to, and it reduces the search space
+                    return vec![move_home];
+                }
+            }
+            if src >= HALLWAY_CELL_COUNT {
+                moves.extend(
+                    (0..HALLWAY_CELL_COUNT).filter_map(|dst| self.try_move(state, src, dst, kind)),
+                );
+            }
+        }
+        moves
+    }
+
+    fn try_move(
+        &self,
+        state: &State,
+        src: usize,
+        dst: usize,
+        kind: usize,
+    ) -> Option<(State, Energy)> {
+        let lookup = &self.distance_and_mask_lkp[src][dst];
+        (src != dst && lookup.mask & state.occupied == 0).then(|| {
+            (
+                state.with_move(src, dst),
+                lookup.distance * STEP_ENERGIES[kind],
+            )
+        })
+    }
+
+    /// The moves between each pair of consecutive states
+    fn moves(&self, states: &[State]) -> Vec<Move> {
+        states
+            .windows(2)
+            .map(|pair| {
+                let src = (pair[0].occupied & !pair[1].occupied).trailing_zeros() as usize;
+                let dst = (pair[1].occupied & !pair[0].occupied).trailing_zeros() as usize;
+                let kind = pair[0].get(src).unwrap();
+                let energy = self.distance_and_mask_lkp[src][dst].distance * STEP_ENERGIES[kind];
+                Move {
+                    kind,
+                    src,
+                    dst,
+                    energy,
+                }
+            })
+            .collect()
+    }
+
+    fn render(&self, state: &State) -> String {
+        let mut lines: Vec<Vec<char>> = vec![
+            "#############".chars().collect(),
+            "#...........#".chars().collect(),
+            "###.#.#.#.###".chars().collect(),
+        ];
+        lines.extend((1..self.depth).map(|_| "  #.#.#.#.#".chars().collect()));
+        lines.push("  #########".chars().collect());
+        for cell in 0..self.cell_count {
+            if let Some(kind) = state.get(cell) {
+                let (line, column) = diagram_position(cell);
+                lines[line][column] = KIND_SYMBOLS[kind];
+            }
+        }
+        lines
+            .iter()
+            .map(|line| line.iter().collect::<String>())
+            .collect::<Vec<String>>()
+            .join("\n")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const EXAMPLE_DIAGRAM: &str = "#############
+#...........#
+###B#C#B#D###
+  #A#D#C#A#
+  #########";
+
+    fn solve(diagram: &str) -> Option<Energy> {
+        let (burrow, initial_state) = parse_diagram(diagram).unwrap();
+        burrow.solve(initial_state).map(|solution| solution.energy)
+    }
+
+    #[test]
+    fn test_example_part1() {
+        assert_eq!(solve(EXAMPLE_DIAGRAM), Some(12521));
+    }
+
+    #[test]
+    fn test_example_part2() {
+        assert_eq!(solve(&unfold(EXAMPLE_DIAGRAM).unwrap()), Some(44169));
+    }
+
+    #[test]
+    fn test_moves_add_up_to_the_energy() {
+        let (burrow, initial_state) = parse_diagram(EXAMPLE_DIAGRAM).unwrap();
+        let solution = burrow.solve(initial_state).unwrap();
+        let moves = burrow.moves(&solution.states);
+        assert_eq!(moves.iter().map(|mv| mv.energy).sum::<Energy>(), 12521);
+        let first_move_in_puzzle = Move {
+            kind: 1,
+            src: room_cell(2, 0),
+            dst: 2,
+            energy: 40,
+        };
+        assert_eq!(
+            first_move_in_puzzle.to_string(),
+            "B moves from room C row 1 to hallway column 4, using 40 energy"
+        );
+        assert_eq!(burrow.render(&initial_state), EXAMPLE_DIAGRAM);
+        assert_eq!(
+            burrow.render(solution.states.last().unwrap()),
+            "#############\n#...........#\n###A#B#C#D###\n  #A#B#C#D#\n  #########"
+        );
+    }
+
+    #[test]
+    fn test_invalid_diagrams() {
+        assert_eq!(
+            parse_diagram("#############\n#...........#\n#############").err(),
+            Some(DiagramError::NoRooms)
+        );
+        assert_eq!(
+            parse_diagram(&EXAMPLE_DIAGRAM.replace("#A#D", "#A#A")).err(),
+            Some(DiagramError::WrongCount {
+                kind: 'A',
+                count: 3,
+                expected: 2
+            })
+        );
+        assert_eq!(
+            parse_diagram(&EXAMPLE_DIAGRAM.replace("#A#D", "#A#E")).err(),
+            Some(DiagramError::InvalidCell { line: 3, column: 5 })
+        );
+        assert_eq!(
+            unfold("#############\n#...........#"),
+            Err(DiagramError::NoRooms)
+        );
+        assert_eq!(
+            unfold("#############\n#...........#\n#############"),
+            Err(DiagramError::NoRooms)
+        );
+    }
+}
@@ -0,0 +1,754 @@
+//! The Buoyancy Interchange Transmission System (BITS) from day 16.
+//!
+//! Packets are decoded from hexadecimal with a bit reader into a tree of `Packet`s,
+//! which can be evaluated, printed as S-expressions, and encoded back into hexadecimal.
+//! Expressions like `sum(1, max(2, 3))` can also be parsed into packets, to make transmissions.
+
+use std::fmt::{Display, Formatter};
+use std::str::FromStr;
+use thiserror::Error;
+
+const LITERAL_TYPE_ID: u8 = 4;
+const BITS_LENGTH_WIDTH: u32 = 15;
+const PACKETS_LENGTH_WIDTH: u32 = 11;
+
+#[derive(Debug, Error, PartialEq, Eq)]
+pub enum BitsError {
+    #[error("invalid hex digit {0:?}")]
+    InvalidHexDigit(char),
+    #[error("the transmission ended unexpectedly at bit {position}")]
+    UnexpectedEnd { position: usize },
+    #[error("the literal value starting at bit {position} does not fit in 64 bits")]
+    LiteralTooLarge { position: usize },
+    #[error("the sub-packets starting at bit {position} overran their length of {length} bits")]
+    SubPacketsOverran { position: usize, length: usize },
+    #[error("{0} sub-packets are too many to encode")]
+    TooManySubPackets(usize),
+    #[error("{0} bits of sub-packets are too many to encode")]
+    SubPacketsTooLong(usize),
+    #[error("invalid expression at character {position}: {message}")]
+    InvalidExpression { position: usize, message: String },
+    #[error("the {operator} of the packet's sub-packets does not fit in 64 bits")]
+    EvaluationOverflow { operator: &'static str },
+    #[error("a {operator} packet cannot have {count} sub-packets")]
+    InvalidSubPacketCount {
+        operator: &'static str,
+        count: usize,
+    },
+}
+
+pub fn hex_to_bytes(hex: &str) -> Result<Vec<u8>, BitsError> {
+    let nibbles = hex
+        .trim()
+        .chars()
+        .map(|ch| {
+            ch.to_digit(16)
+                .map(|nibble| nibble as u8)
+                .ok_or(BitsError::InvalidHexDigit(ch))
+        })
+        .collect::<Result<Vec<u8>, _>>()?;
+    Ok(nibbles
+        .chunks(2)
+        .map(|pair| pair[0] << 4 | pair.get(1).copied().unwrap_or(0))
+        .collect())
+}
+
+pub fn bytes_to_hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|byte| format!("{byte:02X}")).collect()
+}
+
+/// Reads values of up to 64 bits at a time, most significant bit first
+pub struct BitReader<'a> {
+    bytes: &'a [u8],
+    position: usize,
+}
+
+impl<'a> BitReader<'a> {
+    pub fn new(bytes: &'a [u8]) -> Self {
+        Self { bytes, position: 0 }
+    }
+
+    /// The number of bits read so far
+    pub fn position(&self) -> usize {
+        self.position
+    }
+
+    pub fn remaining(&self) -> usize {
+        self.bytes.len() * 8 - self.position
+    }
+
+    pub fn read(&mut self, bit_count: u32) -> Result<u64, BitsError> {
+        debug_assert!(bit_count <= u64::BITS);
+        if (bit_count as usize) > self.remaining() {
+            return Err(BitsError::UnexpectedEnd {
+                position: self.bytes.len() * 8,
+            });
+        }
+        let mut value = 0;
+        for _ in 0..bit_count {
+            let bit = self.bytes[self.position / 8] >> (7 - self.position % 8) & 1;
+            value = value << 1 | bit as u64;
+            self.position += 1;
+        }
+        Ok(value)
+    }
+
+    pub fn read_bit(&mut self) -> Result<bool, BitsError> {
+        Ok(self.read(1)? == 1)
+    }
+}
+
+/// Writes values of up to 64 bits at a time, most significant bit first
+#[derive(Debug, Default)]
+pub struct BitWriter {
+    bytes: Vec<u8>,
+    len: usize,
+}
+
+impl BitWriter {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// The number of bits written
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    pub fn write(&mut self, value: u64, bit_count: u32) {
+        for shift in (0..bit_count).rev() {
+            self.write_bit(value >> shift & 1 == 1);
+        }
+    }
+
+    pub fn write_bit(&mut self, bit: bool) {
+        if self.len.is_multiple_of(8) {
+            self.bytes.push(0);
+        }
+        if bit {
+            *self.bytes.last_mut().unwrap() |= 1 << (7 - self.len % 8);
+        }
+        self.len += 1;
+    }
+
+    pub fn append(&mut self, other: &BitWriter) {
+        let mut reader = BitReader::new(&other.bytes);
+        for _ in 0..other.len {
+            self.write_bit(reader.read_bit().unwrap());
+        }
+    }
+
+    /// The bytes written, with the last byte padded with zeros
+    pub fn into_bytes(self) -> Vec<u8> {
+        self.bytes
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Operator {
+    Sum,
+    Product,
+    Minimum,
+    Maximum,
+    GreaterThan,
+    LessThan,
+    EqualTo,
+}
+
+impl Operator {
+    pub const ALL: [Operator; 7] = [
+        Operator::Sum,
+        Operator::Product,
+        Operator::Minimum,
+        Operator::Maximum,
+        Operator::GreaterThan,
+        Operator::LessThan,
+        Operator::EqualTo,
+    ];
+
+    pub fn type_id(&self) -> u8 {
+        match self {
+            Operator::Sum => 0,
+            Operator::Product => 1,
+            Operator::Minimum => 2,
+            Operator::Maximum => 3,
+            Operator::GreaterThan => 5,
+            Operator::LessThan => 6,
+            Operator::EqualTo => 7,
+        }
+    }
+
+    pub fn from_type_id(type_id: u8) -> Option<Self> {
+        Self::ALL
+            .into_iter()
+            .find(|operator| operator.type_id() == type_id)
+    }
+
+    /// The name used in expressions and S-expressions
+    pub fn name(&self) -> &'static str {
+        match self {
+            Operator::Sum => "sum",
+            Operator::Product => "product",
+            Operator::Minimum => "min",
+            Operator::Maximum => "max",
+            Operator::GreaterThan => "gt",
+            Operator::LessThan => "lt",
+            Operator::EqualTo => "eq",
+        }
+    }
+
+    pub fn from_name(name: &str) -> Option<Self> {
+        Self::ALL
+            .into_iter()
+            .find(|operator| operator.name() == name)
+    }
+
+    pub fn is_comparison(&self) -> bool {
+        matches!(
+            self,
+            Operator::GreaterThan | Operator::LessThan | Operator::EqualTo
+        )
+    }
+}
+
+/// How an operator packet gives the size of its sub-packets
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LengthType {
+    /// The total number of bits in the sub-packets (length type ID 0)
+    Bits,
+    /// The number of sub-packets (length type ID 1)
+    Packets,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Body {
+    Literal(u64),
+    Operator {
+        operator: Operator,
+        length_type: LengthType,
+        sub_packets: Vec<Packet>,
+    },
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Packet {
+    pub version: u8,
+    pub body: Body,
+}
+
+impl Packet {
+    pub fn literal(version: u8, value: u64) -> Self {
+        Self {
+            version,
+            body: Body::Literal(value),
+        }
+    }
+
+    pub fn operator(version: u8, operator: Operator, sub_packets: Vec<Packet>) -> Self {
+        Self {
+            version,
+            body: Body::Operator {
+                operator,
+                length_type: LengthType::Packets,
+                sub_packets,
+            },
+        }
+    }
+
+    /// Decode the outermost packet of a hexadecimal transmission, ignoring any padding after it
+    pub fn decode(hex: &str) -> Result<Self, BitsError> {
+        let bytes = hex_to_bytes(hex)?;
+        Self::read(&mut BitReader::new(&bytes))
+    }
+
+    /// Encode the packet in hexadecimal, padded with zeros to a whole number of bytes
+    pub fn encode(&self) -> Result<String, BitsError> {
+        let mut writer = BitWriter::new();
+        self.write(&mut writer)?;
+        Ok(bytes_to_hex(&writer.into_bytes()))
+    }
+
+    pub fn read(reader: &mut BitReader) -> Result<Self, BitsError> {
+        let version = reader.read(3)? as u8;
+        let type_id = reader.read(3)? as u8;
+        let Some(operator) = Operator::from_type_id(type_id) else {
+            return Ok(Self::literal(version, Self::read_literal_value(reader)?));
+        };
+
+        let mut sub_packets = Vec::new();
+        let length_type = if reader.read_bit()? {
+            let packet_count = reader.read(PACKETS_LENGTH_WIDTH)?;
+            for _ in 0..packet_count {
+                sub_packets.push(Self::read(reader)?);
+            }
+            LengthType::Packets
+        } else {
+            let length = reader.read(BITS_LENGTH_WIDTH)? as usize;
+            let start = reader.position();
+            while reader.position() < start + length {
+                sub_packets.push(Self::read(reader)?);
+            }
+            if reader.position() != start + length {
+                return Err(BitsError::SubPacketsOverran {
+                    position: start,
+                    length,
+                });
+            }
+            LengthType::Bits
+        };
+        Ok(Self {
+            version,
+            body: Body::Operator {
+                operator,
+                length_type,
+                sub_packets,
+            },
+        })
+    }
+
+    fn read_literal_value(reader: &mut BitReader) -> Result<u64, BitsError> {
+        let start = reader.position();
+        let mut value: u64 = 0;
+        loop {
+            let is_last_group = !reader.read_bit()?;
+            if value.leading_zeros() < 4 {
+                return Err(BitsError::LiteralTooLarge { position: start });
+            }
+            value = value << 4 | reader.read(4)?;
+            if is_last_group {
+                return Ok(value);
+            }
+        }
+    }
+
+    pub fn write(&self, writer: &mut BitWriter) -> Result<(), BitsError> {
+        writer.write(self.version as u64, 3);
+        match &self.body {
+            Body::Literal(value) => {
+                writer.write(LITERAL_TYPE_ID as u64, 3);
+                let group_count = (u64::BITS - value.leading_zeros()).div_ceil(4).max(1);
+                for group in (0..group_count).rev() {
+                    writer.write_bit(group > 0);
+                    writer.write(value >> (4 * group) & 0xF, 4);
+                }
+            }
+            Body::Operator {
+                operator,
+                length_type,
+                sub_packets,
+            } => {
+                writer.write(operator.type_id() as u64, 3);
+                let mut sub_packet_writer = BitWriter::new();
+                for sub_packet in sub_packets {
+                    sub_packet.write(&mut sub_packet_writer)?;
+                }
+                match length_type {
+                    LengthType::Bits => {
+                        let length = sub_packet_writer.len();
+                        if length >= 1 << BITS_LENGTH_WIDTH {
+                            return Err(BitsError::SubPacketsTooLong(length));
+                        }
+                        writer.write_bit(false);
+                        writer.write(length as u64, BITS_LENGTH_WIDTH);
+                    }
+                    LengthType::Packets => {
+                        let count = sub_packets.len();
+                        if count >= 1 << PACKETS_LENGTH_WIDTH {
+                            return Err(BitsError::TooManySubPackets(count));
+                        }
+                        writer.write_bit(true);
+                        writer.write(count as u64, PACKETS_LENGTH_WIDTH);
+                    }
+                }
+                writer.append(&sub_packet_writer);
+            }
+        }
+        Ok(())
+    }
+
+    pub fn version_sum(&self) -> u64 {
+        let sub_packet_sum = match &self.body {
+            Body::Literal(_) => 0,
+            Body::Operator { sub_packets, .. } => sub_packets.iter().map(Packet::version_sum).sum(),
+        };
+        self.version as u64 + sub_packet_sum
+    }
+
+    /// The sum of no sub-packets is 0, and their product is 1.
+    ///
+    /// # Errors
+    ///
+    /// If a sum or product overflows 64 bits (instead of panicking or wrapping around),
+    /// a minimum or maximum has no sub-packets, or a comparison doesn't have exactly two
+    pub fn evaluate(&self) -> Result<u64, BitsError> {
+        let (operator, sub_packets) = match &self.body {
+            Body::Literal(value) => return Ok(*value),
+            Body::Operator {
+                operator,
+                sub_packets,
+                ..
+            } => (operator, sub_packets),
+        };
+        let values = sub_packets
+            .iter()
+            .map(Packet::evaluate)
+            .collect::<Result<Vec<u64>, _>>()?;
+        let invalid_count = || BitsError::InvalidSubPacketCount {
+            operator: operator.name(),
+            count: values.len(),
+        };
+        if operator.is_comparison() {
+            let &[left, right] = values.as_slice() else {
+                return Err(invalid_count());
+            };
+            let result = match operator {
+                Operator::GreaterThan => left > right,
+                Operator::LessThan => left < right,
+                _ => left == right,
+            };
+            return Ok(result as u64);
+        }
+        let overflow = || BitsError::EvaluationOverflow {
+            operator: operator.name(),
+        };
+        match operator {
+            Operator::Sum => values
+                .iter()
+                .try_fold(0_u64, |total, &value| total.checked_add(value))
+                .ok_or_else(overflow),
+            Operator::Product => values
+                .iter()
+                .try_fold(1_u64, |total, &value| total.checked_mul(value))
+                .ok_or_else(overflow),
+            Operator::Minimum => values.iter().copied().min().ok_or_else(invalid_count),
+            _ => values.iter().copied().max().ok_or_else(invalid_count),
+        }
+    }
+
+    fn write_s_expression(&self, f: &mut Formatter<'_>, indent: Option<usize>) -> std::fmt::Result {
+        match &self.body {
+            Body::Literal(value) => write!(f, "{value}"),
+            Body::Operator {
+                operator,
+                sub_packets,
+                ..
+            } => {
+                write!(f, "({}", operator.name())?;
+                let has_only_literals = sub_packets
+                    .iter()
+                    .all(|sub_packet| matches!(sub_packet.body, Body::Literal(_)));
+                let indent = indent.filter(|_| !has_only_literals);
+                for sub_packet in sub_packets {
+                    match indent {
+                        Some(depth) => write!(f, "\n{}", "  ".repeat(depth + 1))?,
+                        None => write!(f, " ")?,
+                    }
+                    sub_packet.write_s_expression(f, indent.map(|depth| depth + 1))?;
+                }
+                write!(f, ")")
+            }
+        }
+    }
+}
+
+/// Shows the packet as an S-expression, such as `(sum 1 (max 2 3))`.
+/// The alternate format (`{:#}`) puts each sub-packet on its own indented line,
+/// unless they are all literals.
+impl Display for Packet {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        let indent = f.alternate().then_some(0);
+        self.write_s_expression(f, indent)
+    }
+}
+
+/// Parses an expression such as `sum(1, max(2, 3))` into packets with version 0,
+/// which give their number of sub-packets
+impl FromStr for Packet {
+    type Err = BitsError;
+
+    fn from_str(expression: &str) -> Result<Self, Self::Err> {
+        let mut parser = ExpressionParser {
+            text: expression,
+            position: 0,
+        };
+        let packet = parser.parse_packet()?;
+        parser.skip_whitespace();
+        if parser.position < expression.len() {
+            return Err(parser.error("expected the end of the expression"));
+        }
+        Ok(packet)
+    }
+}
+
+struct ExpressionParser<'a> {
+    text: &'a str,
+    position: usize,
+}
+
+impl ExpressionParser<'_> {
+    fn parse_packet(&mut self) -> Result<Packet, BitsError> {
+        self.skip_whitespace();
+        let token = self.take_while(|ch| ch.is_ascii_alphanumeric()).to_string();
+        if token.is_empty() {
+            return Err(self.error("expected a number or an operator"));
+        }
+        if let Ok(value) = token.parse::<u64>() {
+            return Ok(Packet::literal(0, value));
+        }
+        let operator = Operator::from_name(&token)
+            .ok_or_else(|| self.error(&format!("unknown operator {token:?}")))?;
+        self.expect('(')?;
+        let mut sub_packets = vec![self.parse_packet()?];
+        while self.next_char_if(',') {
+            sub_packets.push(self.parse_packet()?);
+        }
+        self.expect(')')?;
+        if operator.is_comparison() && sub_packets.len() != 2 {
+            return Err(self.error(&format!("{} needs two arguments", operator.name())));
+        }
+        Ok(Packet::operator(0, operator, sub_packets))
+    }
+
+    fn skip_whitespace(&mut self) {
+        self.take_while(char::is_whitespace);
+    }
+
+    fn take_while(&mut self, predicate: impl Fn(char) -> bool) -> &str {
+        let rest = &self.text[self.position..];
+        let len = rest.find(|ch| !predicate(ch)).unwrap_or(rest.len());
+        self.position += len;
+        &rest[..len]
+    }
+
+    fn next_char_if(&mut self, expected: char) -> bool {
+        self.skip_whitespace();
+        if self.text[self.position..].starts_with(expected) {
+            self.position += expected.len_utf8();
+            true
+        } else {
+            false
+        }
+    }
+
+    fn expect(&mut self, expected: char) -> Result<(), BitsError> {
+        if self.next_char_if(expected) {
+            Ok(())
+        } else {
+            Err(self.error(&format!("expected {expected:?}")))
+        }
+    }
+
+    fn error(&self, message: &str) -> BitsError {
+        BitsError::InvalidExpression {
+            position: self.position,
+            message: message.to_string(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_literal() {
+        let packet = Packet::decode("D2FE28").unwrap();
+        assert_eq!(packet, Packet::literal(6, 2021));
+        assert_eq!(packet.encode().unwrap(), "D2FE28");
+    }
+
+    #[test]
+    fn test_operators_with_each_length_type() {
+        let packet = Packet::decode("38006F45291200").unwrap();
+        let Body::Operator {
+            operator,
+            length_type,
+            sub_packets,
+        } = &packet.body
+        else {
+            panic!("Expected an operator");
+        };
+        assert_eq!(*operator, Operator::LessThan);
+        assert_eq!(*length_type, LengthType::Bits);
+        assert_eq!(
+            sub_packets,
+            &[Packet::literal(6, 10), Packet::literal(2, 20)]
+        );
+        assert_eq!(packet.encode().unwrap(), "38006F45291200");
+
+        let packet = Packet::decode("EE00D40C823060").unwrap();
+        assert_eq!(packet.to_string(), "(max 1 2 3)");
+        assert_eq!(packet.encode().unwrap(), "EE00D40C823060");
+    }
+
+    #[test]
+    fn test_version_sums() {
+        for (hex, version_sum) in [
+            ("8A004A801A8002F478", 16),
+            ("620080001611562C8802118E34", 12),
+            ("C0015000016115A2E0802F182340", 23),
+            ("A0016C880162017C3686B18A3D4780", 31),
+        ] {
+            assert_eq!(Packet::decode(hex).unwrap().version_sum(), version_sum);
+        }
+    }
+
+    #[test]
+    fn test_evaluate() {
+        for (hex, value) in [
+            ("C200B40A82", 3),
+            ("04005AC33890", 54),
+            ("880086C3E88112", 7),
+            ("CE00C43D881120", 9),
+            ("D8005AC2A8F0", 1),
+            ("F600BC2D8F", 0),
+            ("9C005AC2F8F0", 0),
+            ("9C0141080250320F1802104A08", 1),
+        ] {
+            assert_eq!(Packet::decode(hex).unwrap().evaluate(), Ok(value));
+        }
+    }
+
+    #[test]
+    fn test_pretty_print() {
+        let packet = Packet::decode("9C0141080250320F1802104A08").unwrap();
+        assert_eq!(packet.to_string(), "(eq (sum 1 3) (product 2 2))");
+        assert_eq!(format!("{packet:#}"), "(eq\n  (sum 1 3)\n  (product 2 2))");
+    }
+
+    #[test]
+    fn test_encode_expression() {
+        let packet: Packet = "sum(1, max(2,3))".parse().unwrap();
+        let hex = packet.encode().unwrap();
+        let decoded = Packet::decode(&hex).unwrap();
+        assert_eq!(decoded, packet);
+        assert_eq!(decoded.evaluate(), Ok(4));
+        assert_eq!(decoded.to_string(), "(sum 1 (max 2 3))");
+    }
+
+    #[test]
+    fn test_evaluation_overflow() {
+        let product: Packet = "product(4294967296, 4294967296)".parse().unwrap();
+        assert_eq!(
+            product.evaluate(),
+            Err(BitsError::EvaluationOverflow {
+                operator: "product"
+            })
+        );
+        let sum: Packet = format!("max(1, sum({}, 1))", u64::MAX).parse().unwrap();
+        assert_eq!(
+            sum.evaluate(),
+            Err(BitsError::EvaluationOverflow { operator: "sum" })
+        );
+        let product: Packet = "product(4294967295, 4294967297)".parse().unwrap();
+        assert_eq!(product.evaluate(), Ok(u64::MAX));
+    }
+
+    #[test]
+    fn test_evaluation_of_invalid_sub_packet_counts() {
+        // Transmissions can hold operators with any number of sub-packets
+        let empty_min = Packet::operator(0, Operator::Minimum, Vec::new());
+        let decoded = Packet::decode(&empty_min.encode().unwrap()).unwrap();
+        assert_eq!(decoded, empty_min);
+        assert_eq!(
+            decoded.evaluate(),
+            Err(BitsError::InvalidSubPacketCount {
+                operator: "min",
+                count: 0
+            })
+        );
+        let empty_max = Packet::operator(0, Operator::Maximum, Vec::new());
+        assert_eq!(
+            Packet::operator(0, Operator::Sum, vec![empty_max]).evaluate(),
+            Err(BitsError::InvalidSubPacketCount {
+                operator: "max",
+                count: 0
+            })
+        );
+        let literals = (1..=3).map(|value| Packet::literal(0, value)).collect();
+        assert_eq!(
+            Packet::operator(0, Operator::LessThan, literals).evaluate(),
+            Err(BitsError::InvalidSubPacketCount {
+                operator: "lt",
+                count: 3
+            })
+        );
+        assert_eq!(
+            Packet::operator(0, Operator::Product, Vec::new()).evaluate(),
+            Ok(1)
+        );
+    }
+
+    #[test]
+    fn test_errors() {
+        assert_eq!(Packet::decode("D2FG"), Err(BitsError::InvalidHexDigit('G')));
+        assert_eq!(
+            Packet::decode("D2FE"),
+            Err(BitsError::UnexpectedEnd { position: 16 })
+        );
+        assert_eq!(
+            "sum(1, 2".parse::<Packet>(),
+            Err(BitsError::InvalidExpression {
+                position: 8,
+                message: "expected ')'".to_string()
+            })
+        );
+        assert!(matches!(
+            "gt(1, 2, 3)".parse::<Packet>(),
+            Err(BitsError::InvalidExpression { .. })
+        ));
+    }
+
+    mod prop_tests {
+        use super::super::*;
+        use proptest::prelude::*;
+        use proptest::{collection, sample};
+
+        fn packets() -> impl Strategy<Value = Packet> {
+            let literals = (0_u8..8, any::<u64>())
+                .prop_map(|(version, value)| Packet::literal(version, value));
+            literals.prop_recursive(4, 40, 5, |inner| {
+                let length_types = sample::select(vec![LengthType::Bits, LengthType::Packets]);
+                (
+                    0_u8..8,
+                    sample::select(Operator::ALL.to_vec()),
+                    length_types,
+                    collection::vec(inner, 1..5),
+                )
+                    .prop_map(
+                        |(version, operator, length_type, mut sub_packets)| {
+                            if operator.is_comparison() {
+                                sub_packets.resize(2, Packet::literal(version, 0));
+                            }
+                            Packet {
+                                version,
+                                body: Body::Operator {
+                                    operator,
+                                    length_type,
+                                    sub_packets,
+                                },
+                            }
+                        },
+                    )
+            })
+        }
+
+        proptest! {
+            #[test]
+            fn encoding_then_decoding_gives_the_same_packet(packet in packets()) {
+                let hex = packet.encode().unwrap();
+                prop_assert_eq!(Packet::decode(&hex).unwrap(), packet);
+            }
+
+            #[test]
+            fn decoding_then_encoding_gives_the_same_hex(packet in packets()) {
+                let hex = packet.encode().unwrap();
+                prop_assert_eq!(Packet::decode(&hex).unwrap().encode().unwrap(), hex);
+            }
+        }
+    }
+}
@@ -0,0 +1,389 @@
+//! The dice game from day 21, with the board size, target score, dice and rolls per turn as parameters.
+//!
+//! Part 1 plays with a deterministic die, which rolls 1, 2, 3, ... and wraps around after its last face.
+//! Part 2 plays with the Dirac die, which splits the universe into a copy for each face.
+//! The number of universes each player wins in is found with dynamic programming
+//! over the positions and scores of both players.
+
+use thiserror::Error;
+
+#[derive(Debug, Error, PartialEq, Eq)]
+pub enum DiceError {
+    #[error("the board size, target score, dice faces and rolls per turn must all be positive")]
+    InvalidRules,
+    #[error("starting position {position} is not on a board of size {board_size}")]
+    InvalidStartPosition { position: u32, board_size: u32 },
+    #[error("a board of size {board_size} with target score {target_score} needs too large a table of win counts")]
+    TableTooLarge { board_size: u32, target_score: u32 },
+    #[error("the {quantity} is too large to count")]
+    Overflow { quantity: &'static str },
+}
+
+/// The most entries the table of win counts for the Dirac die may have (512 MiB of u128 pairs)
+const MAX_TABLE_LEN: usize = 1 << 24;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct GameRules {
+    board_size: u32,
+    target_score: u32,
+    die_faces: u32,
+    rolls_per_turn: u32,
+}
+
+/// The result of a game with the deterministic die
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct DeterministicOutcome {
+    /// 0 for the first player, 1 for the second
+    pub winner: usize,
+    pub losing_score: u32,
+    pub roll_count: u32,
+}
+
+impl DeterministicOutcome {
+    pub fn losing_score_times_rolls(&self) -> u64 {
+        self.losing_score as u64 * self.roll_count as u64
+    }
+}
+
+/// A player's position (from 1 to the board size) and score
+type PlayerState = (u32, u32);
+
+impl GameRules {
+    pub const PRACTICE: GameRules = GameRules {
+        board_size: 10,
+        target_score: 1000,
+        die_faces: 100,
+        rolls_per_turn: 3,
+    };
+
+    pub const DIRAC: GameRules = GameRules {
+        board_size: 10,
+        target_score: 21,
+        die_faces: 3,
+        rolls_per_turn: 3,
+    };
+
+    pub fn new(
+        board_size: u32,
+        target_score: u32,
+        die_faces: u32,
+        rolls_per_turn: u32,
+    ) -> Result<Self, DiceError> {
+        if [board_size, target_score, die_faces, rolls_per_turn].contains(&0) {
+            return Err(DiceError::InvalidRules);
+        }
+        Ok(GameRules {
+            board_size,
+            target_score,
+            die_faces,
+            rolls_per_turn,
+        })
+    }
+
+    fn check_start_positions(&self, start_positions: [u32; 2]) -> Result<(), DiceError> {
+        match start_positions
+            .into_iter()
+            .find(|position| !(1..=self.board_size).contains(position))
+        {
+            Some(position) => Err(DiceError::InvalidStartPosition {
+                position,
+                board_size: self.board_size,
+            }),
+            None => Ok(()),
+        }
+    }
+
+    fn advance(&self, position: u32, steps: u32) -> u32 {
+        let board_size = self.board_size as u64;
+        ((position as u64 - 1 + steps as u64 % board_size) % board_size + 1) as u32
+    }
+
+    /// # Errors
+    ///
+    /// Returns [`DiceError::Overflow`] if the number of rolls, the steps moved in a turn
+    /// or a score does not fit in a u32.
+    pub fn play_deterministic(
+        &self,
+        start_positions: [u32; 2],
+    ) -> Result<DeterministicOutcome, DiceError> {
+        self.check_start_positions(start_positions)?;
+        let overflow = |quantity| move || DiceError::Overflow { quantity };
+        let mut players: [PlayerState; 2] = start_positions.map(|position| (position, 0));
+        let mut roll_count: u32 = 0;
+        for player in (0..2).cycle() {
+            let mut steps: u32 = 0;
+            for _ in 0..self.rolls_per_turn {
+                let face = roll_count % self.die_faces + 1;
+                roll_count = roll_count
+                    .checked_add(1)
+                    .ok_or_else(overflow("number of rolls"))?;
+                steps = steps
+                    .checked_add(face)
+                    .ok_or_else(overflow("number of steps in a turn"))?;
+            }
+            let (position, score) = &mut players[player];
+            *position = self.advance(*position, steps);
+            *score = score.checked_add(*position).ok_or_else(overflow("score"))?;
+            if *score >= self.target_score {
+                return Ok(DeterministicOutcome {
+                    winner: player,
+                    losing_score: players[1 - player].1,
+                    roll_count,
+                });
+            }
+        }
+        unreachable!("the players take turns forever")
+    }
+
+    /// The number of ways of rolling each total in a turn with the Dirac die
+    ///
+    /// # Errors
+    ///
+    /// Returns [`DiceError::Overflow`] if a total does not fit in a u32,
+    /// or the number of ways of rolling it does not fit in a u128.
+    pub fn roll_total_counts(&self) -> Result<Vec<(u32, u128)>, DiceError> {
+        let mut counts: Vec<u128> = vec![1];
+        for _ in 0..self.rolls_per_turn {
+            let mut next_counts = vec![0_u128; counts.len() + self.die_faces as usize];
+            for (total, count) in counts.iter().enumerate() {
+                for face in 1..=self.die_faces as usize {
+                    next_counts[total + face] = next_counts[total + face]
+                        .checked_add(*count)
+                        .ok_or(DiceError::Overflow {
+                            quantity: "number of ways of rolling a total",
+                        })?;
+                }
+            }
+            counts = next_counts;
+        }
+        counts
+            .into_iter()
+            .enumerate()
+            .filter(|(_, count)| *count > 0)
+            .map(|(total, count)| {
+                let total = u32::try_from(total).map_err(|_| DiceError::Overflow {
+                    quantity: "total of a turn's rolls",
+                })?;
+                Ok((total, count))
+            })
+            .collect()
+    }
+
+    /// The number of universes in which each player wins with the Dirac die
+    ///
+    /// # Errors
+    ///
+    /// Returns [`DiceError::TableTooLarge`] if the board size and target score need a table
+    /// of more than [`MAX_TABLE_LEN`] states,
+    /// or [`DiceError::Overflow`] if a number of universes does not fit in a u128.
+    pub fn count_wins(&self, start_positions: [u32; 2]) -> Result<[u128; 2], DiceError> {
+        self.check_start_positions(start_positions)?;
+        let table = self.win_count_table()?;
+        Ok(table[self.table_index((start_positions[0], 0), (start_positions[1], 0))])
+    }
+
+    /// The wins for the player about to move, and for the other player, from every state
+    /// where neither player has reached the target score yet.
+    ///
+    /// Every move increases the total of the scores, so the table is filled in from the highest totals down,
+    /// and each state only needs the states which its moves lead to, which have already been filled in.
+    fn win_count_table(&self) -> Result<Vec<[u128; 2]>, DiceError> {
+        let table_len = self
+            .player_state_count()
+            .checked_mul(self.player_state_count())
+            .filter(|&len| len <= MAX_TABLE_LEN)
+            .ok_or(DiceError::TableTooLarge {
+                board_size: self.board_size,
+                target_score: self.target_score,
+            })?;
+        let roll_total_counts = self.roll_total_counts()?;
+        let overflow = || DiceError::Overflow {
+            quantity: "number of universes won",
+        };
+        let positions = 1..=self.board_size;
+        let mut table = vec![[0, 0]; table_len];
+        for score_total in (0..=2 * (self.target_score - 1)).rev() {
+            let min_mover_score = score_total.saturating_sub(self.target_score - 1);
+            let max_mover_score = score_total.min(self.target_score - 1);
+            for mover_score in min_mover_score..=max_mover_score {
+                let other_score = score_total - mover_score;
+                for mover_position in positions.clone() {
+                    for other_position in positions.clone() {
+                        let mover = (mover_position, mover_score);
+                        let other = (other_position, other_score);
+                        let mut wins: [u128; 2] = [0, 0];
+                        for &(total, count) in &roll_total_counts {
+                            let position = self.advance(mover_position, total);
+                            let score = mover_score + position;
+                            let [mover_wins, other_wins] = if score >= self.target_score {
+                                [1, 0]
+                            } else {
+                                let [other_wins, mover_wins] =
+                                    table[self.table_index(other, (position, score))];
+                                [mover_wins, other_wins]
+                            };
+                            for (total_wins, wins_after_roll) in
+                                wins.iter_mut().zip([mover_wins, other_wins])
+                            {
+                                *total_wins = count
+                                    .checked_mul(wins_after_roll)
+                                    .and_then(|new_wins| total_wins.checked_add(new_wins))
+                                    .ok_or_else(overflow)?;
+                            }
+                        }
+                        table[self.table_index(mover, other)] = wins;
+                    }
+                }
+            }
+        }
+        Ok(table)
+    }
+
+    /// The number of positions and scores below the target which a player can have
+    fn player_state_count(&self) -> usize {
+        self.board_size as usize * self.target_score as usize
+    }
+
+    fn table_index(&self, mover: PlayerState, other: PlayerState) -> usize {
+        let player_index = |(position, score): PlayerState| {
+            (position as usize - 1) * self.target_score as usize + score as usize
+        };
+        player_index(mover) * self.player_state_count() + player_index(other)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const EXAMPLE_START_POSITIONS: [u32; 2] = [4, 8];
+
+    #[test]
+    fn test_example_part1() {
+        let outcome = GameRules::PRACTICE
+            .play_deterministic(EXAMPLE_START_POSITIONS)
+            .unwrap();
+        assert_eq!(
+            outcome,
+            DeterministicOutcome {
+                winner: 0,
+                losing_score: 745,
+                roll_count: 993
+            }
+        );
+        assert_eq!(outcome.losing_score_times_rolls(), 739785);
+    }
+
+    #[test]
+    fn test_example_part2() {
+        assert_eq!(
+            GameRules::DIRAC.count_wins(EXAMPLE_START_POSITIONS),
+            Ok([444356092776315, 341960390180808])
+        );
+    }
+
+    #[test]
+    fn test_roll_total_counts() {
+        assert_eq!(
+            GameRules::DIRAC.roll_total_counts().unwrap(),
+            [(3, 1), (4, 3), (5, 6), (6, 7), (7, 6), (8, 3), (9, 1)]
+        );
+    }
+
+    /// Play out every universe one roll at a time
+    fn count_wins_by_brute_force(
+        rules: &GameRules,
+        players: [PlayerState; 2],
+        rolls: u32,
+    ) -> [u128; 2] {
+        (1..=rules.die_faces)
+            .map(|face| {
+                let (position, score) = players[0];
+                let position = rules.advance(position, face);
+                if rolls + 1 < rules.rolls_per_turn {
+                    return count_wins_by_brute_force(
+                        rules,
+                        [(position, score), players[1]],
+                        rolls + 1,
+                    );
+                }
+                let score = score + position;
+                if score >= rules.target_score {
+                    [1, 0]
+                } else {
+                    let [other_wins, mover_wins] =
+                        count_wins_by_brute_force(rules, [players[1], (position, score)], 0);
+                    [mover_wins, other_wins]
+                }
+            })
+            .fold([0, 0], |[a, b], [c, d]| [a + c, b + d])
+    }
+
+    #[test]
+    fn test_other_rules_match_brute_force() {
+        for (board_size, target_score, die_faces, rolls_per_turn) in
+            [(7, 9, 2, 2), (5, 12, 4, 1), (12, 10, 3, 3)]
+        {
+            let rules =
+                GameRules::new(board_size, target_score, die_faces, rolls_per_turn).unwrap();
+            let start_positions = [1, board_size];
+            assert_eq!(
+                rules.count_wins(start_positions).unwrap(),
+                count_wins_by_brute_force(&rules, start_positions.map(|position| (position, 0)), 0)
+            );
+        }
+    }
+
+    #[test]
+    fn test_invalid_rules() {
+        assert_eq!(GameRules::new(10, 21, 0, 3), Err(DiceError::InvalidRules));
+        assert_eq!(
+            GameRules::DIRAC.count_wins([0, 5]),
+            Err(DiceError::InvalidStartPosition {
+                position: 0,
+                board_size: 10
+            })
+        );
+    }
+
+    #[test]
+    fn test_table_too_large() {
+        let rules = GameRules::new(10, 10000, 3, 3).unwrap();
+        assert_eq!(
+            rules.count_wins([1, 2]),
+            Err(DiceError::TableTooLarge {
+                board_size: 10,
+                target_score: 10000
+            })
+        );
+        let rules = GameRules::new(u32::MAX, u32::MAX, 3, 3).unwrap();
+        assert!(matches!(
+            rules.count_wins([1, 2]),
+            Err(DiceError::TableTooLarge { .. })
+        ));
+    }
+
+    #[test]
+    fn test_overflow() {
+        let universes_won = Err(DiceError::Overflow {
+            quantity: "number of universes won",
+        });
+        assert_eq!(
+            GameRules::new(10, 200, 3, 3).unwrap().count_wins([4, 8]),
+            universes_won
+        );
+        assert_eq!(
+            GameRules::new(3, 2, 2, 200).unwrap().roll_total_counts(),
+            Err(DiceError::Overflow {
+                quantity: "number of ways of rolling a total"
+            })
+        );
+        // Each player moves one step to the end of the huge board, and scores almost u32::MAX
+        assert_eq!(
+            GameRules::new(u32::MAX, u32::MAX, 1, 1)
+                .unwrap()
+                .play_deterministic([u32::MAX - 2, u32::MAX - 2]),
+            Err(DiceError::Overflow { quantity: "score" })
+        );
+    }
+}
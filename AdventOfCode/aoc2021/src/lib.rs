@@ -0,0 +1,5 @@
+pub mod bits;
+pub mod snailfish;
+pub mod scanners;
+pub mod dice;
+pub mod polymer;
@@ -0,0 +1,425 @@
+//! Polymer insertion from day 14, by raising a matrix of pair transitions to the power of the number of steps.
+//!
+//! Only the pairs of elements which appear in the template or the rules are tracked.
+//! The matrix is raised to a power by repeated squaring, so the number of matrix products
+//! grows with the number of binary digits in the number of steps, not the number of steps.
+//! The counts double on most steps, so exact counts need a `BigUint` for more than about 120 steps,
+//! while `ModularCount` gives the counts modulo a constant for any number of steps, such as 10^12.
+
+use num::{BigUint, One, Zero};
+use std::collections::BTreeMap;
+use std::str::FromStr;
+use thiserror::Error;
+
+#[derive(Debug, Error, PartialEq, Eq)]
+pub enum PolymerError {
+    #[error("the polymer template is missing")]
+    MissingTemplate,
+    #[error("invalid pair insertion rule {0:?}")]
+    InvalidRule(String),
+    #[error("more than one insertion rule for the pair {0:?}")]
+    DuplicateRule(String),
+    #[error("the counts overflowed")]
+    Overflow,
+}
+
+/// A type of count, which may overflow
+pub trait Count: Clone {
+    fn zero() -> Self;
+    fn one() -> Self;
+    fn is_zero(&self) -> bool;
+    fn checked_add(&self, other: &Self) -> Option<Self>;
+    fn checked_mul(&self, other: &Self) -> Option<Self>;
+}
+
+macro_rules! impl_count_for_primitive {
+    ($($t:ty),*) => {
+        $(
+            impl Count for $t {
+                fn zero() -> Self {
+                    0
+                }
+
+                fn one() -> Self {
+                    1
+                }
+
+                fn is_zero(&self) -> bool {
+                    *self == 0
+                }
+
+                fn checked_add(&self, other: &Self) -> Option<Self> {
+                    <$t>::checked_add(*self, *other)
+                }
+
+                fn checked_mul(&self, other: &Self) -> Option<Self> {
+                    <$t>::checked_mul(*self, *other)
+                }
+            }
+        )*
+    };
+}
+
+impl_count_for_primitive!(u64, u128);
+
+impl Count for BigUint {
+    fn zero() -> Self {
+        Zero::zero()
+    }
+
+    fn one() -> Self {
+        One::one()
+    }
+
+    fn is_zero(&self) -> bool {
+        Zero::is_zero(self)
+    }
+
+    fn checked_add(&self, other: &Self) -> Option<Self> {
+        Some(self + other)
+    }
+
+    fn checked_mul(&self, other: &Self) -> Option<Self> {
+        Some(self * other)
+    }
+}
+
+/// A count modulo M, which never overflows
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct ModularCount<const M: u64>(pub u64);
+
+impl<const M: u64> Count for ModularCount<M> {
+    fn zero() -> Self {
+        Self(0)
+    }
+
+    fn one() -> Self {
+        Self(1 % M)
+    }
+
+    fn is_zero(&self) -> bool {
+        self.0 == 0
+    }
+
+    fn checked_add(&self, other: &Self) -> Option<Self> {
+        Some(Self(
+            ((self.0 as u128 + other.0 as u128) % M as u128) as u64,
+        ))
+    }
+
+    fn checked_mul(&self, other: &Self) -> Option<Self> {
+        Some(Self(
+            ((self.0 as u128 * other.0 as u128) % M as u128) as u64,
+        ))
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SquareMatrix<T> {
+    size: usize,
+    entries: Vec<T>,
+}
+
+impl<T: Count> SquareMatrix<T> {
+    pub fn zero(size: usize) -> Self {
+        Self {
+            size,
+            entries: vec![T::zero(); size * size],
+        }
+    }
+
+    pub fn identity(size: usize) -> Self {
+        let mut matrix = Self::zero(size);
+        for i in 0..size {
+            matrix.entries[i * size + i] = T::one();
+        }
+        matrix
+    }
+
+    pub fn size(&self) -> usize {
+        self.size
+    }
+
+    pub fn get(&self, row: usize, col: usize) -> &T {
+        &self.entries[row * self.size + col]
+    }
+
+    fn add_to(&mut self, row: usize, col: usize, value: &T) -> Result<(), PolymerError> {
+        let entry = &mut self.entries[row * self.size + col];
+        *entry = entry.checked_add(value).ok_or(PolymerError::Overflow)?;
+        Ok(())
+    }
+
+    pub fn checked_mul(&self, other: &Self) -> Result<Self, PolymerError> {
+        let mut product = Self::zero(self.size);
+        for row in 0..self.size {
+            for k in 0..self.size {
+                let left = self.get(row, k);
+                // The matrices are sparse, so skip the zeroes
+                if left.is_zero() {
+                    continue;
+                }
+                for col in 0..self.size {
+                    let right = other.get(k, col);
+                    if !right.is_zero() {
+                        let term = left.checked_mul(right).ok_or(PolymerError::Overflow)?;
+                        product.add_to(row, col, &term)?;
+                    }
+                }
+            }
+        }
+        Ok(product)
+    }
+
+    /// Raise the matrix to a power by repeated squaring
+    pub fn checked_pow(&self, mut exponent: u64) -> Result<Self, PolymerError> {
+        let mut result = Self::identity(self.size);
+        let mut square = self.clone();
+        while exponent > 0 {
+            if exponent & 1 == 1 {
+                result = result.checked_mul(&square)?;
+            }
+            exponent >>= 1;
+            if exponent > 0 {
+                square = square.checked_mul(&square)?;
+            }
+        }
+        Ok(result)
+    }
+
+    pub fn checked_mul_vector(&self, vector: &[T]) -> Result<Vec<T>, PolymerError> {
+        (0..self.size)
+            .map(|row| {
+                vector
+                    .iter()
+                    .enumerate()
+                    .try_fold(T::zero(), |total, (col, value)| {
+                        total.checked_add(&self.get(row, col).checked_mul(value)?)
+                    })
+                    .ok_or(PolymerError::Overflow)
+            })
+            .collect()
+    }
+}
+
+/// A polymer template and its pair insertion rules, with elements numbered in order of appearance
+#[derive(Debug, Clone)]
+pub struct Polymer {
+    elements: Vec<char>,
+    template: Vec<usize>,
+    /// The pair of elements, and the element inserted between them
+    rules: Vec<((usize, usize), usize)>,
+}
+
+impl Polymer {
+    /// The elements in the template or rules
+    pub fn elements(&self) -> &[char] {
+        &self.elements
+    }
+
+    fn pair_index(&self, left: usize, right: usize) -> usize {
+        left * self.elements.len() + right
+    }
+
+    /// The matrix which maps the counts of each pair to the counts after one step,
+    /// where pairs are indexed by left element * element count + right element
+    pub fn transition_matrix<T: Count>(&self) -> Result<SquareMatrix<T>, PolymerError> {
+        let pair_count = self.elements.len().pow(2);
+        let mut matrix = SquareMatrix::identity(pair_count);
+        for &((left, right), inserted) in &self.rules {
+            let pair = self.pair_index(left, right);
+            // The pair no longer maps to itself, but to the two pairs either side of the inserted element
+            matrix.entries[pair * pair_count + pair] = T::zero();
+            matrix.add_to(self.pair_index(left, inserted), pair, &T::one())?;
+            matrix.add_to(self.pair_index(inserted, right), pair, &T::one())?;
+        }
+        Ok(matrix)
+    }
+
+    /// The number of each element after the given number of steps,
+    /// including elements which no longer appear
+    pub fn histogram<T: Count>(&self, steps: u64) -> Result<BTreeMap<char, T>, PolymerError> {
+        let mut pair_counts = vec![T::zero(); self.elements.len().pow(2)];
+        for pair in self.template.windows(2) {
+            let index = self.pair_index(pair[0], pair[1]);
+            pair_counts[index] = pair_counts[index]
+                .checked_add(&T::one())
+                .ok_or(PolymerError::Overflow)?;
+        }
+        let pair_counts = self
+            .transition_matrix()?
+            .checked_pow(steps)?
+            .checked_mul_vector(&pair_counts)?;
+
+        // Count the right element of each pair, and the first element, which never changes
+        let mut element_counts = vec![T::zero(); self.elements.len()];
+        element_counts[self.template[0]] = T::one();
+        for (pair, count) in pair_counts.iter().enumerate() {
+            let right = pair % self.elements.len();
+            element_counts[right] = element_counts[right]
+                .checked_add(count)
+                .ok_or(PolymerError::Overflow)?;
+        }
+        Ok(self.elements.iter().copied().zip(element_counts).collect())
+    }
+}
+
+impl FromStr for Polymer {
+    type Err = PolymerError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut lines = s.lines();
+        let template_str = lines.next().map(str::trim).unwrap_or_default();
+        if template_str.is_empty() {
+            return Err(PolymerError::MissingTemplate);
+        }
+        let mut elements: Vec<char> = Vec::new();
+        let mut element_index = |ch: char| match elements.iter().position(|&element| element == ch)
+        {
+            Some(index) => index,
+            None => {
+                elements.push(ch);
+                elements.len() - 1
+            }
+        };
+        let template = template_str.chars().map(&mut element_index).collect();
+        let mut rules: Vec<((usize, usize), usize)> = Vec::new();
+        for line in lines.filter(|line| !line.trim().is_empty()) {
+            let invalid_rule = || PolymerError::InvalidRule(line.to_string());
+            let (pair_str, inserted_str) = line.split_once(" -> ").ok_or_else(invalid_rule)?;
+            let (&[left, right], &[inserted]) = (
+                pair_str.trim().chars().collect::<Vec<_>>().as_slice(),
+                inserted_str.trim().chars().collect::<Vec<_>>().as_slice(),
+            ) else {
+                return Err(invalid_rule());
+            };
+            let pair = (element_index(left), element_index(right));
+            if rules.iter().any(|&(rule_pair, _)| rule_pair == pair) {
+                return Err(PolymerError::DuplicateRule(pair_str.trim().to_string()));
+            }
+            rules.push((pair, element_index(inserted)));
+        }
+        Ok(Polymer {
+            elements,
+            template,
+            rules,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const EXAMPLE: &str = "NNCB
+
+CH -> B
+HH -> N
+CB -> H
+NH -> C
+HB -> C
+HC -> B
+HN -> C
+NN -> C
+BH -> H
+NC -> B
+NB -> B
+BN -> B
+BB -> N
+BC -> B
+CC -> N
+CN -> C";
+
+    /// Insert the elements one step at a time, for comparison
+    fn histogram_by_insertion(polymer: &Polymer, steps: u64) -> BTreeMap<char, u64> {
+        let mut template = polymer.template.clone();
+        for _ in 0..steps {
+            let mut next_template = vec![template[0]];
+            for pair in template.windows(2) {
+                if let Some((_, inserted)) = polymer
+                    .rules
+                    .iter()
+                    .find(|(rule_pair, _)| *rule_pair == (pair[0], pair[1]))
+                {
+                    next_template.push(*inserted);
+                }
+                next_template.push(pair[1]);
+            }
+            template = next_template;
+        }
+        let mut histogram: BTreeMap<char, u64> =
+            polymer.elements.iter().map(|&ch| (ch, 0)).collect();
+        for element in template {
+            *histogram.get_mut(&polymer.elements[element]).unwrap() += 1;
+        }
+        histogram
+    }
+
+    #[test]
+    fn test_example() {
+        let polymer: Polymer = EXAMPLE.parse().unwrap();
+        assert_eq!(polymer.elements(), ['N', 'C', 'B', 'H']);
+        for steps in 0..=10 {
+            assert_eq!(
+                polymer.histogram::<u64>(steps).unwrap(),
+                histogram_by_insertion(&polymer, steps)
+            );
+        }
+        let histogram = polymer.histogram::<u128>(10).unwrap();
+        assert_eq!(histogram[&'B'], 1749);
+        assert_eq!(histogram[&'H'], 161);
+        let histogram = polymer.histogram::<u128>(40).unwrap();
+        assert_eq!(histogram[&'B'] - histogram[&'H'], 2188189693529);
+    }
+
+    #[test]
+    fn test_overflow_and_big_counts() {
+        let polymer: Polymer = EXAMPLE.parse().unwrap();
+        assert_eq!(polymer.histogram::<u64>(100), Err(PolymerError::Overflow));
+        let exact = polymer.histogram::<BigUint>(100).unwrap();
+        let total: BigUint = exact.values().sum();
+        // Each step inserts an element between every pair, so the length goes from n to 2n - 1
+        assert_eq!(total, (BigUint::from(2_u8).pow(100) * 3_u8) + 1_u8);
+
+        const MODULUS: u64 = 1_000_000_007;
+        let modular = polymer.histogram::<ModularCount<MODULUS>>(100).unwrap();
+        for (element, count) in &exact {
+            assert_eq!(BigUint::from(modular[element].0), count % MODULUS);
+        }
+        let total = polymer
+            .histogram::<ModularCount<MODULUS>>(1_000_000_000_000)
+            .unwrap()
+            .values()
+            .fold(0, |total, count| (total + count.0) % MODULUS);
+        assert_eq!(
+            BigUint::from(total),
+            (BigUint::from(2_u8).modpow(
+                &BigUint::from(1_000_000_000_000_u64),
+                &BigUint::from(MODULUS)
+            ) * 3_u8
+                + 1_u8)
+                % MODULUS
+        );
+    }
+
+    #[test]
+    fn test_invalid_input() {
+        assert_eq!(
+            "".parse::<Polymer>().err(),
+            Some(PolymerError::MissingTemplate)
+        );
+        assert_eq!(
+            "NN\n\nNN -> CC".parse::<Polymer>().err(),
+            Some(PolymerError::InvalidRule("NN -> CC".to_string()))
+        );
+        assert_eq!(
+            "NN\n\nNN -> C\nNC -> B\nNN -> B".parse::<Polymer>().err(),
+            Some(PolymerError::DuplicateRule("NN".to_string()))
+        );
+        let polymer: Polymer = "AB".parse().unwrap();
+        assert_eq!(
+            polymer.histogram::<u64>(5).unwrap(),
+            BTreeMap::from([('A', 1), ('B', 1)])
+        );
+    }
+}
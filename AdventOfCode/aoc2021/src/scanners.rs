@@ -0,0 +1,427 @@
+//! Aligning the beacon scanners from day 19.
+//!
+//! Each scanner is fingerprinted by the squared distances between every pair of its beacons,
+//! since these don't depend on where the scanner is or which way it faces.
+//! Two scanners which see the same 12 beacons share at least 66 of these distances,
+//! so alignment is only attempted for such scanners, and only with the rotations and
+//! translations which line up a pair of beacons at a shared distance.
+
+use std::collections::{HashMap, HashSet, VecDeque};
+use std::str::FromStr;
+use thiserror::Error;
+
+pub const MIN_OVERLAPPING_BEACONS: usize = 12;
+pub const MIN_SHARED_FINGERPRINTS: usize =
+    MIN_OVERLAPPING_BEACONS * (MIN_OVERLAPPING_BEACONS - 1) / 2;
+
+pub type Point = [i64; 3];
+
+#[derive(Debug, Error, PartialEq, Eq)]
+pub enum ScannerError {
+    #[error("invalid beacon {0:?}")]
+    InvalidBeacon(String),
+    #[error("scanners {0:?} could not be aligned with scanner 0")]
+    UnalignedScanners(Vec<usize>),
+}
+
+/// One of the 24 ways of turning a scanner to face along an axis, as a signed permutation matrix
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Rotation {
+    matrix: [[i64; 3]; 3],
+}
+
+impl Rotation {
+    pub const IDENTITY: Rotation = Rotation {
+        matrix: [[1, 0, 0], [0, 1, 0], [0, 0, 1]],
+    };
+
+    /// All 24 rotations, starting with the identity.
+    /// These are the signed permutation matrices which don't also reflect.
+    pub fn all() -> Vec<Rotation> {
+        const PERMUTATIONS: [[usize; 3]; 6] = [
+            [0, 1, 2],
+            [0, 2, 1],
+            [1, 0, 2],
+            [1, 2, 0],
+            [2, 0, 1],
+            [2, 1, 0],
+        ];
+        PERMUTATIONS
+            .iter()
+            .flat_map(|permutation| {
+                (0..8).map(move |signs| Rotation {
+                    matrix: std::array::from_fn(|row| {
+                        let sign = if signs & (1 << row) == 0 { 1 } else { -1 };
+                        std::array::from_fn(|col| if col == permutation[row] { sign } else { 0 })
+                    }),
+                })
+            })
+            .filter(|rotation| rotation.determinant() == 1)
+            .collect()
+    }
+
+    pub fn determinant(&self) -> i64 {
+        let m = &self.matrix;
+        m[0][0] * (m[1][1] * m[2][2] - m[1][2] * m[2][1])
+            - m[0][1] * (m[1][0] * m[2][2] - m[1][2] * m[2][0])
+            + m[0][2] * (m[1][0] * m[2][1] - m[1][1] * m[2][0])
+    }
+
+    pub fn apply(&self, point: Point) -> Point {
+        self.matrix
+            .map(|row| (0..3).map(|col| row[col] * point[col]).sum())
+    }
+
+    /// The rotation which applies `other` first, then `self`
+    pub fn compose(&self, other: &Rotation) -> Rotation {
+        Rotation {
+            matrix: std::array::from_fn(|row| {
+                std::array::from_fn(|col| {
+                    (0..3)
+                        .map(|k| self.matrix[row][k] * other.matrix[k][col])
+                        .sum()
+                })
+            }),
+        }
+    }
+
+    /// The transpose, since rotation matrices are orthogonal
+    pub fn inverse(&self) -> Rotation {
+        Rotation {
+            matrix: std::array::from_fn(|row| std::array::from_fn(|col| self.matrix[col][row])),
+        }
+    }
+}
+
+/// Where a scanner is, and which way it faces, relative to another scanner
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Placement {
+    pub rotation: Rotation,
+    pub position: Point,
+}
+
+impl Placement {
+    pub const ORIGIN: Placement = Placement {
+        rotation: Rotation::IDENTITY,
+        position: [0; 3],
+    };
+
+    /// Convert a point from the placed scanner's coordinates to the other scanner's
+    pub fn apply(&self, point: Point) -> Point {
+        add(self.rotation.apply(point), self.position)
+    }
+
+    /// The placement which applies `other` first, then `self`
+    pub fn compose(&self, other: &Placement) -> Placement {
+        Placement {
+            rotation: self.rotation.compose(&other.rotation),
+            position: self.apply(other.position),
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct Scanner {
+    beacons: Vec<Point>,
+    beacon_set: HashSet<Point>,
+    /// The pairs of beacons at each squared distance
+    pairs_by_distance: HashMap<i64, Vec<(usize, usize)>>,
+}
+
+impl Scanner {
+    pub fn new(beacons: Vec<Point>) -> Self {
+        let mut pairs_by_distance: HashMap<i64, Vec<(usize, usize)>> = HashMap::new();
+        for (i, a) in beacons.iter().enumerate() {
+            for (j, b) in beacons.iter().enumerate().skip(i + 1) {
+                let diff = subtract(*b, *a);
+                let squared_distance = diff.iter().map(|d| d * d).sum();
+                pairs_by_distance
+                    .entry(squared_distance)
+                    .or_default()
+                    .push((i, j));
+            }
+        }
+        Self {
+            beacon_set: beacons.iter().copied().collect(),
+            beacons,
+            pairs_by_distance,
+        }
+    }
+
+    pub fn beacons(&self) -> &[Point] {
+        &self.beacons
+    }
+
+    /// The number of pairwise distances which both scanners see, counting repeats
+    pub fn shared_fingerprints(&self, other: &Scanner) -> usize {
+        self.pairs_by_distance
+            .iter()
+            .filter_map(|(distance, pairs)| {
+                let other_pairs = other.pairs_by_distance.get(distance)?;
+                Some(pairs.len().min(other_pairs.len()))
+            })
+            .sum()
+    }
+
+    /// Find where the other scanner is relative to this one,
+    /// if at least 12 of their beacons can be lined up
+    pub fn align(&self, other: &Scanner) -> Option<Placement> {
+        if self.shared_fingerprints(other) < MIN_SHARED_FINGERPRINTS {
+            return None;
+        }
+        let rotations = Rotation::all();
+        let mut tried: HashSet<Placement> = HashSet::new();
+        for (distance, pairs) in &self.pairs_by_distance {
+            let Some(other_pairs) = other.pairs_by_distance.get(distance) else {
+                continue;
+            };
+            for &(a1, a2) in pairs {
+                let [a1, a2] = [a1, a2].map(|i| self.beacons[i]);
+                for &(b1, b2) in other_pairs {
+                    let [b1, b2] = [b1, b2].map(|i| other.beacons[i]);
+                    let diff = subtract(b2, b1);
+                    for rotation in &rotations {
+                        let rotated_diff = rotation.apply(diff);
+                        // b1 is either a1 or a2, depending on which way round the pair lines up
+                        let anchor = if rotated_diff == subtract(a2, a1) {
+                            a1
+                        } else if rotated_diff == subtract(a1, a2) {
+                            a2
+                        } else {
+                            continue;
+                        };
+                        let placement = Placement {
+                            rotation: *rotation,
+                            position: subtract(anchor, rotation.apply(b1)),
+                        };
+                        if tried.insert(placement) && self.overlaps(other, &placement) {
+                            return Some(placement);
+                        }
+                    }
+                }
+            }
+        }
+        None
+    }
+
+    fn overlaps(&self, other: &Scanner, placement: &Placement) -> bool {
+        other
+            .beacons
+            .iter()
+            .filter(|beacon| self.beacon_set.contains(&placement.apply(**beacon)))
+            .take(MIN_OVERLAPPING_BEACONS)
+            .count()
+            == MIN_OVERLAPPING_BEACONS
+    }
+}
+
+/// Parses a block of beacons, with or without its "--- scanner N ---" heading
+impl FromStr for Scanner {
+    type Err = ScannerError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let beacons = s
+            .lines()
+            .filter(|line| !line.starts_with("---"))
+            .map(|line| {
+                let coords: Vec<i64> = line
+                    .split(',')
+                    .map(|coord| coord.trim().parse())
+                    .collect::<Result<_, _>>()
+                    .map_err(|_| ScannerError::InvalidBeacon(line.to_string()))?;
+                coords
+                    .try_into()
+                    .map_err(|_| ScannerError::InvalidBeacon(line.to_string()))
+            })
+            .collect::<Result<_, _>>()?;
+        Ok(Scanner::new(beacons))
+    }
+}
+
+pub fn parse_scanners(contents: &str) -> Result<Vec<Scanner>, ScannerError> {
+    contents
+        .replace("\r\n", "\n")
+        .split("\n\n")
+        .filter(|block| !block.trim().is_empty())
+        .map(str::parse)
+        .collect()
+}
+
+/// Place every scanner relative to the first one, searching outwards from it
+pub fn locate_scanners(scanners: &[Scanner]) -> Result<Vec<Placement>, ScannerError> {
+    let mut placements: Vec<Option<Placement>> = vec![None; scanners.len()];
+    let mut to_visit = VecDeque::new();
+    if !scanners.is_empty() {
+        placements[0] = Some(Placement::ORIGIN);
+        to_visit.push_back(0);
+    }
+    while let Some(i) = to_visit.pop_front() {
+        let placement = placements[i].unwrap();
+        for j in 0..scanners.len() {
+            if placements[j].is_some() {
+                continue;
+            }
+            if let Some(relative_placement) = scanners[i].align(&scanners[j]) {
+                placements[j] = Some(placement.compose(&relative_placement));
+                to_visit.push_back(j);
+            }
+        }
+    }
+    let unaligned: Vec<usize> = (0..scanners.len())
+        .filter(|&i| placements[i].is_none())
+        .collect();
+    if !unaligned.is_empty() {
+        return Err(ScannerError::UnalignedScanners(unaligned));
+    }
+    Ok(placements.into_iter().flatten().collect())
+}
+
+/// All the beacons, in the first scanner's coordinates
+pub fn all_beacons(scanners: &[Scanner], placements: &[Placement]) -> HashSet<Point> {
+    scanners
+        .iter()
+        .zip(placements)
+        .flat_map(|(scanner, placement)| {
+            scanner
+                .beacons
+                .iter()
+                .map(|beacon| placement.apply(*beacon))
+        })
+        .collect()
+}
+
+pub fn manhattan_distance(a: Point, b: Point) -> i64 {
+    subtract(a, b).iter().map(|d| d.abs()).sum()
+}
+
+fn add(a: Point, b: Point) -> Point {
+    std::array::from_fn(|i| a[i] + b[i])
+}
+
+fn subtract(a: Point, b: Point) -> Point {
+    std::array::from_fn(|i| a[i] - b[i])
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_rotation_group() {
+        let rotations = Rotation::all();
+        assert_eq!(rotations.len(), 24);
+        assert_eq!(rotations[0], Rotation::IDENTITY);
+        let images: HashSet<Point> = rotations.iter().map(|r| r.apply([1, 2, 3])).collect();
+        assert_eq!(images.len(), 24);
+        for a in &rotations {
+            assert_eq!(a.compose(&a.inverse()), Rotation::IDENTITY);
+            assert_eq!(a.determinant(), 1);
+            for b in &rotations {
+                let ab = a.compose(b);
+                assert!(rotations.contains(&ab));
+                assert_eq!(ab.apply([1, 2, 3]), a.apply(b.apply([1, 2, 3])));
+            }
+        }
+    }
+
+    #[test]
+    fn test_placement_compose() {
+        let rotations = Rotation::all();
+        let outer = Placement {
+            rotation: rotations[5],
+            position: [10, -20, 30],
+        };
+        let inner = Placement {
+            rotation: rotations[17],
+            position: [-4, 5, 6],
+        };
+        let point = [7, 8, -9];
+        assert_eq!(
+            outer.compose(&inner).apply(point),
+            outer.apply(inner.apply(point))
+        );
+    }
+
+    #[test]
+    fn test_parse() {
+        let scanners =
+            parse_scanners("--- scanner 0 ---\n0,2,0\n4,1,0\n\n--- scanner 1 ---\n-1,-1,1")
+                .unwrap();
+        assert_eq!(scanners.len(), 2);
+        assert_eq!(scanners[0].beacons(), [[0, 2, 0], [4, 1, 0]]);
+        assert_eq!(
+            parse_scanners("--- scanner 0 ---\n1,2").unwrap_err(),
+            ScannerError::InvalidBeacon("1,2".to_string())
+        );
+        assert_eq!(
+            manhattan_distance([1105, -1205, 1229], [-92, -2380, -20]),
+            3621
+        );
+    }
+
+    mod prop_tests {
+        use super::super::*;
+        use proptest::prelude::*;
+        use proptest::{collection, sample};
+
+        /// Scanners at known placements, which each see the beacons within 1000 of them
+        /// along every axis. The placements are relative to the first scanner.
+        fn scanner_layouts() -> impl Strategy<Value = (Vec<Scanner>, Vec<Placement>)> {
+            let world_beacons = collection::vec([-1000_i64..2600, -1000..2600, -1000..2600], 400);
+            let other_placements =
+                collection::vec((sample::select(Rotation::all()), -100_i64..100), 5);
+            (world_beacons, other_placements).prop_map(|(world_beacons, other_placements)| {
+                let grid_positions = [(0, 1), (1, 0), (1, 1), (2, 0), (2, 1)];
+                let placements: Vec<Placement> = std::iter::once(Placement::ORIGIN)
+                    .chain(grid_positions.iter().zip(other_placements).map(
+                        |(&(x, y), (rotation, z))| Placement {
+                            rotation,
+                            position: [x * 800, y * 800, z],
+                        },
+                    ))
+                    .collect();
+                let scanners = placements
+                    .iter()
+                    .map(|placement| {
+                        let inverse = placement.rotation.inverse();
+                        let beacons = world_beacons
+                            .iter()
+                            .filter(|beacon| {
+                                (0..3).all(|i| (beacon[i] - placement.position[i]).abs() <= 1000)
+                            })
+                            .map(|beacon| inverse.apply(subtract(*beacon, placement.position)))
+                            .collect();
+                        Scanner::new(beacons)
+                    })
+                    .collect();
+                (scanners, placements)
+            })
+        }
+
+        proptest! {
+            #![proptest_config(ProptestConfig::with_cases(16))]
+
+            #[test]
+            fn scanners_are_located_at_their_placements(
+                (scanners, expected_placements) in scanner_layouts()
+            ) {
+                prop_assert!(
+                    scanners[0].shared_fingerprints(&scanners[1]) >= MIN_SHARED_FINGERPRINTS
+                );
+                let placements = locate_scanners(&scanners).unwrap();
+                prop_assert_eq!(&placements, &expected_placements);
+                let beacons = all_beacons(&scanners, &placements);
+                prop_assert!(beacons.len() < scanners.iter().map(|s| s.beacons().len()).sum());
+            }
+
+            #[test]
+            fn unaligned_scanners_are_reported((mut scanners, _) in scanner_layouts()) {
+                scanners.push(Scanner::new(vec![[1, 2, 3], [4, 5, 6]]));
+                prop_assert_eq!(
+                    locate_scanners(&scanners),
+                    Err(ScannerError::UnalignedScanners(vec![6]))
+                );
+            }
+        }
+    }
+}
@@ -0,0 +1,433 @@
+//! Snailfish numbers from day 18, stored flat as their regular numbers in order, with their depths.
+//!
+//! A pair is never stored directly. Two adjacent leaves at the same depth are siblings
+//! whenever the number is nested at most five pairs deep, which is all that reducing needs.
+//! So exploding and splitting become edits to a vector instead of walks over a tree.
+
+use std::fmt::{Display, Formatter};
+use std::iter::Sum;
+use std::ops::Add;
+use std::str::FromStr;
+use thiserror::Error;
+
+/// Pairs nested inside this many pairs explode
+const EXPLODING_DEPTH: u8 = 5;
+const SPLITTING_VALUE: u32 = 10;
+
+#[derive(Debug, Error, PartialEq, Eq)]
+pub enum SnailfishError {
+    #[error("expected {expected} at character {position}, but found {found:?}")]
+    UnexpectedChar {
+        position: usize,
+        expected: &'static str,
+        found: char,
+    },
+    #[error("expected {expected} at character {position}, but the text ended")]
+    UnexpectedEnd {
+        position: usize,
+        expected: &'static str,
+    },
+    #[error("unexpected text after the number at character {position}")]
+    TrailingText { position: usize },
+    #[error("the regular number at character {position} is too large")]
+    ValueTooLarge { position: usize },
+    #[error("the pair at character {position} is nested more than {EXPLODING_DEPTH} pairs deep")]
+    TooDeep { position: usize },
+}
+
+/// A regular number, and the number of pairs it is nested inside
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Leaf {
+    pub depth: u8,
+    pub value: u32,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SnailfishNumber {
+    leaves: Vec<Leaf>,
+}
+
+impl SnailfishNumber {
+    pub fn leaves(&self) -> &[Leaf] {
+        &self.leaves
+    }
+
+    /// Explode the leftmost pair nested inside four pairs, if there is one
+    pub fn explode(&mut self) -> bool {
+        let Some(i) = self
+            .leaves
+            .iter()
+            .position(|leaf| leaf.depth >= EXPLODING_DEPTH)
+        else {
+            return false;
+        };
+        let Leaf { value: left, .. } = self.leaves[i];
+        let Leaf { value: right, .. } = self.leaves.remove(i + 1);
+        if i > 0 {
+            self.leaves[i - 1].value += left;
+        }
+        if let Some(next) = self.leaves.get_mut(i + 1) {
+            next.value += right;
+        }
+        self.leaves[i] = Leaf {
+            depth: EXPLODING_DEPTH - 1,
+            value: 0,
+        };
+        true
+    }
+
+    /// Split the leftmost regular number of 10 or more, if there is one
+    pub fn split(&mut self) -> bool {
+        let Some(i) = self
+            .leaves
+            .iter()
+            .position(|leaf| leaf.value >= SPLITTING_VALUE)
+        else {
+            return false;
+        };
+        let Leaf { depth, value } = self.leaves[i];
+        let left = Leaf {
+            depth: depth + 1,
+            value: value / 2,
+        };
+        let right = Leaf {
+            depth: depth + 1,
+            value: value.div_ceil(2),
+        };
+        self.leaves.splice(i..=i, [left, right]);
+        true
+    }
+
+    pub fn reduce(&mut self) {
+        while self.explode() || self.split() {}
+    }
+
+    pub fn is_reduced(&self) -> bool {
+        self.leaves
+            .iter()
+            .all(|leaf| leaf.depth < EXPLODING_DEPTH && leaf.value < SPLITTING_VALUE)
+    }
+
+    pub fn magnitude(&self) -> u64 {
+        self.fold_pairs(|leaf| leaf.value as u64, |left, right| 3 * left + 2 * right)
+    }
+
+    /// Rebuild the tree from the leaves, by combining the last two items on a stack
+    /// whenever they are at the same depth
+    fn fold_pairs<T>(&self, make_leaf: impl Fn(&Leaf) -> T, make_pair: impl Fn(T, T) -> T) -> T {
+        let mut stack: Vec<(u8, T)> = Vec::with_capacity(EXPLODING_DEPTH as usize + 1);
+        for leaf in &self.leaves {
+            let mut item = (leaf.depth, make_leaf(leaf));
+            while let Some((depth, _)) = stack.last() {
+                if *depth != item.0 {
+                    break;
+                }
+                let (depth, left) = stack.pop().unwrap();
+                item = (depth - 1, make_pair(left, item.1));
+            }
+            stack.push(item);
+        }
+        stack.pop().unwrap().1
+    }
+}
+
+impl Display for SnailfishNumber {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        let text = self.fold_pairs(
+            |leaf| leaf.value.to_string(),
+            |left, right| format!("[{left},{right}]"),
+        );
+        write!(f, "{text}")
+    }
+}
+
+impl FromStr for SnailfishNumber {
+    type Err = SnailfishError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut parser = Parser {
+            text: s.trim(),
+            position: 0,
+            leaves: Vec::new(),
+        };
+        parser.parse_pair(0)?;
+        if parser.position < parser.text.len() {
+            return Err(SnailfishError::TrailingText {
+                position: parser.position,
+            });
+        }
+        Ok(Self {
+            leaves: parser.leaves,
+        })
+    }
+}
+
+struct Parser<'a> {
+    text: &'a str,
+    position: usize,
+    leaves: Vec<Leaf>,
+}
+
+impl Parser<'_> {
+    fn peek(&self) -> Option<char> {
+        self.text[self.position..].chars().next()
+    }
+
+    fn expect(&mut self, expected_ch: char, expected: &'static str) -> Result<(), SnailfishError> {
+        if self.peek() != Some(expected_ch) {
+            return Err(self.unexpected(expected));
+        }
+        self.position += expected_ch.len_utf8();
+        Ok(())
+    }
+
+    fn unexpected(&self, expected: &'static str) -> SnailfishError {
+        match self.peek() {
+            Some(found) => SnailfishError::UnexpectedChar {
+                position: self.position,
+                expected,
+                found,
+            },
+            None => SnailfishError::UnexpectedEnd {
+                position: self.position,
+                expected,
+            },
+        }
+    }
+
+    /// Parse a pair nested inside `depth` pairs
+    fn parse_pair(&mut self, depth: u8) -> Result<(), SnailfishError> {
+        if depth >= EXPLODING_DEPTH {
+            return Err(SnailfishError::TooDeep {
+                position: self.position,
+            });
+        }
+        self.expect('[', "'['")?;
+        self.parse_element(depth + 1)?;
+        self.expect(',', "','")?;
+        self.parse_element(depth + 1)?;
+        self.expect(']', "']'")
+    }
+
+    fn parse_element(&mut self, depth: u8) -> Result<(), SnailfishError> {
+        if self.peek() == Some('[') {
+            return self.parse_pair(depth);
+        }
+        let start = self.position;
+        let digits = self.text[start..]
+            .find(|ch: char| !ch.is_ascii_digit())
+            .unwrap_or(self.text.len() - start);
+        if digits == 0 {
+            return Err(self.unexpected("'[' or a digit"));
+        }
+        self.position += digits;
+        let value = self.text[start..self.position]
+            .parse()
+            .map_err(|_| SnailfishError::ValueTooLarge { position: start })?;
+        self.leaves.push(Leaf { depth, value });
+        Ok(())
+    }
+}
+
+/// Adding puts the numbers into a pair, then reduces it.
+/// The numbers are reduced first, so that the pair is nested at most five pairs deep.
+impl Add for SnailfishNumber {
+    type Output = SnailfishNumber;
+
+    fn add(mut self, mut rhs: Self) -> Self::Output {
+        self.reduce();
+        rhs.reduce();
+        self.leaves.append(&mut rhs.leaves);
+        for leaf in &mut self.leaves {
+            leaf.depth += 1;
+        }
+        self.reduce();
+        self
+    }
+}
+
+impl Add for &SnailfishNumber {
+    type Output = SnailfishNumber;
+
+    fn add(self, rhs: Self) -> Self::Output {
+        self.clone() + rhs.clone()
+    }
+}
+
+/// There is no zero snailfish number, so the sum of no numbers is `None`
+impl Sum<SnailfishNumber> for Option<SnailfishNumber> {
+    fn sum<I: Iterator<Item = SnailfishNumber>>(iter: I) -> Self {
+        iter.reduce(|total, number| total + number)
+    }
+}
+
+impl<'a> Sum<&'a SnailfishNumber> for Option<SnailfishNumber> {
+    fn sum<I: Iterator<Item = &'a SnailfishNumber>>(iter: I) -> Self {
+        iter.cloned().sum()
+    }
+}
+
+/// The largest magnitude of the sum of two of the numbers (part 2).
+/// A number may be added to an equal number elsewhere in the list, but not to itself.
+pub fn largest_magnitude_of_pair_sum(numbers: &[SnailfishNumber]) -> Option<u64> {
+    (0..numbers.len())
+        .flat_map(|i| (0..numbers.len()).map(move |j| (i, j)))
+        .filter(|(i, j)| i != j)
+        .map(|(i, j)| (&numbers[i] + &numbers[j]).magnitude())
+        .max()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const HOMEWORK_EXAMPLE: &str = "\
+        [[[0,[5,8]],[[1,7],[9,6]]],[[4,[1,2]],[[1,4],2]]]\n\
+        [[[5,[2,8]],4],[5,[[9,9],0]]]\n\
+        [6,[[[6,2],[5,6]],[[7,6],[4,7]]]]\n\
+        [[[6,[0,7]],[0,9]],[4,[9,[9,0]]]]\n\
+        [[[7,[6,4]],[3,[1,3]]],[[[5,5],1],9]]\n\
+        [[6,[[7,3],[3,2]]],[[[3,8],[5,7]],4]]\n\
+        [[[[5,4],[7,7]],8],[[8,3],8]]\n\
+        [[9,3],[[9,9],[6,[4,9]]]]\n\
+        [[2,[[7,7],7]],[[5,8],[[9,3],[0,2]]]]\n\
+        [[[[5,2],5],[8,[3,7]]],[[5,[7,5]],[4,4]]]";
+
+    fn number(text: &str) -> SnailfishNumber {
+        text.parse().unwrap()
+    }
+
+    #[test]
+    fn test_explode() {
+        for (text, expected) in [
+            ("[[[[[9,8],1],2],3],4]", "[[[[0,9],2],3],4]"),
+            ("[7,[6,[5,[4,[3,2]]]]]", "[7,[6,[5,[7,0]]]]"),
+            ("[[6,[5,[4,[3,2]]]],1]", "[[6,[5,[7,0]]],3]"),
+            (
+                "[[3,[2,[1,[7,3]]]],[6,[5,[4,[3,2]]]]]",
+                "[[3,[2,[8,0]]],[9,[5,[4,[3,2]]]]]",
+            ),
+            (
+                "[[3,[2,[8,0]]],[9,[5,[4,[3,2]]]]]",
+                "[[3,[2,[8,0]]],[9,[5,[7,0]]]]",
+            ),
+        ] {
+            let mut input = number(text);
+            assert!(input.explode());
+            assert_eq!(input.to_string(), expected);
+        }
+        assert!(!number("[[[[0,9],2],3],4]").explode());
+    }
+
+    #[test]
+    fn test_split() {
+        let mut input = number("[[[[0,7],4],[15,[0,13]]],[1,1]]");
+        assert!(input.split());
+        assert_eq!(input.to_string(), "[[[[0,7],4],[[7,8],[0,13]]],[1,1]]");
+        assert!(input.split());
+        assert_eq!(input.to_string(), "[[[[0,7],4],[[7,8],[0,[6,7]]]],[1,1]]");
+        assert!(!input.split());
+    }
+
+    #[test]
+    fn test_add_and_reduce() {
+        let sum = number("[[[[4,3],4],4],[7,[[8,4],9]]]") + number("[1,1]");
+        assert_eq!(sum.to_string(), "[[[[0,7],4],[[7,8],[6,0]]],[8,1]]");
+        assert!(sum.is_reduced());
+    }
+
+    #[test]
+    fn test_sum() {
+        let numbers: Vec<SnailfishNumber> =
+            (1..=6).map(|i| number(&format!("[{i},{i}]"))).collect();
+        let sum: Option<SnailfishNumber> = numbers.iter().sum();
+        assert_eq!(sum.unwrap().to_string(), "[[[[5,0],[7,4]],[5,5]],[6,6]]");
+        assert_eq!(numbers[..0].iter().sum::<Option<SnailfishNumber>>(), None);
+    }
+
+    #[test]
+    fn test_magnitude() {
+        for (text, expected) in [
+            ("[[1,2],[[3,4],5]]", 143),
+            ("[[[[0,7],4],[[7,8],[6,0]]],[8,1]]", 1384),
+            ("[[[[1,1],[2,2]],[3,3]],[4,4]]", 445),
+            ("[[[[3,0],[5,3]],[4,4]],[5,5]]", 791),
+            ("[[[[5,0],[7,4]],[5,5]],[6,6]]", 1137),
+            (
+                "[[[[8,7],[7,7]],[[8,6],[7,7]]],[[[0,7],[6,6]],[8,7]]]",
+                3488,
+            ),
+        ] {
+            assert_eq!(number(text).magnitude(), expected);
+        }
+    }
+
+    #[test]
+    fn test_homework_example() {
+        let numbers: Vec<SnailfishNumber> = HOMEWORK_EXAMPLE.lines().map(number).collect();
+        let sum: SnailfishNumber = numbers.iter().sum::<Option<_>>().unwrap();
+        assert_eq!(
+            sum.to_string(),
+            "[[[[6,6],[7,6]],[[7,7],[7,0]]],[[[7,7],[7,7]],[[7,8],[9,9]]]]"
+        );
+        assert_eq!(sum.magnitude(), 4140);
+        assert_eq!(largest_magnitude_of_pair_sum(&numbers), Some(3993));
+    }
+
+    #[test]
+    fn test_equal_numbers_can_be_paired() {
+        let numbers = [number("[1,1]"), number("[1,1]")];
+        assert_eq!(largest_magnitude_of_pair_sum(&numbers), Some(25));
+        assert_eq!(largest_magnitude_of_pair_sum(&numbers[..1]), None);
+    }
+
+    #[test]
+    fn test_parse_round_trip_and_errors() {
+        for line in HOMEWORK_EXAMPLE.lines() {
+            assert_eq!(number(line).to_string(), line);
+        }
+        assert_eq!(
+            number("[12,[3,45]]").leaves()[2],
+            Leaf {
+                depth: 2,
+                value: 45
+            }
+        );
+        assert_eq!(
+            "[1,2".parse::<SnailfishNumber>(),
+            Err(SnailfishError::UnexpectedEnd {
+                position: 4,
+                expected: "']'"
+            })
+        );
+        assert_eq!(
+            "[1;2]".parse::<SnailfishNumber>(),
+            Err(SnailfishError::UnexpectedChar {
+                position: 2,
+                expected: "','",
+                found: ';'
+            })
+        );
+        assert_eq!(
+            "[1,x]".parse::<SnailfishNumber>(),
+            Err(SnailfishError::UnexpectedChar {
+                position: 3,
+                expected: "'[' or a digit",
+                found: 'x'
+            })
+        );
+        assert_eq!(
+            "[1,2]]".parse::<SnailfishNumber>(),
+            Err(SnailfishError::TrailingText { position: 5 })
+        );
+        assert_eq!(
+            "[1,99999999999]".parse::<SnailfishNumber>(),
+            Err(SnailfishError::ValueTooLarge { position: 3 })
+        );
+        assert_eq!(
+            "[[[[[[1,2],3],4],5],6],7]".parse::<SnailfishNumber>(),
+            Err(SnailfishError::TooDeep { position: 5 })
+        );
+        assert!("7".parse::<SnailfishNumber>().is_err());
+    }
+}
@@ -0,0 +1,31 @@
+//! Day 10 again, using the CPU emulator with the signal-strength probe and the CRT as cycle hooks,
+//! and reading the letters on the CRT with OCR.
+
+use aoc2022::crt::{Cpu, Crt, InstructionSet, SignalStrengthProbe};
+use std::fs;
+use std::process::exit;
+use std::time::Instant;
+
+fn main() {
+    let start_time = Instant::now();
+    let contents = fs::read_to_string("data/day10_input.txt").unwrap();
+    let program = InstructionSet::standard()
+        .parse_program(&contents)
+        .unwrap_or_else(|err| exit_with_error(&err.to_string()));
+    let mut probe = SignalStrengthProbe::DEFAULT;
+    let mut crt = Crt::new();
+    Cpu::new().run(&program, &mut [&mut probe, &mut crt]);
+    let part2_answer = crt
+        .read_letters()
+        .unwrap_or_else(|err| exit_with_error(&format!("{crt}\n\n{err}")));
+    let duration = start_time.elapsed();
+    println!("Part 1 answer: {}", probe.sum());
+    println!("Part 2 answer: {part2_answer}");
+    println!("Duration (including I/O): {:?}", duration);
+    println!("\n{crt}");
+}
+
+fn exit_with_error(message: &str) -> ! {
+    eprintln!("{message}");
+    exit(1);
+}
@@ -0,0 +1,120 @@
+//! Day 5 again, with pluggable crane models and playback of the stacks in the terminal.
+//!
+//! Usage:
+//!     day5_cranes                             Solve both parts
+//!     day5_cranes CRANE                       Rearrange the stacks with another crane
+//!     day5_cranes CRANE --play [DELAY_MS]     Show the stacks after each swing of the crane
+//!
+//! CRANE is 9000, 9001, bounded:CAPACITY or arms:ARM_COUNT:ARM_CAPACITY
+
+use aoc2022::supply_stacks::{
+    parse_input, BoundedCrane, Crane, CrateMover9000, CrateMover9001, MultiArmCrane, Stacks,
+    SupplyStacksError,
+};
+use std::fs;
+use std::process::exit;
+use std::thread;
+use std::time::{Duration, Instant};
+
+const DEFAULT_DELAY_MS: u64 = 200;
+const CLEAR_SCREEN: &str = "\x1b[2J\x1b[H";
+
+fn main() {
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    let contents = fs::read_to_string("data/day5_input.txt").unwrap();
+
+    match args.as_slice() {
+        [] => solve_both_parts(&contents),
+        [crane] => {
+            let crane = parse_crane(crane);
+            let (mut stacks, instructions) = parse_input(&contents).unwrap_or_else(exit_on_error);
+            let top_crates = crane
+                .rearrange(&mut stacks, &instructions)
+                .unwrap_or_else(exit_on_error);
+            println!("{} answer: {top_crates}", crane.name());
+        }
+        [crane, play] if play == "--play" => {
+            play_back(parse_crane(crane).as_ref(), &contents, None)
+        }
+        [crane, play, delay_ms] if play == "--play" => {
+            let delay_ms = delay_ms
+                .parse()
+                .unwrap_or_else(|_| exit_with_error(&format!("Invalid delay {delay_ms:?}")));
+            play_back(parse_crane(crane).as_ref(), &contents, Some(delay_ms))
+        }
+        _ => exit_with_error("Unrecognised arguments. See the usage in the source code."),
+    }
+}
+
+fn solve_both_parts(contents: &str) {
+    let start_time = Instant::now();
+    let (stacks, instructions) = parse_input(contents).unwrap_or_else(exit_on_error);
+    let part1_answer = CrateMover9000
+        .rearrange(&mut stacks.clone(), &instructions)
+        .unwrap_or_else(exit_on_error);
+    let part2_answer = CrateMover9001
+        .rearrange(&mut stacks.clone(), &instructions)
+        .unwrap_or_else(exit_on_error);
+    let duration = start_time.elapsed();
+    println!("Part 1 answer: {part1_answer}");
+    println!("Part 2 answer: {part2_answer}");
+    println!("Duration (including I/O): {:?}", duration);
+}
+
+fn play_back(crane: &dyn Crane, contents: &str, delay_ms: Option<u64>) {
+    let delay = Duration::from_millis(delay_ms.unwrap_or(DEFAULT_DELAY_MS));
+    let (mut stacks, instructions) = parse_input(contents).unwrap_or_else(exit_on_error);
+    let show = |stacks: &Stacks, caption: &str| {
+        println!("{CLEAR_SCREEN}{}\n\n{stacks}\n\n{caption}", crane.name());
+        thread::sleep(delay);
+    };
+    show(&stacks, "Starting position");
+    let mut swing_count = 0;
+    for (index, instruction) in instructions.iter().enumerate() {
+        crane
+            .execute_with_playback(&mut stacks, instruction, &mut |stacks| {
+                swing_count += 1;
+                let caption = format!(
+                    "Instruction {} of {}: move {} from {} to {}\nSwings: {swing_count}",
+                    index + 1,
+                    instructions.len(),
+                    instruction.crate_count,
+                    instruction.src_stack,
+                    instruction.dst_stack
+                );
+                show(stacks, &caption);
+            })
+            .unwrap_or_else(exit_on_error);
+    }
+    println!("Top crates: {}", stacks.top_crates());
+}
+
+fn parse_crane(crane: &str) -> Box<dyn Crane> {
+    let parse_number = |number: &str| {
+        number
+            .parse()
+            .unwrap_or_else(|_| exit_with_error(&format!("Invalid number {number:?}")))
+    };
+    let fields: Vec<&str> = crane.split(':').collect();
+    match fields.as_slice() {
+        ["9000"] => Box::new(CrateMover9000),
+        ["9001"] => Box::new(CrateMover9001),
+        ["bounded", capacity] => {
+            Box::new(BoundedCrane::new(parse_number(capacity)).unwrap_or_else(exit_on_error))
+        }
+        ["arms", arm_count, arm_capacity] => Box::new(
+            MultiArmCrane::new(parse_number(arm_count), parse_number(arm_capacity))
+                .unwrap_or_else(exit_on_error),
+        ),
+        _ => exit_with_error(&format!("Unrecognised crane {crane:?}")),
+    }
+}
+
+fn exit_on_error<T>(err: SupplyStacksError) -> T {
+    exit_with_error(&err.to_string())
+}
+
+fn exit_with_error(message: &str) -> ! {
+    eprintln!("{message}");
+    exit(1);
+}
@@ -0,0 +1,67 @@
+//! Day 7 again, using the in-memory filesystem model.
+//!
+//! Usage:
+//!     day7_filesystem           Solve both parts
+//!     day7_filesystem --tree    Also print the filesystem like the tree command
+//!     day7_filesystem --du      Also print the size of every directory
+//!     day7_filesystem --json    Also print the filesystem as JSON
+
+use aoc2022::filesystem::{FileSystem, TranscriptError};
+use std::fs;
+use std::process::exit;
+use std::time::Instant;
+
+const MAX_DIR_SIZE: u64 = 100_000;
+const TOTAL_DISK_SPACE: u64 = 70_000_000;
+const UNUSED_DISK_SPACE_NEEDED: u64 = 30_000_000;
+
+fn main() {
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    for arg in &args {
+        if !["--tree", "--du", "--json"].contains(&arg.as_str()) {
+            exit_with_error(&format!("Unrecognised argument {arg:?}"));
+        }
+    }
+    let has_flag = |flag: &str| args.iter().any(|arg| arg == flag);
+
+    let start_time = Instant::now();
+    let contents = fs::read_to_string("data/day7_input.txt").unwrap();
+    let file_sys: FileSystem = contents
+        .parse()
+        .unwrap_or_else(|err: TranscriptError| exit_with_error(&err.to_string()));
+    let part1_answer: u64 = file_sys
+        .find_dirs(|size| size <= MAX_DIR_SIZE)
+        .iter()
+        .map(|(_, size)| size)
+        .sum();
+    let space_to_free =
+        (file_sys.total_size() + UNUSED_DISK_SPACE_NEEDED).saturating_sub(TOTAL_DISK_SPACE);
+    let part2_answer = file_sys
+        .find_dirs(|size| size >= space_to_free)
+        .into_iter()
+        .map(|(_, size)| size)
+        .min()
+        .unwrap_or_else(|| exit_with_error("The disk is too small"));
+    let duration = start_time.elapsed();
+
+    if has_flag("--tree") {
+        println!("{file_sys}\n");
+    }
+    if has_flag("--du") {
+        for (path, size) in file_sys.du() {
+            println!("{size}\t{path}");
+        }
+        println!();
+    }
+    if has_flag("--json") {
+        println!("{}\n", serde_json::to_string_pretty(&file_sys).unwrap());
+    }
+    println!("Part 1 answer: {part1_answer}");
+    println!("Part 2 answer: {part2_answer}");
+    println!("Duration (including I/O): {:?}", duration);
+}
+
+fn exit_with_error(message: &str) -> ! {
+    eprintln!("{message}");
+    exit(1);
+}
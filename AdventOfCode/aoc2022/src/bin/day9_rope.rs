@@ -0,0 +1,73 @@
+//! Day 9 again, with one rope simulator for any number of knots.
+//!
+//! Usage:
+//!     day9_rope                   Solve both parts
+//!     day9_rope KNOTS             Count the cells visited by each knot of a rope with KNOTS knots
+//!     day9_rope KNOTS --show      Also draw the rope after each motion, and the cells visited by the tail
+
+use aoc2022::rope::{parse_motions, simulate, Bounds, Motion};
+use std::fs;
+use std::process::exit;
+use std::time::Instant;
+
+const REPETITIONS: u32 = 100;
+
+fn main() {
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    let contents = fs::read_to_string("data/day9_input.txt").unwrap();
+    let motions = parse_motions(&contents).unwrap_or_else(|err| exit_with_error(&err.to_string()));
+
+    let (knot_count, show) = match args.as_slice() {
+        [] => {
+            solve_both_parts(&motions);
+            return;
+        }
+        [knot_count] => (knot_count, false),
+        [knot_count, show] if show == "--show" => (knot_count, true),
+        _ => exit_with_error("Unrecognised arguments. See the usage in the source code."),
+    };
+    let knot_count: usize = knot_count
+        .parse()
+        .unwrap_or_else(|_| exit_with_error(&format!("Invalid knot count {knot_count:?}")));
+    let bounds =
+        Bounds::of_head_path(&motions).unwrap_or_else(|err| exit_with_error(&err.to_string()));
+    let visited = simulate(knot_count, &motions, |motion, rope| {
+        if show {
+            println!(
+                "== {:?} {} ==\n\n{}\n",
+                motion.direction,
+                motion.steps,
+                rope.render(&bounds)
+            );
+        }
+    })
+    .unwrap_or_else(|err| exit_with_error(&err.to_string()));
+    if show {
+        println!("{}\n", visited.last().unwrap().render(&bounds));
+    }
+    for (index, cells) in visited.iter().enumerate() {
+        println!("Knot {index} visited {} cells", cells.count());
+    }
+}
+
+fn solve_both_parts(motions: &[Motion]) {
+    let start_time = Instant::now();
+    let mut answers = (0, 0);
+    for _ in 0..REPETITIONS {
+        let visited = simulate(10, motions, |_, _| {}).unwrap();
+        answers = (visited[1].count(), visited[9].count());
+    }
+    let duration = start_time.elapsed();
+    println!("Part 1 answer: {}", answers.0);
+    println!("Part 2 answer: {}", answers.1);
+    println!(
+        "Avg duration of {} repetitions (excluding I/O): {:?}",
+        REPETITIONS,
+        duration / REPETITIONS
+    );
+}
+
+fn exit_with_error(message: &str) -> ! {
+    eprintln!("{message}");
+    exit(1);
+}
@@ -0,0 +1,372 @@
+//! The CPU and cathode-ray tube from day 10.
+//!
+//! The CPU runs a program one clock cycle at a time, calling hooks during each cycle
+//! with the values of the registers during that cycle.
+//! The signal-strength probe (part 1) and the CRT (part 2) are both hooks.
+//! Instructions are trait objects, so new instructions can be added to an `InstructionSet`.
+
+use aoc_common::ocr::{self, OcrError};
+use std::collections::HashMap;
+use std::fmt;
+use thiserror::Error;
+
+#[derive(Debug, Error, PartialEq, Eq)]
+pub enum CpuError {
+    #[error("line {line_number}: unknown instruction {mnemonic:?}")]
+    UnknownInstruction {
+        line_number: usize,
+        mnemonic: String,
+    },
+    #[error("line {line_number}: invalid operands {operands:?}")]
+    InvalidOperands {
+        line_number: usize,
+        operands: String,
+    },
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Registers {
+    pub x: i64,
+}
+
+impl Default for Registers {
+    fn default() -> Self {
+        Registers { x: 1 }
+    }
+}
+
+pub trait Instruction: fmt::Debug {
+    fn cycle_count(&self) -> u64;
+
+    /// Update the registers at the end of the instruction's last cycle
+    fn complete(&self, registers: &mut Registers);
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Noop;
+
+impl Instruction for Noop {
+    fn cycle_count(&self) -> u64 {
+        1
+    }
+
+    fn complete(&self, _: &mut Registers) {}
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct AddX(pub i64);
+
+impl Instruction for AddX {
+    fn cycle_count(&self) -> u64 {
+        2
+    }
+
+    fn complete(&self, registers: &mut Registers) {
+        registers.x += self.0;
+    }
+}
+
+pub type Program = Vec<Box<dyn Instruction>>;
+
+/// Parses the operands of an instruction, returning None if they are invalid
+pub type InstructionParser = fn(&[&str]) -> Option<Box<dyn Instruction>>;
+
+/// The instructions which a program can use, by mnemonic
+pub struct InstructionSet {
+    parsers: HashMap<String, InstructionParser>,
+}
+
+impl InstructionSet {
+    pub fn empty() -> Self {
+        InstructionSet {
+            parsers: HashMap::new(),
+        }
+    }
+
+    /// The instruction set of the handheld device: noop and addx
+    pub fn standard() -> Self {
+        Self::empty()
+            .with("noop", |operands| match operands {
+                [] => Some(Box::new(Noop)),
+                _ => None,
+            })
+            .with("addx", |operands| match operands {
+                [value] => Some(Box::new(AddX(value.parse().ok()?))),
+                _ => None,
+            })
+    }
+
+    pub fn with(mut self, mnemonic: &str, parser: InstructionParser) -> Self {
+        self.parsers.insert(mnemonic.to_string(), parser);
+        self
+    }
+
+    pub fn parse_program(&self, source: &str) -> Result<Program, CpuError> {
+        source
+            .lines()
+            .enumerate()
+            .filter(|(_, line)| !line.trim().is_empty())
+            .map(|(line_index, line)| {
+                let line_number = line_index + 1;
+                let mut words = line.split_ascii_whitespace();
+                let mnemonic = words.next().unwrap_or_default();
+                let operands: Vec<&str> = words.collect();
+                let parser =
+                    self.parsers
+                        .get(mnemonic)
+                        .ok_or_else(|| CpuError::UnknownInstruction {
+                            line_number,
+                            mnemonic: mnemonic.to_string(),
+                        })?;
+                parser(&operands).ok_or_else(|| CpuError::InvalidOperands {
+                    line_number,
+                    operands: operands.join(" "),
+                })
+            })
+            .collect()
+    }
+}
+
+/// Called during every clock cycle, with the cycle number (starting from 1) and the registers
+pub trait CycleHook {
+    fn during_cycle(&mut self, cycle: u64, registers: &Registers);
+}
+
+impl<F: FnMut(u64, &Registers)> CycleHook for F {
+    fn during_cycle(&mut self, cycle: u64, registers: &Registers) {
+        self(cycle, registers)
+    }
+}
+
+#[derive(Debug, Default)]
+pub struct Cpu {
+    registers: Registers,
+    cycles_completed: u64,
+}
+
+impl Cpu {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn registers(&self) -> &Registers {
+        &self.registers
+    }
+
+    pub fn cycles_completed(&self) -> u64 {
+        self.cycles_completed
+    }
+
+    pub fn run(&mut self, program: &[Box<dyn Instruction>], hooks: &mut [&mut dyn CycleHook]) {
+        for instruction in program {
+            for _ in 0..instruction.cycle_count() {
+                self.cycles_completed += 1;
+                for hook in hooks.iter_mut() {
+                    hook.during_cycle(self.cycles_completed, &self.registers);
+                }
+            }
+            instruction.complete(&mut self.registers);
+        }
+    }
+}
+
+/// Sums cycle * x during the first probed cycle and every interval cycles after it
+pub struct SignalStrengthProbe {
+    first_cycle: u64,
+    interval: u64,
+    sum: i64,
+}
+
+impl SignalStrengthProbe {
+    pub const DEFAULT: SignalStrengthProbe = SignalStrengthProbe::new(20, 40);
+
+    pub const fn new(first_cycle: u64, interval: u64) -> Self {
+        SignalStrengthProbe {
+            first_cycle,
+            interval,
+            sum: 0,
+        }
+    }
+
+    pub fn sum(&self) -> i64 {
+        self.sum
+    }
+}
+
+impl CycleHook for SignalStrengthProbe {
+    fn during_cycle(&mut self, cycle: u64, registers: &Registers) {
+        if cycle >= self.first_cycle && (cycle - self.first_cycle).is_multiple_of(self.interval) {
+            self.sum += cycle as i64 * registers.x;
+        }
+    }
+}
+
+/// Draws one pixel per cycle, row by row. The pixel is lit if the 3 pixel wide sprite,
+/// centred on register x, covers the pixel being drawn.
+/// Once the screen is full, any further cycles draw nothing.
+pub struct Crt {
+    pixels: Vec<bool>,
+}
+
+impl Crt {
+    pub const WIDTH: usize = 40;
+    pub const HEIGHT: usize = 6;
+
+    pub fn new() -> Self {
+        Crt {
+            pixels: Vec::with_capacity(Self::WIDTH * Self::HEIGHT),
+        }
+    }
+
+    pub fn pixels(&self) -> &[bool] {
+        &self.pixels
+    }
+
+    pub fn read_letters(&self) -> Result<String, OcrError> {
+        ocr::read_letters(&self.to_string())
+    }
+}
+
+impl Default for Crt {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl CycleHook for Crt {
+    fn during_cycle(&mut self, cycle: u64, registers: &Registers) {
+        if self.pixels.len() == Self::WIDTH * Self::HEIGHT {
+            return;
+        }
+        let column = ((cycle - 1) % Self::WIDTH as u64) as i64;
+        self.pixels.push((column - registers.x).abs() <= 1);
+    }
+}
+
+/// Shows lit pixels as `#` and dark pixels as `.`, with one line per row
+impl fmt::Display for Crt {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let rows: Vec<String> = self
+            .pixels
+            .chunks(Self::WIDTH)
+            .map(|row| row.iter().map(|&lit| if lit { '#' } else { '.' }).collect())
+            .collect();
+        write!(f, "{}", rows.join("\n"))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SMALL_PROGRAM: &str = "noop\naddx 3\naddx -5\n";
+
+    #[test]
+    fn test_small_program() {
+        let program = InstructionSet::standard()
+            .parse_program(SMALL_PROGRAM)
+            .unwrap();
+        let mut x_values = Vec::new();
+        let mut cpu = Cpu::new();
+        cpu.run(
+            &program,
+            &mut [&mut |_, registers: &Registers| x_values.push(registers.x)],
+        );
+        assert_eq!(x_values, [1, 1, 1, 4, 4]);
+        assert_eq!(cpu.registers().x, -1);
+        assert_eq!(cpu.cycles_completed(), 5);
+    }
+
+    #[test]
+    fn test_signal_strength_probe() {
+        // x is 1 + (cycle - 1) / 2 during each cycle of this program
+        let source = "addx 1\n".repeat(120);
+        let program = InstructionSet::standard().parse_program(&source).unwrap();
+        let mut probe = SignalStrengthProbe::DEFAULT;
+        Cpu::new().run(&program, &mut [&mut probe]);
+        let expected: i64 = [20, 60, 100, 140, 180, 220]
+            .iter()
+            .map(|&cycle| cycle * (1 + (cycle - 1) / 2))
+            .sum();
+        assert_eq!(probe.sum(), expected);
+    }
+
+    /// Run the CRT with x chosen to light exactly the pixels of the letters
+    fn draw_letters(letters: &str) -> (Crt, Vec<bool>) {
+        let image = ocr::render_letters(letters).unwrap();
+        let lit_pixels: Vec<bool> = image
+            .lines()
+            .flat_map(|row| format!("{row:.<40}").into_bytes())
+            .map(|pixel| pixel == b'#')
+            .collect();
+        let mut crt = Crt::new();
+        for (index, &lit) in lit_pixels.iter().enumerate() {
+            let column = (index % Crt::WIDTH) as i64;
+            let x = if lit { column } else { column + 2 };
+            crt.during_cycle(index as u64 + 1, &Registers { x });
+        }
+        (crt, lit_pixels)
+    }
+
+    #[test]
+    fn test_crt_reads_letters() {
+        let (crt, lit_pixels) = draw_letters("PZULBAUA");
+        assert_eq!(crt.pixels(), lit_pixels);
+        assert_eq!(crt.read_letters(), Ok("PZULBAUA".to_string()));
+    }
+
+    #[test]
+    fn test_crt_stops_drawing_when_full() {
+        let (mut crt, lit_pixels) = draw_letters("PZULBAUA");
+        let screen_size = (Crt::WIDTH * Crt::HEIGHT) as u64;
+        for cycle in screen_size + 1..=screen_size + 100 {
+            crt.during_cycle(cycle, &Registers { x: 1 });
+        }
+        assert_eq!(crt.pixels(), lit_pixels);
+        assert_eq!(crt.read_letters(), Ok("PZULBAUA".to_string()));
+    }
+
+    #[derive(Debug)]
+    struct Multiply(i64);
+
+    impl Instruction for Multiply {
+        fn cycle_count(&self) -> u64 {
+            3
+        }
+
+        fn complete(&self, registers: &mut Registers) {
+            registers.x *= self.0;
+        }
+    }
+
+    #[test]
+    fn test_extended_instruction_set() {
+        let instruction_set = InstructionSet::standard().with("mulx", |operands| match operands {
+            [value] => Some(Box::new(Multiply(value.parse().ok()?))),
+            _ => None,
+        });
+        let program = instruction_set
+            .parse_program("addx 2\nmulx 5\nnoop")
+            .unwrap();
+        let mut cpu = Cpu::new();
+        cpu.run(&program, &mut []);
+        assert_eq!(cpu.registers().x, 15);
+        assert_eq!(cpu.cycles_completed(), 6);
+        assert_eq!(
+            InstructionSet::standard()
+                .parse_program("noop\nmulx 5")
+                .err(),
+            Some(CpuError::UnknownInstruction {
+                line_number: 2,
+                mnemonic: "mulx".to_string()
+            })
+        );
+        assert_eq!(
+            InstructionSet::standard().parse_program("addx five").err(),
+            Some(CpuError::InvalidOperands {
+                line_number: 1,
+                operands: "five".to_string()
+            })
+        );
+    }
+}
@@ -0,0 +1,509 @@
+//! An in-memory model of the filesystem from day 7.
+//!
+//! The tree is built by replaying a transcript of `cd` and `ls` commands,
+//! and can be queried for directory sizes (like `du`), searched by size (like `find`),
+//! rendered like the output of `tree`, turned back into a transcript, and serialised as JSON.
+
+use serde::{Deserialize, Serialize};
+use std::collections::btree_map::Entry;
+use std::collections::BTreeMap;
+use std::fmt;
+use std::str::FromStr;
+use thiserror::Error;
+
+#[derive(Debug, Error, PartialEq, Eq)]
+pub enum TranscriptError {
+    #[error("line {line_number}: unrecognised command {line:?}")]
+    UnknownCommand { line_number: usize, line: String },
+    #[error("line {line_number}: {line:?} is not the output of an ls command")]
+    UnexpectedOutput { line_number: usize, line: String },
+    #[error("line {line_number}: invalid ls output {line:?}")]
+    InvalidListing { line_number: usize, line: String },
+    #[error("line {line_number}: cannot cd above the root directory")]
+    AboveRoot { line_number: usize },
+    #[error("line {line_number}: no directory named {name:?} has been listed")]
+    NoSuchDirectory { line_number: usize, name: String },
+    #[error("line {line_number}: {name:?} was listed differently before")]
+    ConflictingListing { line_number: usize, name: String },
+}
+
+/// A name which a transcript could not list, found when deserialising a filesystem
+#[derive(Debug, Error, PartialEq, Eq)]
+#[error("{0:?} is not a valid file or directory name")]
+pub struct InvalidNameError(pub String);
+
+fn is_valid_name(name: &str) -> bool {
+    !(name.is_empty() || name.contains('/') || name == "." || name == "..")
+}
+
+/// A file with its size, or a directory with its children sorted by name
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Node {
+    File(u64),
+    Dir(BTreeMap<String, Node>),
+}
+
+impl Node {
+    pub fn size(&self) -> u64 {
+        match self {
+            Node::File(size) => *size,
+            Node::Dir(children) => children.values().map(Node::size).sum(),
+        }
+    }
+}
+
+/// The children of the root directory. Serialised as a JSON object from names to nodes,
+/// and deserialised only if every name could have come from a transcript.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(into = "BTreeMap<String, Node>", try_from = "BTreeMap<String, Node>")]
+pub struct FileSystem {
+    root: BTreeMap<String, Node>,
+}
+
+impl TryFrom<BTreeMap<String, Node>> for FileSystem {
+    type Error = InvalidNameError;
+
+    fn try_from(root: BTreeMap<String, Node>) -> Result<Self, Self::Error> {
+        fn check_names(children: &BTreeMap<String, Node>) -> Result<(), InvalidNameError> {
+            for (name, child) in children {
+                if !is_valid_name(name) {
+                    return Err(InvalidNameError(name.clone()));
+                }
+                if let Node::Dir(grandchildren) = child {
+                    check_names(grandchildren)?;
+                }
+            }
+            Ok(())
+        }
+
+        check_names(&root)?;
+        Ok(Self { root })
+    }
+}
+
+impl From<FileSystem> for BTreeMap<String, Node> {
+    fn from(file_sys: FileSystem) -> Self {
+        file_sys.root
+    }
+}
+
+impl FileSystem {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn root(&self) -> &BTreeMap<String, Node> {
+        &self.root
+    }
+
+    pub fn total_size(&self) -> u64 {
+        self.root.values().map(Node::size).sum()
+    }
+
+    /// The path and total size of every directory, with each directory after its sub-directories (like `du`)
+    pub fn du(&self) -> Vec<(String, u64)> {
+        let mut usage = Vec::new();
+        collect_dir_sizes(&self.root, "/", &mut usage);
+        usage
+    }
+
+    /// The paths and sizes of the directories whose total size matches the predicate
+    pub fn find_dirs<P: Fn(u64) -> bool>(&self, predicate: P) -> Vec<(String, u64)> {
+        self.du()
+            .into_iter()
+            .filter(|&(_, size)| predicate(size))
+            .collect()
+    }
+
+    /// The paths and sizes of the files whose size matches the predicate
+    pub fn find_files<P: Fn(u64) -> bool>(&self, predicate: P) -> Vec<(String, u64)> {
+        let mut files = Vec::new();
+        collect_files(&self.root, "/", &predicate, &mut files);
+        files
+    }
+
+    /// A transcript which replays to this filesystem, listing each directory before visiting its sub-directories
+    pub fn transcript(&self) -> String {
+        let mut transcript = String::from("$ cd /\n");
+        write_transcript(&self.root, &mut transcript);
+        transcript
+    }
+
+    fn dir_mut(&mut self, path: &[String]) -> &mut BTreeMap<String, Node> {
+        path.iter()
+            .fold(&mut self.root, |dir, name| match dir.get_mut(name) {
+                Some(Node::Dir(children)) => children,
+                _ => unreachable!("only listed directories can be in the path"),
+            })
+    }
+
+    fn add_listing(&mut self, path: &[String], name: &str, node: Node) -> Result<(), String> {
+        match self.dir_mut(path).entry(name.to_string()) {
+            Entry::Vacant(entry) => {
+                entry.insert(node);
+                Ok(())
+            }
+            Entry::Occupied(entry) => match (entry.get(), &node) {
+                (Node::Dir(_), Node::Dir(_)) => Ok(()),
+                (Node::File(old_size), Node::File(new_size)) if old_size == new_size => Ok(()),
+                _ => Err(name.to_string()),
+            },
+        }
+    }
+}
+
+fn join_path(parent_path: &str, name: &str) -> String {
+    if parent_path == "/" {
+        format!("/{name}")
+    } else {
+        format!("{parent_path}/{name}")
+    }
+}
+
+fn collect_dir_sizes(
+    children: &BTreeMap<String, Node>,
+    path: &str,
+    usage: &mut Vec<(String, u64)>,
+) -> u64 {
+    let size = children
+        .iter()
+        .map(|(name, child)| match child {
+            Node::File(size) => *size,
+            Node::Dir(grandchildren) => {
+                collect_dir_sizes(grandchildren, &join_path(path, name), usage)
+            }
+        })
+        .sum();
+    usage.push((path.to_string(), size));
+    size
+}
+
+fn collect_files<P: Fn(u64) -> bool>(
+    children: &BTreeMap<String, Node>,
+    path: &str,
+    predicate: &P,
+    files: &mut Vec<(String, u64)>,
+) {
+    for (name, child) in children {
+        match child {
+            Node::File(size) if predicate(*size) => files.push((join_path(path, name), *size)),
+            Node::File(_) => {}
+            Node::Dir(grandchildren) => {
+                collect_files(grandchildren, &join_path(path, name), predicate, files)
+            }
+        }
+    }
+}
+
+fn write_transcript(children: &BTreeMap<String, Node>, transcript: &mut String) {
+    transcript.push_str("$ ls\n");
+    for (name, child) in children {
+        match child {
+            Node::File(size) => transcript.push_str(&format!("{size} {name}\n")),
+            Node::Dir(_) => transcript.push_str(&format!("dir {name}\n")),
+        }
+    }
+    for (name, child) in children {
+        if let Node::Dir(grandchildren) = child {
+            transcript.push_str(&format!("$ cd {name}\n"));
+            write_transcript(grandchildren, transcript);
+            transcript.push_str("$ cd ..\n");
+        }
+    }
+}
+
+impl FromStr for FileSystem {
+    type Err = TranscriptError;
+
+    fn from_str(transcript: &str) -> Result<Self, Self::Err> {
+        let mut file_sys = FileSystem::new();
+        let mut path: Vec<String> = Vec::new();
+        let mut is_listing = false;
+
+        for (line_index, line) in transcript.lines().enumerate() {
+            let line_number = line_index + 1;
+            if let Some(command) = line.strip_prefix("$ ") {
+                is_listing = false;
+                match command.split_once(' ') {
+                    None if command == "ls" => is_listing = true,
+                    Some(("cd", "/")) => path.clear(),
+                    Some(("cd", "..")) => {
+                        path.pop()
+                            .ok_or(TranscriptError::AboveRoot { line_number })?;
+                    }
+                    Some(("cd", name)) => match file_sys.dir_mut(&path).get(name) {
+                        Some(Node::Dir(_)) => path.push(name.to_string()),
+                        _ => {
+                            return Err(TranscriptError::NoSuchDirectory {
+                                line_number,
+                                name: name.to_string(),
+                            })
+                        }
+                    },
+                    _ => {
+                        return Err(TranscriptError::UnknownCommand {
+                            line_number,
+                            line: line.to_string(),
+                        })
+                    }
+                }
+                continue;
+            }
+
+            if !is_listing {
+                return Err(TranscriptError::UnexpectedOutput {
+                    line_number,
+                    line: line.to_string(),
+                });
+            }
+            let invalid_listing = || TranscriptError::InvalidListing {
+                line_number,
+                line: line.to_string(),
+            };
+            let (dir_or_size, name) = line.split_once(' ').ok_or_else(invalid_listing)?;
+            if !is_valid_name(name) {
+                return Err(invalid_listing());
+            }
+            let node = if dir_or_size == "dir" {
+                Node::Dir(BTreeMap::new())
+            } else {
+                Node::File(dir_or_size.parse().map_err(|_| invalid_listing())?)
+            };
+            file_sys
+                .add_listing(&path, name, node)
+                .map_err(|name| TranscriptError::ConflictingListing { line_number, name })?;
+        }
+        Ok(file_sys)
+    }
+}
+
+/// Renders the filesystem like the `tree` command, with file sizes in brackets
+impl fmt::Display for FileSystem {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fn write_children(
+            f: &mut fmt::Formatter<'_>,
+            children: &BTreeMap<String, Node>,
+            indent: &str,
+            counts: &mut (usize, usize),
+        ) -> fmt::Result {
+            for (index, (name, child)) in children.iter().enumerate() {
+                let is_last = index + 1 == children.len();
+                let branch = if is_last { "└── " } else { "├── " };
+                match child {
+                    Node::File(size) => {
+                        counts.1 += 1;
+                        writeln!(f, "{indent}{branch}[{size:>10}]  {name}")?;
+                    }
+                    Node::Dir(grandchildren) => {
+                        counts.0 += 1;
+                        writeln!(f, "{indent}{branch}{name}")?;
+                        let child_indent = if is_last { "    " } else { "│   " };
+                        write_children(
+                            f,
+                            grandchildren,
+                            &(indent.to_string() + child_indent),
+                            counts,
+                        )?;
+                    }
+                }
+            }
+            Ok(())
+        }
+
+        writeln!(f, "/")?;
+        let mut counts = (0, 0);
+        write_children(f, &self.root, "", &mut counts)?;
+        let (dir_count, file_count) = counts;
+        write!(
+            f,
+            "\n{dir_count} director{}, {file_count} file{}",
+            if dir_count == 1 { "y" } else { "ies" },
+            if file_count == 1 { "" } else { "s" }
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const EXAMPLE: &str = "\
+$ cd /
+$ ls
+dir a
+14848514 b.txt
+8504156 c.dat
+dir d
+$ cd a
+$ ls
+dir e
+29116 f
+2557 g
+62596 h.lst
+$ cd e
+$ ls
+584 i
+$ cd ..
+$ cd ..
+$ cd d
+$ ls
+4060174 j
+8033020 d.log
+5626152 d.ext
+7214296 k
+";
+
+    #[test]
+    fn test_example_du() {
+        let file_sys: FileSystem = EXAMPLE.parse().unwrap();
+        assert_eq!(
+            file_sys.du(),
+            [
+                ("/a/e".to_string(), 584),
+                ("/a".to_string(), 94853),
+                ("/d".to_string(), 24933642),
+                ("/".to_string(), 48381165)
+            ]
+        );
+        assert_eq!(file_sys.total_size(), 48381165);
+    }
+
+    #[test]
+    fn test_example_find() {
+        let file_sys: FileSystem = EXAMPLE.parse().unwrap();
+        let small_dirs = file_sys.find_dirs(|size| size <= 100_000);
+        assert_eq!(small_dirs.iter().map(|(_, size)| size).sum::<u64>(), 95437);
+        let space_to_free = file_sys.total_size() - 40_000_000;
+        let dir_to_delete = file_sys
+            .find_dirs(|size| size >= space_to_free)
+            .into_iter()
+            .min_by_key(|&(_, size)| size);
+        assert_eq!(dir_to_delete, Some(("/d".to_string(), 24933642)));
+        assert_eq!(
+            file_sys.find_files(|size| size < 3000),
+            [("/a/e/i".to_string(), 584), ("/a/g".to_string(), 2557)]
+        );
+    }
+
+    #[test]
+    fn test_tree_rendering() {
+        let file_sys: FileSystem = "$ cd /\n$ ls\ndir a\n12 b.txt\n$ cd a\n$ ls\n584 i\n"
+            .parse()
+            .unwrap();
+        assert_eq!(
+            file_sys.to_string(),
+            "\
+/
+├── a
+│   └── [       584]  i
+└── [        12]  b.txt
+
+1 directory, 2 files"
+        );
+    }
+
+    #[test]
+    fn test_json() {
+        let file_sys: FileSystem = "$ cd /\n$ ls\ndir a\n12 b.txt\n$ cd a\n$ ls\n584 i\n"
+            .parse()
+            .unwrap();
+        let json = serde_json::to_string(&file_sys).unwrap();
+        assert_eq!(
+            json,
+            r#"{"a":{"dir":{"i":{"file":584}}},"b.txt":{"file":12}}"#
+        );
+        assert_eq!(serde_json::from_str::<FileSystem>(&json).unwrap(), file_sys);
+        for bad_name in ["", ".", "..", "a/b"] {
+            let json = format!(r#"{{"a":{{"dir":{{"{bad_name}":{{"file":1}}}}}}}}"#);
+            let error = serde_json::from_str::<FileSystem>(&json).unwrap_err();
+            assert!(error
+                .to_string()
+                .contains("not a valid file or directory name"));
+        }
+    }
+
+    #[test]
+    fn test_transcript_errors() {
+        assert_eq!(
+            "$ cd /\n$ cd ..".parse::<FileSystem>(),
+            Err(TranscriptError::AboveRoot { line_number: 2 })
+        );
+        assert_eq!(
+            "$ cd a".parse::<FileSystem>(),
+            Err(TranscriptError::NoSuchDirectory {
+                line_number: 1,
+                name: "a".to_string()
+            })
+        );
+        assert_eq!(
+            "$ ls\n12 a\n$ cd a".parse::<FileSystem>(),
+            Err(TranscriptError::NoSuchDirectory {
+                line_number: 3,
+                name: "a".to_string()
+            })
+        );
+        assert_eq!(
+            "$ ls\ndir a\n$ ls\n12 a".parse::<FileSystem>(),
+            Err(TranscriptError::ConflictingListing {
+                line_number: 4,
+                name: "a".to_string()
+            })
+        );
+        assert_eq!(
+            "$ cd /\n12 a".parse::<FileSystem>(),
+            Err(TranscriptError::UnexpectedOutput {
+                line_number: 2,
+                line: "12 a".to_string()
+            })
+        );
+        assert!(matches!(
+            "$ rm -rf /".parse::<FileSystem>(),
+            Err(TranscriptError::UnknownCommand { line_number: 1, .. })
+        ));
+        assert!(matches!(
+            "$ ls\nbig a".parse::<FileSystem>(),
+            Err(TranscriptError::InvalidListing { line_number: 2, .. })
+        ));
+    }
+
+    mod prop_tests {
+        use super::*;
+        use proptest::collection::btree_map;
+        use proptest::prelude::*;
+
+        fn arb_node() -> impl Strategy<Value = Node> {
+            let leaf = (0..1_000_000_u64).prop_map(Node::File);
+            leaf.prop_recursive(4, 64, 5, |inner| {
+                btree_map("[a-z]{1,3}(\\.[a-z]{1,3})?", inner, 0..5).prop_map(Node::Dir)
+            })
+        }
+
+        fn arb_file_system() -> impl Strategy<Value = FileSystem> {
+            btree_map("[a-z]{1,3}(\\.[a-z]{1,3})?", arb_node(), 0..6)
+                .prop_map(|root| FileSystem { root })
+        }
+
+        proptest! {
+            #[test]
+            fn replaying_the_transcript_reproduces_the_tree(file_sys in arb_file_system()) {
+                let replayed: FileSystem = file_sys.transcript().parse().unwrap();
+                prop_assert_eq!(replayed, file_sys);
+            }
+
+            #[test]
+            fn json_round_trip_gives_the_same_tree(file_sys in arb_file_system()) {
+                let json = serde_json::to_string(&file_sys).unwrap();
+                prop_assert_eq!(serde_json::from_str::<FileSystem>(&json).unwrap(), file_sys);
+            }
+
+            #[test]
+            fn root_is_last_in_du_with_the_total_size(file_sys in arb_file_system()) {
+                let usage = file_sys.du();
+                prop_assert_eq!(usage.last(), Some(&("/".to_string(), file_sys.total_size())));
+                let file_total: u64 = file_sys.find_files(|_| true).iter().map(|(_, size)| size).sum();
+                prop_assert_eq!(file_total, file_sys.total_size());
+            }
+        }
+    }
+}
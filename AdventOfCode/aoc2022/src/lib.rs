@@ -1,3 +1,7 @@
+pub mod crt;
+pub mod filesystem;
+pub mod rope;
+pub mod supply_stacks;
 
 #[cfg(test)]
 mod tests {
@@ -0,0 +1,485 @@
+//! The rope from day 9, with any number of knots.
+//!
+//! The head can also move diagonally (`UR`, `UL`, `DR` and `DL`).
+//! Every knot follows the knot in front of it, so no knot can leave the bounding box of the head's path.
+//! The cells visited by each knot are recorded in a bitmap covering that bounding box.
+
+use std::str::FromStr;
+use thiserror::Error;
+
+/// (x, y), with y increasing upwards
+pub type Pos = (i32, i32);
+
+/// The most cells a bitmap of visited cells may cover (128 MiB for each knot)
+pub const MAX_CELLS: usize = 1 << 30;
+
+#[derive(Debug, Error, PartialEq, Eq)]
+pub enum MotionError {
+    #[error("a motion needs a direction and a number of steps")]
+    MissingSteps,
+    #[error("unknown direction {0:?}")]
+    UnknownDirection(String),
+    #[error("invalid number of steps {0:?}")]
+    InvalidSteps(String),
+}
+
+#[derive(Debug, Error, PartialEq, Eq)]
+pub enum RopeError {
+    #[error("line {line_number}: invalid motion {line:?}: {source}")]
+    InvalidMotion {
+        line_number: usize,
+        line: String,
+        source: MotionError,
+    },
+    #[error("a rope needs at least one knot")]
+    NoKnots,
+    #[error("the head moves too far from the start")]
+    HeadTooFar,
+    #[error("the head's path covers {width}x{height} cells, more than the limit of {MAX_CELLS}")]
+    TooManyCells { width: usize, height: usize },
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Direction {
+    Up,
+    Down,
+    Left,
+    Right,
+    UpLeft,
+    UpRight,
+    DownLeft,
+    DownRight,
+}
+
+impl Direction {
+    pub fn delta(self) -> Pos {
+        match self {
+            Direction::Up => (0, 1),
+            Direction::Down => (0, -1),
+            Direction::Left => (-1, 0),
+            Direction::Right => (1, 0),
+            Direction::UpLeft => (-1, 1),
+            Direction::UpRight => (1, 1),
+            Direction::DownLeft => (-1, -1),
+            Direction::DownRight => (1, -1),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Motion {
+    pub direction: Direction,
+    pub steps: u32,
+}
+
+impl FromStr for Motion {
+    type Err = MotionError;
+
+    fn from_str(motion_str: &str) -> Result<Self, Self::Err> {
+        let (direction_str, steps_str) = motion_str
+            .split_once(' ')
+            .ok_or(MotionError::MissingSteps)?;
+        let direction = match direction_str {
+            "U" => Direction::Up,
+            "D" => Direction::Down,
+            "L" => Direction::Left,
+            "R" => Direction::Right,
+            "UL" => Direction::UpLeft,
+            "UR" => Direction::UpRight,
+            "DL" => Direction::DownLeft,
+            "DR" => Direction::DownRight,
+            _ => return Err(MotionError::UnknownDirection(direction_str.to_string())),
+        };
+        let steps = steps_str
+            .parse()
+            .map_err(|_| MotionError::InvalidSteps(steps_str.to_string()))?;
+        Ok(Motion { direction, steps })
+    }
+}
+
+pub fn parse_motions(contents: &str) -> Result<Vec<Motion>, RopeError> {
+    contents
+        .lines()
+        .enumerate()
+        .map(|(line_index, line)| {
+            line.parse().map_err(|source| RopeError::InvalidMotion {
+                line_number: line_index + 1,
+                line: line.to_string(),
+                source,
+            })
+        })
+        .collect()
+}
+
+/// An inclusive rectangle of cells
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Bounds {
+    pub min: Pos,
+    pub max: Pos,
+}
+
+impl Bounds {
+    /// The cells which the head passes through, starting from the origin.
+    ///
+    /// # Errors
+    ///
+    /// Returns `RopeError::HeadTooFar` if the head leaves the range of an `i32`.
+    pub fn of_head_path(motions: &[Motion]) -> Result<Self, RopeError> {
+        let mut head: Pos = (0, 0);
+        let mut bounds = Bounds {
+            min: head,
+            max: head,
+        };
+        for motion in motions {
+            let (dx, dy) = motion.direction.delta();
+            let steps = i32::try_from(motion.steps).map_err(|_| RopeError::HeadTooFar)?;
+            let move_by = |coord: i32, delta: i32| {
+                delta
+                    .checked_mul(steps)
+                    .and_then(|offset| coord.checked_add(offset))
+                    .ok_or(RopeError::HeadTooFar)
+            };
+            head = (move_by(head.0, dx)?, move_by(head.1, dy)?);
+            bounds.min = (bounds.min.0.min(head.0), bounds.min.1.min(head.1));
+            bounds.max = (bounds.max.0.max(head.0), bounds.max.1.max(head.1));
+        }
+        Ok(bounds)
+    }
+
+    pub fn width(&self) -> usize {
+        span(self.min.0, self.max.0)
+    }
+
+    pub fn height(&self) -> usize {
+        span(self.min.1, self.max.1)
+    }
+
+    pub fn contains(&self, (x, y): Pos) -> bool {
+        (self.min.0..=self.max.0).contains(&x) && (self.min.1..=self.max.1).contains(&y)
+    }
+
+    /// Draw a character for each cell, with the highest row first, as in the puzzle text
+    fn render<F: Fn(Pos) -> char>(&self, cell_char: F) -> String {
+        (self.min.1..=self.max.1)
+            .rev()
+            .map(|y| {
+                (self.min.0..=self.max.0)
+                    .map(|x| cell_char((x, y)))
+                    .collect::<String>()
+            })
+            .collect::<Vec<_>>()
+            .join("\n")
+    }
+}
+
+/// The number of values from min to max inclusive, which is 0 if max is less than min
+fn span(min: i32, max: i32) -> usize {
+    usize::try_from(i64::from(max) - i64::from(min) + 1).unwrap_or(0)
+}
+
+/// A set of cells within fixed bounds, with one bit per cell
+#[derive(Debug, Clone)]
+pub struct VisitedCells {
+    bounds: Bounds,
+    bits: Vec<u64>,
+    count: usize,
+}
+
+impl VisitedCells {
+    /// An empty set of cells within the bounds.
+    ///
+    /// # Errors
+    ///
+    /// Returns `RopeError::TooManyCells` if the bounds cover more than `MAX_CELLS` cells.
+    pub fn new(bounds: Bounds) -> Result<Self, RopeError> {
+        let (width, height) = (bounds.width(), bounds.height());
+        let cell_count = width
+            .checked_mul(height)
+            .filter(|&cell_count| cell_count <= MAX_CELLS)
+            .ok_or(RopeError::TooManyCells { width, height })?;
+        Ok(VisitedCells {
+            bounds,
+            bits: vec![0; cell_count.div_ceil(64)],
+            count: 0,
+        })
+    }
+
+    fn bit_index(&self, (x, y): Pos) -> usize {
+        (y - self.bounds.min.1) as usize * self.bounds.width() + (x - self.bounds.min.0) as usize
+    }
+
+    /// Add the cell (which must be within the bounds), returning whether it was not visited before
+    pub fn insert(&mut self, pos: Pos) -> bool {
+        debug_assert!(self.bounds.contains(pos));
+        let index = self.bit_index(pos);
+        let mask = 1 << (index % 64);
+        let word = &mut self.bits[index / 64];
+        let is_new = *word & mask == 0;
+        if is_new {
+            *word |= mask;
+            self.count += 1;
+        }
+        is_new
+    }
+
+    pub fn contains(&self, pos: Pos) -> bool {
+        if !self.bounds.contains(pos) {
+            return false;
+        }
+        let index = self.bit_index(pos);
+        self.bits[index / 64] & (1 << (index % 64)) != 0
+    }
+
+    pub fn count(&self) -> usize {
+        self.count
+    }
+
+    /// Draw the visited cells as `#` and the starting cell as `s`, as in the puzzle text
+    pub fn render(&self, bounds: &Bounds) -> String {
+        bounds.render(|pos| match pos {
+            (0, 0) => 's',
+            _ if self.contains(pos) => '#',
+            _ => '.',
+        })
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Rope {
+    knots: Vec<Pos>,
+}
+
+impl Rope {
+    /// A rope with all its knots at the origin
+    pub fn new(knot_count: usize) -> Result<Self, RopeError> {
+        if knot_count == 0 {
+            return Err(RopeError::NoKnots);
+        }
+        Ok(Rope {
+            knots: vec![(0, 0); knot_count],
+        })
+    }
+
+    /// The positions of the knots, from the head to the tail
+    pub fn knots(&self) -> &[Pos] {
+        &self.knots
+    }
+
+    /// Move the head one step, and then move each knot which is no longer touching the knot in front of it
+    pub fn step(&mut self, direction: Direction) {
+        let (dx, dy) = direction.delta();
+        self.knots[0] = (self.knots[0].0 + dx, self.knots[0].1 + dy);
+        for index in 1..self.knots.len() {
+            let (lead_x, lead_y) = self.knots[index - 1];
+            let (x, y) = &mut self.knots[index];
+            if (lead_x - *x).abs() <= 1 && (lead_y - *y).abs() <= 1 {
+                break;
+            }
+            *x += (lead_x - *x).signum();
+            *y += (lead_y - *y).signum();
+        }
+    }
+
+    /// Label each knot as in the puzzle text: `H` for the head, then the index of the knot,
+    /// except that the tail of a rope with 2 knots is `T`.
+    /// Knots nearer the head are drawn over knots further back, and any knot is drawn over the start `s`.
+    pub fn render(&self, bounds: &Bounds) -> String {
+        bounds.render(
+            |pos| match self.knots.iter().position(|&knot| knot == pos) {
+                Some(0) => 'H',
+                Some(_) if self.knots.len() == 2 => 'T',
+                Some(index) => char::from_digit(index as u32, 36).unwrap_or('#'),
+                None if pos == (0, 0) => 's',
+                None => '.',
+            },
+        )
+    }
+}
+
+/// Move a rope with knot_count knots through the motions, calling after_motion with the rope after each motion,
+/// and return the cells visited by each knot.
+pub fn simulate<F>(
+    knot_count: usize,
+    motions: &[Motion],
+    mut after_motion: F,
+) -> Result<Vec<VisitedCells>, RopeError>
+where
+    F: FnMut(&Motion, &Rope),
+{
+    let mut rope = Rope::new(knot_count)?;
+    let bounds = Bounds::of_head_path(motions)?;
+    let mut visited = vec![VisitedCells::new(bounds)?; knot_count];
+    for cells in visited.iter_mut() {
+        cells.insert((0, 0));
+    }
+    for motion in motions {
+        for _ in 0..motion.steps {
+            rope.step(motion.direction);
+            for (cells, &knot) in visited.iter_mut().zip(rope.knots()) {
+                cells.insert(knot);
+            }
+        }
+        after_motion(motion, &rope);
+    }
+    Ok(visited)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashSet;
+
+    const EXAMPLE: &str = "R 4\nU 4\nL 3\nD 1\nR 4\nD 1\nL 5\nR 2\n";
+    const LARGER_EXAMPLE: &str = "R 5\nU 8\nL 8\nD 3\nR 17\nD 10\nL 25\nU 20\n";
+
+    fn tail_visit_count(contents: &str, knot_count: usize) -> usize {
+        let motions = parse_motions(contents).unwrap();
+        let visited = simulate(knot_count, &motions, |_, _| {}).unwrap();
+        visited.last().unwrap().count()
+    }
+
+    #[test]
+    fn test_examples() {
+        assert_eq!(tail_visit_count(EXAMPLE, 2), 13);
+        assert_eq!(tail_visit_count(EXAMPLE, 10), 1);
+        assert_eq!(tail_visit_count(LARGER_EXAMPLE, 10), 36);
+    }
+
+    #[test]
+    fn test_rendering_as_in_puzzle_text() {
+        let motions = parse_motions(EXAMPLE).unwrap();
+        let bounds = Bounds::of_head_path(&motions).unwrap();
+        let mut frames = Vec::new();
+        let visited = simulate(2, &motions, |_, rope| frames.push(rope.render(&bounds))).unwrap();
+        assert_eq!(frames[0], "......\n......\n......\n......\ns..TH.");
+        assert_eq!(frames[7], "......\n......\n.TH...\n......\ns.....");
+        assert_eq!(
+            visited[1].render(&bounds),
+            "..##..\n...##.\n.####.\n....#.\ns###.."
+        );
+    }
+
+    #[test]
+    fn test_rendering_knot_numbers() {
+        let motions = parse_motions("R 4\nU 4").unwrap();
+        let bounds = Bounds::of_head_path(&motions).unwrap();
+        let mut frames = Vec::new();
+        simulate(10, &motions, |_, rope| frames.push(rope.render(&bounds))).unwrap();
+        assert_eq!(frames[1], "....H\n....1\n..432\n.5...\n6....");
+    }
+
+    /// The original approach, with a hash set of the cells visited by the tail
+    fn tail_visit_count_with_hash_set(motions: &[Motion], knot_count: usize) -> usize {
+        let mut rope = Rope::new(knot_count).unwrap();
+        let mut tail_cells_visited = HashSet::from([(0, 0)]);
+        for motion in motions {
+            for _ in 0..motion.steps {
+                rope.step(motion.direction);
+                tail_cells_visited.insert(*rope.knots().last().unwrap());
+            }
+        }
+        tail_cells_visited.len()
+    }
+
+    #[test]
+    fn test_diagonal_moves() {
+        let motions = parse_motions("UR 3\nDL 1\nDR 2").unwrap();
+        let visited = simulate(3, &motions, |_, _| {}).unwrap();
+        let tail_cells: Vec<Pos> = [(0, 0), (1, 1), (2, 1), (2, 2)]
+            .into_iter()
+            .filter(|&pos| visited[2].contains(pos))
+            .collect();
+        assert_eq!(tail_cells, [(0, 0), (1, 1), (2, 1)]);
+        assert_eq!(visited[2].count(), 3);
+        assert_eq!(
+            visited[0].count(),
+            tail_visit_count_with_hash_set(&motions, 1)
+        );
+    }
+
+    #[test]
+    fn test_invalid_input() {
+        assert_eq!(
+            parse_motions("R 4\nX 1"),
+            Err(RopeError::InvalidMotion {
+                line_number: 2,
+                line: "X 1".to_string(),
+                source: MotionError::UnknownDirection("X".to_string())
+            })
+        );
+        assert_eq!("R".parse::<Motion>(), Err(MotionError::MissingSteps));
+        assert_eq!(
+            "R -1".parse::<Motion>(),
+            Err(MotionError::InvalidSteps("-1".to_string()))
+        );
+        assert_eq!(Rope::new(0), Err(RopeError::NoKnots));
+    }
+
+    #[test]
+    fn test_paths_too_large_for_the_bitmap() {
+        let motions = parse_motions("R 3000000000").unwrap();
+        assert_eq!(Bounds::of_head_path(&motions), Err(RopeError::HeadTooFar));
+        let motions = parse_motions("R 2000000000\nR 2000000000").unwrap();
+        assert_eq!(Bounds::of_head_path(&motions), Err(RopeError::HeadTooFar));
+        let motions = parse_motions("UR 40000\nDL 80000").unwrap();
+        assert_eq!(
+            simulate(2, &motions, |_, _| {}).unwrap_err(),
+            RopeError::TooManyCells {
+                width: 80001,
+                height: 80001
+            }
+        );
+        let motions = parse_motions("R 2000000000\nL 2000000000\nL 2000000000").unwrap();
+        let bounds = Bounds::of_head_path(&motions).unwrap();
+        assert_eq!(bounds.width(), 4_000_000_001);
+        assert!(VisitedCells::new(bounds).is_err());
+    }
+
+    mod prop_tests {
+        use super::*;
+        use proptest::prelude::*;
+
+        fn arb_motion() -> impl Strategy<Value = Motion> {
+            let direction = prop_oneof![
+                Just(Direction::Up),
+                Just(Direction::Down),
+                Just(Direction::Left),
+                Just(Direction::Right),
+                Just(Direction::UpLeft),
+                Just(Direction::UpRight),
+                Just(Direction::DownLeft),
+                Just(Direction::DownRight),
+            ];
+            (direction, 0..10_u32).prop_map(|(direction, steps)| Motion { direction, steps })
+        }
+
+        proptest! {
+            #[test]
+            fn bitmap_counts_the_same_cells_as_a_hash_set(
+                motions in proptest::collection::vec(arb_motion(), 0..40),
+                knot_count in 1..12_usize,
+            ) {
+                let visited = simulate(knot_count, &motions, |_, _| {}).unwrap();
+                prop_assert_eq!(
+                    visited.last().unwrap().count(),
+                    tail_visit_count_with_hash_set(&motions, knot_count)
+                );
+            }
+
+            #[test]
+            fn knots_stay_touching_the_knot_in_front(
+                motions in proptest::collection::vec(arb_motion(), 0..40),
+                knot_count in 1..12_usize,
+            ) {
+                let mut all_touching = true;
+                simulate(knot_count, &motions, |_, rope| {
+                    all_touching &= rope.knots().windows(2).all(|pair| {
+                        (pair[0].0 - pair[1].0).abs() <= 1 && (pair[0].1 - pair[1].1).abs() <= 1
+                    });
+                })
+                .unwrap();
+                prop_assert!(all_touching);
+            }
+        }
+    }
+}
@@ -0,0 +1,487 @@
+//! The stacks of crates from day 5, with the cranes which rearrange them.
+//!
+//! Each crane model carries out an instruction as a sequence of swings,
+//! so that the stacks can be played back after every swing.
+//! The stacks render in the same drawing format that they are parsed from.
+
+use std::fmt;
+use std::str::FromStr;
+use thiserror::Error;
+
+#[derive(Debug, Error, PartialEq, Eq)]
+pub enum SupplyStacksError {
+    #[error("the drawing of the stacks has no line of stack numbers")]
+    MissingStackNumbers,
+    #[error("expected a blank line between the drawing and the instructions")]
+    MissingInstructions,
+    #[error("invalid instruction {0:?}")]
+    InvalidInstruction(String),
+    #[error("there is no stack {stack} (there are {stack_count} stacks)")]
+    NoSuchStack { stack: usize, stack_count: usize },
+    #[error("cannot move {crate_count} crates from stack {stack}, which only has {available}")]
+    NotEnoughCrates {
+        stack: usize,
+        crate_count: usize,
+        available: usize,
+    },
+    #[error("a crane must be able to carry at least one crate")]
+    ZeroCapacity,
+}
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct Instruction {
+    pub crate_count: usize,
+    /// Stacks are numbered from 1, as in the drawing
+    pub src_stack: usize,
+    pub dst_stack: usize,
+}
+
+impl FromStr for Instruction {
+    type Err = SupplyStacksError;
+
+    fn from_str(instruction_str: &str) -> Result<Self, Self::Err> {
+        let invalid = || SupplyStacksError::InvalidInstruction(instruction_str.to_string());
+        let words: Vec<&str> = instruction_str.split_ascii_whitespace().collect();
+        match words.as_slice() {
+            ["move", crate_count, "from", src_stack, "to", dst_stack] => Ok(Instruction {
+                crate_count: crate_count.parse().map_err(|_| invalid())?,
+                src_stack: src_stack.parse().map_err(|_| invalid())?,
+                dst_stack: dst_stack.parse().map_err(|_| invalid())?,
+            }),
+            _ => Err(invalid()),
+        }
+    }
+}
+
+/// The crates in each stack, from the bottom of the stack to the top
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Stacks(Vec<Vec<char>>);
+
+impl Stacks {
+    pub fn new(stacks: Vec<Vec<char>>) -> Self {
+        Stacks(stacks)
+    }
+
+    pub fn stacks(&self) -> &[Vec<char>] {
+        &self.0
+    }
+
+    /// The crate on top of each stack, skipping empty stacks
+    pub fn top_crates(&self) -> String {
+        self.0.iter().filter_map(|stack| stack.last()).collect()
+    }
+
+    fn check(&self, instruction: &Instruction) -> Result<(), SupplyStacksError> {
+        let stack_count = self.0.len();
+        for stack in [instruction.src_stack, instruction.dst_stack] {
+            if !(1..=stack_count).contains(&stack) {
+                return Err(SupplyStacksError::NoSuchStack { stack, stack_count });
+            }
+        }
+        let available = self.0[instruction.src_stack - 1].len();
+        if instruction.crate_count > available {
+            return Err(SupplyStacksError::NotEnoughCrates {
+                stack: instruction.src_stack,
+                crate_count: instruction.crate_count,
+                available,
+            });
+        }
+        Ok(())
+    }
+
+    /// Lift the top crates off a stack (numbered from 0), keeping them in the same order
+    pub fn take(&mut self, stack_index: usize, crate_count: usize) -> Vec<char> {
+        let stack = &mut self.0[stack_index];
+        stack.split_off(stack.len() - crate_count)
+    }
+
+    /// Set crates down on a stack (numbered from 0), with the last crate on top
+    pub fn put(&mut self, stack_index: usize, crates: Vec<char>) {
+        self.0[stack_index].extend(crates);
+    }
+}
+
+impl FromStr for Stacks {
+    type Err = SupplyStacksError;
+
+    fn from_str(drawing: &str) -> Result<Self, Self::Err> {
+        let mut stack_lines: Vec<&str> = drawing.lines().collect();
+        let stack_numbers_line = stack_lines
+            .pop()
+            .ok_or(SupplyStacksError::MissingStackNumbers)?;
+        let stack_count = stack_numbers_line.split_ascii_whitespace().count();
+        if stack_count == 0 {
+            return Err(SupplyStacksError::MissingStackNumbers);
+        }
+        let mut stacks = vec![Vec::with_capacity(stack_lines.len()); stack_count];
+        for stack_line in stack_lines.iter().rev() {
+            let stack_line_bytes = stack_line.as_bytes();
+            for (i, stack) in stacks.iter_mut().enumerate() {
+                match stack_line_bytes.get(1 + 4 * i) {
+                    Some(b' ') => {}
+                    Some(&crate_byte) => stack.push(crate_byte as char),
+                    None => break,
+                }
+            }
+        }
+        Ok(Stacks(stacks))
+    }
+}
+
+/// Draws the stacks in the same format as the puzzle input
+impl fmt::Display for Stacks {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let max_height = self.0.iter().map(Vec::len).max().unwrap_or(0);
+        for level in (0..max_height).rev() {
+            let row: Vec<String> = self
+                .0
+                .iter()
+                .map(|stack| match stack.get(level) {
+                    Some(crate_char) => format!("[{crate_char}]"),
+                    None => "   ".to_string(),
+                })
+                .collect();
+            writeln!(f, "{}", row.join(" "))?;
+        }
+        let stack_numbers: Vec<String> = (1..=self.0.len())
+            .map(|stack| format!("{stack:^3}"))
+            .collect();
+        write!(f, "{}", stack_numbers.join(" "))
+    }
+}
+
+pub fn parse_input(contents: &str) -> Result<(Stacks, Vec<Instruction>), SupplyStacksError> {
+    let (drawing, instructions_str) = contents
+        .split_once("\n\n")
+        .ok_or(SupplyStacksError::MissingInstructions)?;
+    let stacks = drawing.parse()?;
+    let instructions = instructions_str
+        .lines()
+        .map(str::parse)
+        .collect::<Result<_, _>>()?;
+    Ok((stacks, instructions))
+}
+
+pub trait Crane {
+    fn name(&self) -> String;
+
+    /// Move some of the crate_count crates from the top of one stack to another (both numbered from 0),
+    /// returning how many were moved. There are always enough crates on the source stack.
+    fn swing(
+        &self,
+        stacks: &mut Stacks,
+        src_index: usize,
+        dst_index: usize,
+        crate_count: usize,
+    ) -> usize;
+
+    fn execute(
+        &self,
+        stacks: &mut Stacks,
+        instruction: &Instruction,
+    ) -> Result<(), SupplyStacksError> {
+        self.execute_with_playback(stacks, instruction, &mut |_| {})
+    }
+
+    /// Carry out the instruction, calling on_swing with the stacks after each swing of the crane
+    fn execute_with_playback(
+        &self,
+        stacks: &mut Stacks,
+        instruction: &Instruction,
+        on_swing: &mut dyn FnMut(&Stacks),
+    ) -> Result<(), SupplyStacksError> {
+        stacks.check(instruction)?;
+        let mut remaining = instruction.crate_count;
+        while remaining > 0 {
+            remaining -= self.swing(
+                stacks,
+                instruction.src_stack - 1,
+                instruction.dst_stack - 1,
+                remaining,
+            );
+            on_swing(stacks);
+        }
+        Ok(())
+    }
+
+    /// Carry out all the instructions and return the crates which end up on top of each stack
+    fn rearrange(
+        &self,
+        stacks: &mut Stacks,
+        instructions: &[Instruction],
+    ) -> Result<String, SupplyStacksError> {
+        for instruction in instructions {
+            self.execute(stacks, instruction)?;
+        }
+        Ok(stacks.top_crates())
+    }
+}
+
+/// Moves one crate at a time (part 1)
+pub struct CrateMover9000;
+
+impl Crane for CrateMover9000 {
+    fn name(&self) -> String {
+        "CrateMover 9000".to_string()
+    }
+
+    fn swing(&self, stacks: &mut Stacks, src_index: usize, dst_index: usize, _: usize) -> usize {
+        let crates = stacks.take(src_index, 1);
+        stacks.put(dst_index, crates);
+        1
+    }
+}
+
+/// Moves all the crates at once, keeping them in the same order (part 2)
+pub struct CrateMover9001;
+
+impl Crane for CrateMover9001 {
+    fn name(&self) -> String {
+        "CrateMover 9001".to_string()
+    }
+
+    fn swing(
+        &self,
+        stacks: &mut Stacks,
+        src_index: usize,
+        dst_index: usize,
+        crate_count: usize,
+    ) -> usize {
+        let crates = stacks.take(src_index, crate_count);
+        stacks.put(dst_index, crates);
+        crate_count
+    }
+}
+
+/// Moves up to capacity crates at a time, keeping them in the same order
+pub struct BoundedCrane {
+    capacity: usize,
+}
+
+impl BoundedCrane {
+    pub fn new(capacity: usize) -> Result<Self, SupplyStacksError> {
+        if capacity == 0 {
+            return Err(SupplyStacksError::ZeroCapacity);
+        }
+        Ok(BoundedCrane { capacity })
+    }
+}
+
+impl Crane for BoundedCrane {
+    fn name(&self) -> String {
+        format!("Bounded crane (capacity {})", self.capacity)
+    }
+
+    fn swing(
+        &self,
+        stacks: &mut Stacks,
+        src_index: usize,
+        dst_index: usize,
+        crate_count: usize,
+    ) -> usize {
+        let crates = stacks.take(src_index, crate_count.min(self.capacity));
+        let moved = crates.len();
+        stacks.put(dst_index, crates);
+        moved
+    }
+}
+
+/// Each arm lifts up to arm_capacity crates in turn, starting from the top of the stack.
+/// The arms then set their loads down in the order they picked them up,
+/// so each load keeps its order, but the order of the loads is reversed.
+pub struct MultiArmCrane {
+    arm_count: usize,
+    arm_capacity: usize,
+}
+
+impl MultiArmCrane {
+    pub fn new(arm_count: usize, arm_capacity: usize) -> Result<Self, SupplyStacksError> {
+        if arm_count == 0 || arm_capacity == 0 {
+            return Err(SupplyStacksError::ZeroCapacity);
+        }
+        Ok(MultiArmCrane {
+            arm_count,
+            arm_capacity,
+        })
+    }
+}
+
+impl Crane for MultiArmCrane {
+    fn name(&self) -> String {
+        format!(
+            "Multi-arm crane ({} arms, each with capacity {})",
+            self.arm_count, self.arm_capacity
+        )
+    }
+
+    fn swing(
+        &self,
+        stacks: &mut Stacks,
+        src_index: usize,
+        dst_index: usize,
+        crate_count: usize,
+    ) -> usize {
+        let mut moved = 0;
+        let mut loads = Vec::with_capacity(self.arm_count);
+        while loads.len() < self.arm_count && moved < crate_count {
+            let load = stacks.take(src_index, (crate_count - moved).min(self.arm_capacity));
+            moved += load.len();
+            loads.push(load);
+        }
+        for load in loads {
+            stacks.put(dst_index, load);
+        }
+        moved
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const EXAMPLE: &str = "    [D]    \n\
+                           [N] [C]    \n\
+                           [Z] [M] [P]\n \
+                           1   2   3 \n\
+                           \n\
+                           move 1 from 2 to 1\n\
+                           move 3 from 1 to 3\n\
+                           move 2 from 2 to 1\n\
+                           move 1 from 1 to 2\n";
+
+    fn rearrange_example(crane: &dyn Crane) -> String {
+        let (mut stacks, instructions) = parse_input(EXAMPLE).unwrap();
+        crane.rearrange(&mut stacks, &instructions).unwrap()
+    }
+
+    #[test]
+    fn test_example_crate_movers() {
+        assert_eq!(rearrange_example(&CrateMover9000), "CMZ");
+        assert_eq!(rearrange_example(&CrateMover9001), "MCD");
+    }
+
+    #[test]
+    fn test_bounded_crane_extremes() {
+        assert_eq!(rearrange_example(&BoundedCrane::new(1).unwrap()), "CMZ");
+        assert_eq!(rearrange_example(&BoundedCrane::new(3).unwrap()), "MCD");
+    }
+
+    #[test]
+    fn test_multi_arm_crane() {
+        let mut stacks = Stacks::new(vec![vec!['A', 'B', 'C', 'D', 'E'], vec![]]);
+        let crane = MultiArmCrane::new(2, 2).unwrap();
+        let instruction: Instruction = "move 5 from 1 to 2".parse().unwrap();
+        let mut swings = Vec::new();
+        crane
+            .execute_with_playback(&mut stacks, &instruction, &mut |stacks| {
+                swings.push(stacks.clone())
+            })
+            .unwrap();
+        assert_eq!(
+            swings,
+            [
+                Stacks::new(vec![vec!['A'], vec!['D', 'E', 'B', 'C']]),
+                Stacks::new(vec![vec![], vec!['D', 'E', 'B', 'C', 'A']])
+            ]
+        );
+        assert_eq!(
+            MultiArmCrane::new(0, 2).err(),
+            Some(SupplyStacksError::ZeroCapacity)
+        );
+    }
+
+    #[test]
+    fn test_rendering_matches_drawing() {
+        let (drawing, _) = EXAMPLE.split_once("\n\n").unwrap();
+        let stacks: Stacks = drawing.parse().unwrap();
+        assert_eq!(stacks.to_string(), drawing);
+    }
+
+    #[test]
+    fn test_invalid_instructions() {
+        let (mut stacks, _) = parse_input(EXAMPLE).unwrap();
+        assert_eq!(
+            CrateMover9001.execute(&mut stacks, &"move 4 from 1 to 2".parse().unwrap()),
+            Err(SupplyStacksError::NotEnoughCrates {
+                stack: 1,
+                crate_count: 4,
+                available: 2
+            })
+        );
+        assert_eq!(
+            CrateMover9000.execute(&mut stacks, &"move 1 from 1 to 4".parse().unwrap()),
+            Err(SupplyStacksError::NoSuchStack {
+                stack: 4,
+                stack_count: 3
+            })
+        );
+        assert_eq!(
+            "move one from 1 to 2".parse::<Instruction>(),
+            Err(SupplyStacksError::InvalidInstruction(
+                "move one from 1 to 2".to_string()
+            ))
+        );
+    }
+
+    mod prop_tests {
+        use super::*;
+        use proptest::collection::vec;
+        use proptest::prelude::*;
+
+        fn arb_stacks() -> impl Strategy<Value = Stacks> {
+            vec(vec(proptest::char::range('A', 'Z'), 0..8), 1..10).prop_map(Stacks::new)
+        }
+
+        fn arb_stacks_and_moves() -> impl Strategy<Value = (Stacks, Vec<(usize, usize, usize)>)> {
+            arb_stacks().prop_flat_map(|stacks| {
+                let stack_count = stacks.stacks().len();
+                let moves = vec((1..=stack_count, 1..=stack_count, 0..6_usize), 0..20);
+                (Just(stacks), moves)
+            })
+        }
+
+        /// Clamp the crate counts to the crates available when each instruction is carried out.
+        /// Moves from a stack to itself are skipped, since a multi-arm crane reorders those crates.
+        fn valid_instructions(
+            stacks: &Stacks,
+            moves: &[(usize, usize, usize)],
+        ) -> Vec<Instruction> {
+            let mut heights: Vec<usize> = stacks.stacks().iter().map(Vec::len).collect();
+            moves
+                .iter()
+                .filter(|(src_stack, dst_stack, _)| src_stack != dst_stack)
+                .map(|&(src_stack, dst_stack, crate_count)| {
+                    let crate_count = crate_count.min(heights[src_stack - 1]);
+                    heights[src_stack - 1] -= crate_count;
+                    heights[dst_stack - 1] += crate_count;
+                    Instruction {
+                        crate_count,
+                        src_stack,
+                        dst_stack,
+                    }
+                })
+                .collect()
+        }
+
+        proptest! {
+            #[test]
+            fn rendering_then_parsing_gives_the_same_stacks(stacks in arb_stacks()) {
+                prop_assert_eq!(stacks.to_string().parse::<Stacks>().unwrap(), stacks);
+            }
+
+            #[test]
+            fn equivalent_cranes_rearrange_the_same_way((stacks, moves) in arb_stacks_and_moves()) {
+                let instructions = valid_instructions(&stacks, &moves);
+                let rearrange = |crane: &dyn Crane| {
+                    crane.rearrange(&mut stacks.clone(), &instructions).unwrap()
+                };
+                let one_at_a_time = rearrange(&CrateMover9000);
+                let all_at_once = rearrange(&CrateMover9001);
+                prop_assert_eq!(&rearrange(&BoundedCrane::new(1).unwrap()), &one_at_a_time);
+                prop_assert_eq!(&rearrange(&MultiArmCrane::new(5, 1).unwrap()), &one_at_a_time);
+                prop_assert_eq!(&rearrange(&BoundedCrane::new(8).unwrap()), &all_at_once);
+                prop_assert_eq!(&rearrange(&MultiArmCrane::new(1, 8).unwrap()), &all_at_once);
+            }
+        }
+    }
+}
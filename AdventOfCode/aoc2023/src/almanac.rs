@@ -0,0 +1,115 @@
+//! The seeds and maps of the almanac in day 5, parsed with errors located in the input
+
+use aoc_common::parse::{Input, ParseError};
+use std::ops::Range;
+
+pub type Id = u64;
+
+/// Maps `count` ids starting from `src_start_id` to the same number of ids from `dest_start_id`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct MappingRule {
+    pub dest_start_id: Id,
+    pub src_start_id: Id,
+    pub count: Id,
+}
+
+/// A map such as "seed-to-soil", with its rules in the order they appear in the input
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AlmanacMap {
+    pub name: String,
+    pub rules: Vec<MappingRule>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Almanac {
+    pub seeds: Vec<Id>,
+    pub maps: Vec<AlmanacMap>,
+}
+
+impl Almanac {
+    /// Parse the seeds line, followed by maps separated by blank lines
+    pub fn parse(contents: &str) -> Result<Self, ParseError> {
+        let input = Input::new(contents);
+        let mut sections = input.sections();
+        let seeds_section = input.next_line(contents, &mut sections, "the seeds")?;
+        let (key, seeds_text) = input.key_value(seeds_section, ":")?;
+        if key != "seeds" {
+            return Err(input.error_at(key, "expected key \"seeds\""));
+        }
+        let seeds = input.numbers(seeds_text)?;
+        let maps = sections
+            .map(|section| parse_map(&input, section))
+            .collect::<Result<_, _>>()?;
+        Ok(Almanac { seeds, maps })
+    }
+
+    /// The seed line read as pairs of a start id and a count (as in part 2).
+    /// An unpaired final number is ignored.
+    pub fn seed_ranges(&self) -> impl Iterator<Item = Range<Id>> + '_ {
+        self.seeds
+            .chunks_exact(2)
+            .map(|pair| pair[0]..pair[0] + pair[1])
+    }
+}
+
+fn parse_map<'a>(input: &Input<'a>, section: &'a str) -> Result<AlmanacMap, ParseError> {
+    let mut line_iter = section.lines();
+    let header = input.next_line(section, &mut line_iter, "a map header")?;
+    let (name, after_name) = input.split_once(header, " map:")?;
+    if !after_name.trim().is_empty() {
+        return Err(input.error_at(after_name, "expected the end of the map header"));
+    }
+    let rules = line_iter
+        .map(|line| match input.numbers(line)?.as_slice() {
+            &[dest_start_id, src_start_id, count] => Ok(MappingRule {
+                dest_start_id,
+                src_start_id,
+                count,
+            }),
+            _ => Err(input.error_at(line, "expected 3 numbers: dest start, src start and count")),
+        })
+        .collect::<Result<_, _>>()?;
+    Ok(AlmanacMap {
+        name: name.trim().to_string(),
+        rules,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse() {
+        let almanac =
+            Almanac::parse("seeds: 79 14 55 13\n\nseed-to-soil map:\n50 98 2\n52 50 48\n").unwrap();
+        assert_eq!(almanac.seeds, [79, 14, 55, 13]);
+        assert_eq!(almanac.seed_ranges().collect::<Vec<_>>(), [79..93, 55..68]);
+        assert_eq!(almanac.maps.len(), 1);
+        assert_eq!(almanac.maps[0].name, "seed-to-soil");
+        assert_eq!(
+            almanac.maps[0].rules[1],
+            MappingRule {
+                dest_start_id: 52,
+                src_start_id: 50,
+                count: 48
+            }
+        );
+    }
+
+    #[test]
+    fn test_malformed_input_is_located() {
+        let err = Almanac::parse("seeds: 79 14\n\nseed-to-soil map:\n50 98\n").unwrap_err();
+        assert_eq!(
+            (err.line, err.column, err.excerpt.as_str()),
+            (4, 1, "50 98")
+        );
+
+        let err = Almanac::parse("seeds: 79 x4\n").unwrap_err();
+        assert_eq!((err.line, err.column), (1, 11));
+
+        let err = Almanac::parse("seeds: 1\n\nseed-to-soil\n1 2 3\n").unwrap_err();
+        assert_eq!((err.line, err.column), (3, 1));
+        assert_eq!(err.message, "expected \" map:\"");
+    }
+}
@@ -1,132 +1,57 @@
-use aoc2023::read_and_solve_and_time_more_runs;
-use std::str::{FromStr, Lines};
+use aoc2023::almanac::{Almanac, AlmanacMap, Id};
+use aoc2023::read_and_try_solve_and_time_more_runs;
+use aoc_common::parse::ParseError;
 
 fn main() {
-    read_and_solve_and_time_more_runs("data/day5_input.txt", "Day 5 part 1", solve_part1, 10_000);
-    read_and_solve_and_time_more_runs("data/day5_input.txt", "Day 5 part 2", solve_part2, 0);
+    read_and_try_solve_and_time_more_runs(
+        "data/day5_input.txt",
+        "Day 5 part 1",
+        solve_part1,
+        10_000,
+    );
+    read_and_try_solve_and_time_more_runs("data/day5_input.txt", "Day 5 part 2", solve_part2, 0);
 }
 
-fn solve_part1(contents: &str) -> Id {
-    let mut line_iter = contents.lines();
-    let seeds = get_numbers_in_seed_line(&mut line_iter);
-    let mappings = get_mappings(&mut line_iter);
-    get_min_location_id(seeds.iter().copied(), &mappings)
+fn solve_part1(contents: &str) -> Result<Id, ParseError> {
+    let almanac = Almanac::parse(contents)?;
+    Ok(get_min_location_id(
+        almanac.seeds.iter().copied(),
+        &almanac.maps,
+    ))
 }
 
-fn solve_part2(contents: &str) -> Id {
-    let mut line_iter = contents.lines();
-    let seed_line_numbers = get_numbers_in_seed_line(&mut line_iter);
-    let mappings = get_mappings(&mut line_iter);
-
-    // Form ranges from pairs of numbers in the seed line:
-    let mut seed_ranges = Vec::new();
-    let mut range_start: Id = 0;
-
-    for (i, &seed) in seed_line_numbers.iter().enumerate() {
-        if i % 2 == 0 {
-            range_start = seed;
-        } else {
-            seed_ranges.push(range_start..(range_start + seed));
-        }
-    }
-
-    seed_ranges
-        .into_iter()
-        .map(|range| get_min_location_id(range.into_iter(), &mappings))
+fn solve_part2(contents: &str) -> Result<Id, ParseError> {
+    let almanac = Almanac::parse(contents)?;
+    Ok(almanac
+        .seed_ranges()
+        .map(|range| get_min_location_id(range, &almanac.maps))
         .min()
-        .expect("A minimum location id could be found from the seed ranges")
+        .expect("A minimum location id could be found from the seed ranges"))
 }
 
 // ------------------------------------------------------------------------------------------------
 // Shared logic
 // ------------------------------------------------------------------------------------------------
 
-type Id = u64;
-
-struct MappingRule {
-    dest_start_id: Id,
-    src_start_id: Id,
-    count: Id,
-}
-
-impl MappingRule {
-    fn map(&self, input: Id) -> Option<Id> {
-        if self.src_start_id <= input && self.src_start_id + self.count > input {
-            Some(input + self.dest_start_id - self.src_start_id)
-        } else {
-            None
-        }
-    }
-}
-
-struct Mapping {
-    mapping_rules: Vec<MappingRule>,
-}
-
-impl Mapping {
-    fn map(&self, input: Id) -> Id {
-        self.mapping_rules
-            .iter()
-            .filter_map(|rule| rule.map(input))
-            .next()
-            .unwrap_or(input)
-    }
+fn map_id(mapping: &AlmanacMap, input: Id) -> Id {
+    mapping
+        .rules
+        .iter()
+        .find(|rule| rule.src_start_id <= input && rule.src_start_id + rule.count > input)
+        .map_or(input, |rule| input + rule.dest_start_id - rule.src_start_id)
 }
 
-fn get_min_location_id(seed_iter: impl Iterator<Item = Id>, mappings: &[Mapping]) -> Id {
+fn get_min_location_id(seed_iter: impl Iterator<Item = Id>, mappings: &[AlmanacMap]) -> Id {
     seed_iter
-        .map(|seed| mappings.iter().fold(seed, |id, mapping| mapping.map(id)))
+        .map(|seed| {
+            mappings
+                .iter()
+                .fold(seed, |id, mapping| map_id(mapping, id))
+        })
         .min()
         .expect("No seed could be mapped to a location")
 }
 
-// ------------------------------------------------------------------------------------------------
-// Shared parsing code
-// ------------------------------------------------------------------------------------------------
-
-fn get_numbers_in_seed_line(line_iter: &mut Lines) -> Vec<Id> {
-    let seed_line = line_iter.next().unwrap();
-    seed_line[7..]
-        .split(' ')
-        .map(|seed_str| Id::from_str(seed_str).expect("A seed was not a number"))
-        .collect::<Vec<Id>>()
-}
-
-fn get_mappings(line_iter: &mut Lines) -> Vec<Mapping> {
-    let mut mappings = Vec::new();
-    for ln in line_iter {
-        if ln.is_empty() {
-            continue;
-        } else if ln.contains("map:") {
-            mappings.push(Mapping {
-                mapping_rules: Vec::new(),
-            });
-        } else {
-            let rule_params: Vec<Id> = ln
-                .split(' ')
-                .map(|id_str| Id::from_str(id_str).expect("Id's should be numeric"))
-                .collect();
-
-            assert_eq!(
-                rule_params.len(),
-                3,
-                "Exactly 3 parameters are expected to each mapping"
-            );
-
-            mappings
-                .last_mut()
-                .expect("A current mapping was not available")
-                .mapping_rules
-                .push(MappingRule {
-                    dest_start_id: rule_params[0],
-                    src_start_id: rule_params[1],
-                    count: rule_params[2],
-                });
-        }
-    }
-    mappings
-}
-
 // ------------------------------------------------------------------------------------------------
 // Unit tests
 // ------------------------------------------------------------------------------------------------
@@ -172,12 +97,12 @@ humidity-to-location map:
     #[test]
     fn test_part1_example() {
         let solution = solve_part1(EXAMPLE);
-        assert_eq!(solution, 35);
+        assert_eq!(solution, Ok(35));
     }
 
     #[test]
     fn test_part2_example() {
         let solution = solve_part2(EXAMPLE);
-        assert_eq!(solution, 46);
+        assert_eq!(solution, Ok(46));
     }
 }
@@ -0,0 +1,84 @@
+use aoc2023::almanac::{Almanac, Id};
+use aoc2023::read_and_try_solve_and_time_more_runs;
+use aoc_common::parse::ParseError;
+use aoc_common::ranges::{PiecewiseMap, RangeSet};
+
+fn main() {
+    read_and_try_solve_and_time_more_runs(
+        "data/day5_input.txt",
+        "Day 5 part 2 (using ranges)",
+        solve,
+        10_000,
+    );
+}
+
+/// Map whole ranges of seeds through each mapping, instead of mapping the seeds one at a time
+fn solve(contents: &str) -> Result<Id, ParseError> {
+    let almanac = Almanac::parse(contents)?;
+    let mappings: Vec<PiecewiseMap<Id>> = almanac
+        .maps
+        .iter()
+        .map(|map| {
+            let mut mapping = PiecewiseMap::new();
+            for rule in &map.rules {
+                mapping.add_rule(
+                    rule.src_start_id..rule.src_start_id + rule.count,
+                    rule.dest_start_id,
+                );
+            }
+            mapping
+        })
+        .collect();
+
+    let seed_ranges: RangeSet<Id> = almanac.seed_ranges().collect();
+    let location_ranges = mappings
+        .iter()
+        .fold(seed_ranges, |ranges, mapping| mapping.map_set(&ranges));
+    Ok(location_ranges
+        .min()
+        .expect("No seed could be mapped to a location"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::solve;
+
+    const EXAMPLE: &str = "seeds: 79 14 55 13
+
+seed-to-soil map:
+50 98 2
+52 50 48
+
+soil-to-fertilizer map:
+0 15 37
+37 52 2
+39 0 15
+
+fertilizer-to-water map:
+49 53 8
+0 11 42
+42 0 7
+57 7 4
+
+water-to-light map:
+88 18 7
+18 25 70
+
+light-to-temperature map:
+45 77 23
+81 45 19
+68 64 13
+
+temperature-to-humidity map:
+0 69 1
+1 0 69
+
+humidity-to-location map:
+60 56 37
+56 93 4";
+
+    #[test]
+    fn test_example() {
+        assert_eq!(solve(EXAMPLE), Ok(46));
+    }
+}
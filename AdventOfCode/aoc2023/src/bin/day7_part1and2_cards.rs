@@ -0,0 +1,64 @@
+//! Day 7 again, using the card-hand evaluation shared with Project Euler problem 54.
+
+use aoc2023::read_and_solve_and_time_more_runs;
+use aoc_common::cards::CardGame;
+
+const INPUT_FILE_PATH: &str = "data/day7_input.txt";
+
+fn main() {
+    read_and_solve_and_time_more_runs(INPUT_FILE_PATH, "Day 7 part 1", solve_part_1, 1_000);
+    read_and_solve_and_time_more_runs(INPUT_FILE_PATH, "Day 7 part 2", solve_part_2, 1_000);
+}
+
+fn solve_part_1(contents: &str) -> u32 {
+    solve(contents, &CardGame::camel_cards())
+}
+
+fn solve_part_2(contents: &str) -> u32 {
+    solve(contents, &CardGame::camel_cards_with_jokers())
+}
+
+fn solve(contents: &str, game: &CardGame) -> u32 {
+    let mut hands: Vec<_> = contents
+        .lines()
+        .map(|ln| {
+            let (hand, bid_str) = ln.split_once(' ').expect("No separator found in line.");
+            let bid: u32 = bid_str
+                .parse()
+                .expect("The bid could not be parsed as a number.");
+            let hand = game
+                .parse_hand(hand)
+                .expect("The hand could not be parsed.");
+            (game.evaluate(&hand), bid)
+        })
+        .collect();
+    hands.sort();
+    hands
+        .iter()
+        .enumerate()
+        .map(|(i, (_, bid))| bid * (i as u32 + 1))
+        .sum()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{solve_part_1, solve_part_2};
+
+    const EXAMPLE: &str = "32T3K 765
+T55J5 684
+KK677 28
+KTJJT 220
+QQQJA 483";
+
+    #[test]
+    fn test_part1_example() {
+        let solution = solve_part_1(EXAMPLE);
+        assert_eq!(solution, 6440);
+    }
+
+    #[test]
+    fn test_part2_example() {
+        let solution = solve_part_2(EXAMPLE);
+        assert_eq!(solution, 5905);
+    }
+}
@@ -1,5 +1,7 @@
 use std::time::Instant;
 
+pub mod almanac;
+
 pub fn read_and_solve_and_time_more_runs<S, T>(
     file_path: &str,
     problem_desc: &str,
@@ -15,6 +17,29 @@ pub fn read_and_solve_and_time_more_runs<S, T>(
     solve_and_time_more_runs(problem_desc, || solve(&contents), repetitions);
 }
 
+/// Like [`read_and_solve_and_time_more_runs`], but for solvers which can reject the input.
+/// An error is reported (e.g. with its line and column) instead of being timed.
+pub fn read_and_try_solve_and_time_more_runs<S, T, E>(
+    file_path: &str,
+    problem_desc: &str,
+    solve: S,
+    repetitions: u32,
+) where
+    S: Fn(&str) -> Result<T, E>,
+    T: std::fmt::Debug,
+    E: std::fmt::Display,
+{
+    let contents = std::fs::read_to_string(file_path).expect("Input file not readable");
+
+    if let Err(error) = solve(&contents) {
+        eprintln!("{problem_desc}: invalid input in {file_path} at {error}");
+        std::process::exit(1);
+    }
+
+    // The solver is deterministic, so having succeeded once, it will succeed on every run
+    solve_and_time_more_runs(problem_desc, || solve(&contents).ok().unwrap(), repetitions);
+}
+
 pub fn solve_and_time_more_runs<S, T>(problem_desc: &str, solve: S, repetitions: u32)
 where
     S: Fn() -> T,
@@ -0,0 +1,158 @@
+use aoc2024_rs::read_and_try_solve_and_time_more_runs;
+use aoc_common::parse::{Input, ParseError};
+
+const INPUT_FILE_PATH: &str = "data/day13_input.txt";
+
+struct Machine {
+    a_x: i128,
+    a_y: i128,
+    b_x: i128,
+    b_y: i128,
+    prize_x: i128,
+    prize_y: i128,
+}
+
+impl Machine {
+    fn solve(&self) -> Option<i128> {
+        let det = self.a_x * self.b_y - self.a_y * self.b_x;
+        if det == 0 {
+            // There are either infinitely many or no solutions.
+            // Assuming there are infinitely many solutions.
+            // Since more tokens are spent on button A,
+            // choose the solution that only uses button B
+            let n = if self.b_x == 0 {
+                0
+            } else {
+                self.prize_x / self.b_x
+            };
+            if self.b_x * n == self.prize_x && self.b_y * n == self.prize_y {
+                Some(n)
+            } else {
+                None
+            }
+        } else {
+            // Invert the 2x2 [A B] matrix and multiply by p (column vector of prize coordinates)
+            let a_count = (self.b_y * self.prize_x - self.b_x * self.prize_y) / det;
+            let b_count = (self.a_x * self.prize_y - self.a_y * self.prize_x) / det;
+            if a_count < 0 || b_count < 0 {
+                None
+            } else if (a_count * self.a_x + b_count * self.b_x == self.prize_x)
+                && (a_count * self.a_y + b_count * self.b_y == self.prize_y)
+            {
+                Some(3 * a_count + b_count)
+            } else {
+                None
+            }
+        }
+    }
+}
+
+fn main() {
+    read_and_try_solve_and_time_more_runs(INPUT_FILE_PATH, "Day 13 part 1 (parsed)", solve, 1000);
+}
+
+fn solve(contents: &str) -> Result<i128, ParseError> {
+    let machines = parse(contents)?;
+    Ok(machines.iter().filter_map(|machine| machine.solve()).sum())
+}
+
+fn parse(contents: &str) -> Result<Vec<Machine>, ParseError> {
+    let input = Input::new(contents);
+    input
+        .sections()
+        .map(|section| parse_machine(&input, section))
+        .collect()
+}
+
+fn parse_machine<'a>(input: &Input<'a>, section: &'a str) -> Result<Machine, ParseError> {
+    let mut line_iter = section.lines();
+    let (a_x, a_y) = parse_line(input, section, &mut line_iter, "Button A: X+", ", Y+")?;
+    let (b_x, b_y) = parse_line(input, section, &mut line_iter, "Button B: X+", ", Y+")?;
+    let (prize_x, prize_y) = parse_line(input, section, &mut line_iter, "Prize: X=", ", Y=")?;
+    if let Some(extra_line) = line_iter.next() {
+        return Err(input.error_at(extra_line, "expected a blank line after the prize"));
+    }
+    Ok(Machine {
+        a_x,
+        a_y,
+        b_x,
+        b_y,
+        prize_x,
+        prize_y,
+    })
+}
+
+fn parse_line<'a>(
+    input: &Input<'a>,
+    section: &'a str,
+    line_iter: &mut impl Iterator<Item = &'a str>,
+    x_prefix: &str,
+    y_separator: &str,
+) -> Result<(i128, i128), ParseError> {
+    let line = input.next_line(section, line_iter, x_prefix.split(':').next().unwrap())?;
+    let (x_str, y_str) = input.split_once(input.strip_prefix(line, x_prefix)?, y_separator)?;
+    Ok((input.parse(x_str)?, input.parse(y_str)?))
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::solve;
+
+    #[test]
+    fn test_example() {
+        let contents = "Button A: X+94, Y+34
+Button B: X+22, Y+67
+Prize: X=8400, Y=5400
+
+Button A: X+26, Y+66
+Button B: X+67, Y+21
+Prize: X=12748, Y=12176
+
+Button A: X+17, Y+86
+Button B: X+84, Y+37
+Prize: X=7870, Y=6450
+
+Button A: X+69, Y+23
+Button B: X+27, Y+71
+Prize: X=18641, Y=10279";
+
+        assert_eq!(solve(contents), Ok(480));
+    }
+
+    #[test]
+    fn test_malformed_input_is_located() {
+        let contents = "Button A: X+94, Y+34
+Button B: X+22, Y+67
+Prize: X=8400, Y=5400
+
+Button A: X+26, Y+66
+Button B: X+67; Y+21
+Prize: X=12748, Y=12176";
+
+        let err = solve(contents).unwrap_err();
+        assert_eq!((err.line, err.column), (6, 13));
+        assert_eq!(err.message, "expected \", Y+\"");
+    }
+
+    #[test]
+    fn test_missing_prize_line() {
+        let err = solve("Button A: X+94, Y+34\nButton B: X+22, Y+67\n").unwrap_err();
+        assert_eq!((err.line, err.column), (2, 21));
+        assert_eq!(err.message, "expected Prize, found end of input");
+    }
+
+    #[test]
+    fn test_missing_prize_line_in_first_machine() {
+        let contents = "Button A: X+94, Y+34
+Button B: X+22, Y+67
+
+Button A: X+26, Y+66
+Button B: X+67, Y+21
+Prize: X=12748, Y=12176
+";
+        let err = solve(contents).unwrap_err();
+        assert_eq!((err.line, err.column), (2, 21));
+        assert_eq!(err.excerpt, "Button B: X+22, Y+67");
+        assert_eq!(err.message, "expected Prize, found end of section");
+    }
+}
@@ -0,0 +1,306 @@
+//! Find the Christmas tree automatically, instead of stepping through frames by hand.
+//!
+//! The x coordinates repeat every 101 steps and the y coordinates every 103 steps.
+//! When the robots form a picture, they are much more tightly clustered than usual,
+//! so the variance of x is smallest at some step t_x < 101 and the variance of y at t_y < 103.
+//! The Chinese remainder theorem then gives the step t with t = t_x (mod 101), t = t_y (mod 103).
+//!
+//! Two slower detectors, which score every step up to 101 x 103, are used to cross-check this:
+//! the entropy of the robots' distribution over blocks of tiles, and the largest group of
+//! adjacent robots.
+//!
+//! Pass "--cross-check" to run them too, and "--show" to print the picture found
+//! (or view it in day14_part2_visualiser).
+
+use aoc2024_rs::read_and_solve_and_time_more_runs;
+use aoc2024_rs::restroom_robots::{parse, Robot};
+use aoc_common::grid::{Grid, Pos};
+use std::time::Instant;
+
+const INPUT_FILE_PATH: &str = "data/day14_input.txt";
+const WIDTH: usize = 101;
+const HEIGHT: usize = 103;
+
+/// The width and height of the blocks which robots are counted in, to calculate the entropy
+const ENTROPY_BLOCK_SIZE: usize = 4;
+
+/// Scores the robot counts on each tile after some step. The tree has the lowest score.
+type ScoreFn = fn(&Grid<u32>) -> f64;
+
+fn main() {
+    read_and_solve_and_time_more_runs(INPUT_FILE_PATH, "Day 14 part 2 (auto)", solve, 100);
+
+    let show = std::env::args().any(|arg| arg == "--show");
+    let cross_check = std::env::args().any(|arg| arg == "--cross-check");
+    if show || cross_check {
+        let contents = std::fs::read_to_string(INPUT_FILE_PATH).expect("Input file not readable");
+        let robots = parse(&contents);
+        let steps = find_tree_using_variance_and_crt(&robots, WIDTH, HEIGHT);
+        if cross_check {
+            cross_check_step(&robots, steps);
+        }
+        if show {
+            println!();
+            println!("Picture after {steps} steps:");
+            print!("{}", render(&robots, steps, WIDTH, HEIGHT));
+        }
+    }
+}
+
+/// Compare the step found using the variance with the steps found by the slower detectors
+fn cross_check_step(robots: &[Robot], steps: usize) {
+    println!();
+    println!("Cross-checking step {steps} against the slower detectors...");
+    let detectors: [(&str, ScoreFn); 2] = [
+        ("Lowest entropy", entropy_score),
+        ("Largest group of adjacent robots", largest_component_score),
+    ];
+    for (description, score) in detectors {
+        let start_time = Instant::now();
+        let detected_steps = find_step_with_lowest_score(robots, WIDTH, HEIGHT, score);
+        let verdict = if detected_steps == steps {
+            "agrees"
+        } else {
+            "DISAGREES"
+        };
+        println!(
+            "{description}: step {detected_steps} ({verdict}), found in {:?}",
+            start_time.elapsed()
+        );
+    }
+}
+
+fn solve(contents: &str) -> usize {
+    let robots = parse(contents);
+    find_tree_using_variance_and_crt(&robots, WIDTH, HEIGHT)
+}
+
+fn find_tree_using_variance_and_crt(robots: &[Robot], width: usize, height: usize) -> usize {
+    let step_with_min_variance = |period: usize, coordinate: fn((usize, usize)) -> usize| {
+        (0..period)
+            .map(|steps| {
+                let positions = robots.iter().map(|r| r.get_position(steps, width, height));
+                (variance(positions.map(coordinate)), steps)
+            })
+            .min_by(|(variance_1, _), (variance_2, _)| variance_1.total_cmp(variance_2))
+            .unwrap()
+            .1
+    };
+    let step_x = step_with_min_variance(width, |(x, _)| x);
+    let step_y = step_with_min_variance(height, |(_, y)| y);
+    chinese_remainder(step_x, width, step_y, height)
+}
+
+fn variance(values: impl Iterator<Item = usize>) -> f64 {
+    let (count, sum, sum_of_squares) = values.fold((0, 0, 0), |(count, sum, sum_sq), value| {
+        (count + 1, sum + value, sum_sq + value * value)
+    });
+    let mean = sum as f64 / count as f64;
+    sum_of_squares as f64 / count as f64 - mean * mean
+}
+
+/// The smallest t with t = a (mod m) and t = b (mod n), for coprime m and n
+fn chinese_remainder(a: usize, m: usize, b: usize, n: usize) -> usize {
+    let m_inverse = mod_inverse(m as i64, n as i64);
+    let k = ((b as i64 - a as i64) * m_inverse).rem_euclid(n as i64) as usize;
+    a + m * k
+}
+
+/// The inverse of a modulo n, using the extended Euclidean algorithm
+fn mod_inverse(a: i64, n: i64) -> i64 {
+    let (mut old_r, mut r) = (a.rem_euclid(n), n);
+    let (mut old_s, mut s) = (1, 0);
+    while r != 0 {
+        let quotient = old_r / r;
+        (old_r, r) = (r, old_r - quotient * r);
+        (old_s, s) = (s, old_s - quotient * s);
+    }
+    assert_eq!(old_r, 1, "{a} has no inverse modulo {n}");
+    old_s.rem_euclid(n)
+}
+
+/// The step with the lowest score, out of all the distinct arrangements of the robots
+fn find_step_with_lowest_score<F>(robots: &[Robot], width: usize, height: usize, score: F) -> usize
+where
+    F: Fn(&Grid<u32>) -> f64,
+{
+    (0..width * height)
+        .map(|steps| (steps, score(&count_robots(robots, steps, width, height))))
+        .min_by(|(_, score_1), (_, score_2)| score_1.total_cmp(score_2))
+        .unwrap()
+        .0
+}
+
+fn count_robots(robots: &[Robot], steps: usize, width: usize, height: usize) -> Grid<u32> {
+    let mut counts = Grid::new(width, height, 0);
+    for robot in robots {
+        let (x, y) = robot.get_position(steps, width, height);
+        counts[Pos::new(y, x)] += 1;
+    }
+    counts
+}
+
+/// The Shannon entropy of the distribution of robots over square blocks of tiles.
+/// A picture has lower entropy than robots scattered at random.
+fn entropy_score(counts: &Grid<u32>) -> f64 {
+    let blocks_wide = counts.width().div_ceil(ENTROPY_BLOCK_SIZE);
+    let blocks_high = counts.height().div_ceil(ENTROPY_BLOCK_SIZE);
+    let mut block_counts = Grid::new(blocks_wide, blocks_high, 0);
+    for (pos, &count) in counts.iter() {
+        let block_pos = Pos::new(pos.row / ENTROPY_BLOCK_SIZE, pos.col / ENTROPY_BLOCK_SIZE);
+        block_counts[block_pos] += count;
+    }
+    let total: u32 = block_counts.iter().map(|(_, &count)| count).sum();
+    block_counts
+        .iter()
+        .filter(|(_, &count)| count > 0)
+        .map(|(_, &count)| {
+            let p = count as f64 / total as f64;
+            -p * p.log2()
+        })
+        .sum()
+}
+
+/// The negated size of the largest group of orthogonally adjacent tiles with robots on them
+fn largest_component_score(counts: &Grid<u32>) -> f64 {
+    let mut is_visited = counts.map(|_, _| false);
+    let mut largest_size = 0;
+    let mut to_visit = Vec::new();
+    for (start_pos, &count) in counts.iter() {
+        if count == 0 || is_visited[start_pos] {
+            continue;
+        }
+        is_visited[start_pos] = true;
+        to_visit.push(start_pos);
+        let mut size = 0;
+        while let Some(pos) = to_visit.pop() {
+            size += 1;
+            for next_pos in counts.neighbours_4(pos) {
+                if counts[next_pos] > 0 && !is_visited[next_pos] {
+                    is_visited[next_pos] = true;
+                    to_visit.push(next_pos);
+                }
+            }
+        }
+        largest_size = largest_size.max(size);
+    }
+    -(largest_size as f64)
+}
+
+fn render(robots: &[Robot], steps: usize, width: usize, height: usize) -> String {
+    count_robots(robots, steps, width, height).render(|_, &count| if count > 0 { '*' } else { '.' })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use proptest::collection;
+    use proptest::prelude::*;
+
+    /// The size of the solid triangle which the tree robots form, in rows
+    const TREE_HEIGHT: i64 = 12;
+
+    /// Robots which form a solid triangle at some step, with others scattered around randomly,
+    /// and that step. Each robot's starting position is found by running its final position
+    /// backwards.
+    fn robots_with_hidden_tree() -> impl Strategy<Value = (Vec<Robot>, usize)> {
+        let tree_robot_count = (TREE_HEIGHT * TREE_HEIGHT) as usize;
+        let scattered = collection::vec((0..WIDTH as i64, 0..HEIGHT as i64), 150);
+        let velocities = (
+            -(WIDTH as i64)..WIDTH as i64,
+            -(HEIGHT as i64)..HEIGHT as i64,
+        );
+        (
+            0..WIDTH * HEIGHT,
+            scattered,
+            collection::vec(velocities, tree_robot_count + 150),
+        )
+            .prop_map(|(tree_step, scattered, velocities)| {
+                let triangle = (0..TREE_HEIGHT)
+                    .flat_map(|row| (-row..=row).map(move |col| (50 + col, 40 + row)));
+                let robots = triangle
+                    .chain(scattered)
+                    .zip(velocities)
+                    .map(|((final_x, final_y), (v_x, v_y))| {
+                        let n = tree_step as i64;
+                        Robot {
+                            p_x: (final_x - v_x * n).rem_euclid(WIDTH as i64),
+                            p_y: (final_y - v_y * n).rem_euclid(HEIGHT as i64),
+                            v_x,
+                            v_y,
+                        }
+                    })
+                    .collect();
+                (robots, tree_step)
+            })
+    }
+
+    #[test]
+    fn test_mod_inverse() {
+        assert_eq!(mod_inverse(101, 103), 51);
+    }
+
+    #[test]
+    fn test_variance() {
+        assert_eq!(variance([2, 4, 4, 4, 5, 5, 7, 9].into_iter()), 4.0);
+    }
+
+    proptest! {
+        #![proptest_config(ProptestConfig::with_cases(32))]
+
+        #[test]
+        fn chinese_remainder_recovers_the_step(steps in 0..WIDTH * HEIGHT) {
+            let t = chinese_remainder(steps % WIDTH, WIDTH, steps % HEIGHT, HEIGHT);
+            prop_assert_eq!(t, steps);
+        }
+
+        #[test]
+        fn variance_and_crt_find_the_tree((robots, tree_step) in robots_with_hidden_tree()) {
+            prop_assert_eq!(
+                find_tree_using_variance_and_crt(&robots, WIDTH, HEIGHT),
+                tree_step
+            );
+        }
+
+        #[test]
+        fn scores_are_lowest_for_the_tree((robots, tree_step) in robots_with_hidden_tree()) {
+            let scores_at = |steps: usize| {
+                let counts = count_robots(&robots, steps, WIDTH, HEIGHT);
+                (entropy_score(&counts), largest_component_score(&counts))
+            };
+            let (tree_entropy, tree_component) = scores_at(tree_step);
+            for offset in [1, WIDTH, HEIGHT, 5000, WIDTH * HEIGHT - 1] {
+                let steps = (tree_step + offset) % (WIDTH * HEIGHT);
+                let (entropy, component) = scores_at(steps);
+                prop_assert!(tree_entropy < entropy, "entropy at step {}", steps);
+                prop_assert!(tree_component < component, "component at step {}", steps);
+            }
+        }
+
+        #[test]
+        fn render_shows_the_tree((robots, tree_step) in robots_with_hidden_tree()) {
+            let picture = render(&robots, tree_step, WIDTH, HEIGHT);
+            let rows: Vec<&str> = picture.lines().collect();
+            for row in 0..TREE_HEIGHT {
+                let tree_row = &rows[(40 + row) as usize][(50 - row) as usize..=(50 + row) as usize];
+                prop_assert_eq!(tree_row, "*".repeat(2 * row as usize + 1));
+            }
+        }
+    }
+
+    proptest! {
+        #![proptest_config(ProptestConfig::with_cases(2))]
+
+        #[test]
+        #[ignore = "Runs slowly in debug mode, since it scores every step up to 101 x 103"]
+        fn entropy_and_largest_component_find_the_tree(
+            (robots, tree_step) in robots_with_hidden_tree()
+        ) {
+            let by_entropy = find_step_with_lowest_score(&robots, WIDTH, HEIGHT, entropy_score);
+            let by_component =
+                find_step_with_lowest_score(&robots, WIDTH, HEIGHT, largest_component_score);
+            prop_assert_eq!(by_entropy, tree_step);
+            prop_assert_eq!(by_component, tree_step);
+        }
+    }
+}
@@ -0,0 +1,111 @@
+//! An alternative to the day 14 part 2 viewer, using the shared visualiser.
+//! Play it at high speed, and pause when the robots form a picture of a Christmas tree.
+//! Or pass a step count (such as the one found by day14_part2_auto) to start from that step.
+
+use aoc2024_rs::restroom_robots::{parse, Robot};
+use aoc2024_rs::visualiser::{run_interactive, Player, Simulation};
+use aoc_common::grid::{Grid, Pos};
+
+const INPUT_FILE_PATH: &str = "data/day14_input.txt";
+const WIDTH: usize = 101;
+const HEIGHT: usize = 103;
+
+fn main() -> std::io::Result<()> {
+    let contents = std::fs::read_to_string(INPUT_FILE_PATH).expect("Input file not readable");
+    let robots = parse(&contents);
+    let mut player = Player::new(Restroom::new(robots, WIDTH, HEIGHT));
+    if let Some(start_step) = std::env::args().nth(1) {
+        let start_step: usize = start_step.parse().expect("The start step must be a number");
+        while player.step_count() < start_step && player.step_forward() {}
+    }
+    run_interactive(&mut player)
+}
+
+#[derive(Clone)]
+struct Restroom {
+    robots: Vec<Robot>,
+    width: usize,
+    height: usize,
+}
+
+impl Restroom {
+    fn new(robots: Vec<Robot>, width: usize, height: usize) -> Self {
+        Self {
+            robots,
+            width,
+            height,
+        }
+    }
+}
+
+impl Simulation for Restroom {
+    fn title(&self) -> String {
+        "Advent of Code 2024: Day 14 Part 2".to_string()
+    }
+
+    fn step(&mut self) -> bool {
+        let (w, h) = (self.width as i64, self.height as i64);
+        for robot in self.robots.iter_mut() {
+            robot.p_x = (robot.p_x + robot.v_x).rem_euclid(w);
+            robot.p_y = (robot.p_y + robot.v_y).rem_euclid(h);
+        }
+        true
+    }
+
+    /// Show the number of robots on each tile, as in the puzzle description
+    fn to_grid(&self) -> Grid<char> {
+        let mut counts = Grid::new(self.width, self.height, 0);
+        for robot in &self.robots {
+            counts[Pos::new(robot.p_y as usize, robot.p_x as usize)] += 1;
+        }
+        counts.map(|_, &count| {
+            if count == 0 {
+                '.'
+            } else {
+                char::from_digit(count.min(9), 10).unwrap()
+            }
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const EXAMPLE: &str = "p=0,4 v=3,-3
+p=6,3 v=-1,-3
+p=10,3 v=-1,2
+p=2,0 v=2,-1
+p=0,0 v=1,3
+p=3,0 v=-2,-2
+p=7,6 v=-1,-3
+p=3,0 v=-1,-2
+p=9,3 v=2,3
+p=7,3 v=-1,2
+p=2,4 v=2,-3
+p=9,5 v=-3,-3";
+
+    #[test]
+    fn test_example_after_100_steps() {
+        let mut player = Player::new(Restroom::new(parse(EXAMPLE), 11, 7));
+        for _ in 0..100 {
+            player.step_forward();
+        }
+        assert_eq!(
+            player.frame_text(),
+            "Step 100\n\
+             ......2..1.\n\
+             ...........\n\
+             1..........\n\
+             .11........\n\
+             .....1.....\n\
+             ...12......\n\
+             .1....1....\n"
+        );
+
+        // Stepping back replays from the snapshot at step 0
+        player.step_back();
+        player.step_forward();
+        assert!(player.frame_text().starts_with("Step 100\n......2..1.\n"));
+    }
+}
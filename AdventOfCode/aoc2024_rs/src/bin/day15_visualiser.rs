@@ -0,0 +1,253 @@
+//! Watch the robot push boxes around the day 15 warehouse in the terminal.
+//! Pass `--wide` for the doubled-width warehouse of part 2,
+//! and a folder name to write the frames to text files instead.
+
+use aoc2024_rs::visualiser::{dump_frames, run_interactive, Player, Simulation};
+use aoc_common::grid::{Direction, Grid, Pos};
+use std::collections::HashSet;
+use std::path::Path;
+
+const INPUT_FILE_PATH: &str = "data/day15_input.txt";
+const MAX_STEPS_TO_DUMP: usize = 100_000;
+
+fn main() -> std::io::Result<()> {
+    let contents = std::fs::read_to_string(INPUT_FILE_PATH).expect("Input file not readable");
+    let mut args: Vec<String> = std::env::args().skip(1).collect();
+    let is_wide = args.first().is_some_and(|arg| arg == "--wide");
+    if is_wide {
+        args.remove(0);
+    }
+    let mut player = Player::new(Warehouse::parse(&contents, is_wide));
+    match args.first() {
+        Some(frames_dir) => {
+            let frame_paths =
+                dump_frames(&mut player, Path::new(frames_dir), 1, MAX_STEPS_TO_DUMP)?;
+            println!("Wrote {} frames to {frames_dir}", frame_paths.len());
+            Ok(())
+        }
+        None => run_interactive(&mut player),
+    }
+}
+
+#[derive(Clone)]
+struct Warehouse {
+    /// Walls, boxes (`O`, or `[` and `]` when wide) and empty cells, without the robot
+    grid: Grid<char>,
+    robot: Pos,
+    moves: Vec<Direction>,
+    move_count: usize,
+}
+
+impl Warehouse {
+    fn parse(contents: &str, is_wide: bool) -> Self {
+        let (map_text, moves_text) = contents
+            .split_once("\n\n")
+            .or_else(|| contents.split_once("\r\n\r\n"))
+            .expect("A blank line is missing after the map");
+        let map_text = if is_wide {
+            map_text
+                .chars()
+                .map(|ch| match ch {
+                    'O' => "[]".to_string(),
+                    '@' => "@.".to_string(),
+                    '#' | '.' => ch.to_string().repeat(2),
+                    _ => ch.to_string(),
+                })
+                .collect()
+        } else {
+            map_text.to_string()
+        };
+        let mut robot = None;
+        let grid = Grid::parse_with(&map_text, |pos, ch| {
+            if ch == '@' {
+                robot = Some(pos);
+                '.'
+            } else {
+                ch
+            }
+        });
+        let moves = moves_text
+            .chars()
+            .filter(|ch| !ch.is_whitespace())
+            .map(|ch| Direction::from_arrow(ch).expect("Unrecognized move"))
+            .collect();
+        Self {
+            grid,
+            robot: robot.expect("The robot's position is missing"),
+            moves,
+            move_count: 0,
+        }
+    }
+
+    /// Move the robot, and any boxes in its way, unless a wall stops them
+    fn try_push(&mut self, dir: Direction) {
+        let mut box_cells = Vec::new();
+        let mut seen = HashSet::new();
+        let mut frontier = vec![self.robot];
+        while let Some(pos) = frontier.pop() {
+            let Some(next_pos) = self.grid.step(pos, dir) else {
+                return;
+            };
+            let mut push_box_cell = |box_pos: Pos| {
+                if seen.insert(box_pos) {
+                    box_cells.push(box_pos);
+                    frontier.push(box_pos);
+                }
+            };
+            match self.grid[next_pos] {
+                '#' => return,
+                'O' => push_box_cell(next_pos),
+                '[' => {
+                    push_box_cell(next_pos);
+                    if !dir.is_horizontal() {
+                        push_box_cell(Pos::new(next_pos.row, next_pos.col + 1));
+                    }
+                }
+                ']' => {
+                    push_box_cell(next_pos);
+                    if !dir.is_horizontal() {
+                        push_box_cell(Pos::new(next_pos.row, next_pos.col - 1));
+                    }
+                }
+                _ => {}
+            }
+        }
+
+        // Move the box cells furthest ahead first, so that none are overwritten
+        let (row_offset, col_offset) = dir.offset();
+        box_cells
+            .sort_by_key(|pos| -(pos.row as isize * row_offset + pos.col as isize * col_offset));
+        for pos in box_cells {
+            let next_pos = self.grid.step(pos, dir).unwrap();
+            self.grid[next_pos] = self.grid[pos];
+            self.grid[pos] = '.';
+        }
+        self.robot = self.grid.step(self.robot, dir).unwrap();
+    }
+
+    fn sum_of_gps_coordinates(&self) -> usize {
+        self.grid
+            .find_all(|&ch| ch == 'O' || ch == '[')
+            .map(|pos| 100 * pos.row + pos.col)
+            .sum()
+    }
+}
+
+impl Simulation for Warehouse {
+    fn title(&self) -> String {
+        "Advent of Code 2024: Day 15 warehouse robot".to_string()
+    }
+
+    fn step(&mut self) -> bool {
+        let Some(&dir) = self.moves.get(self.move_count) else {
+            return false;
+        };
+        self.try_push(dir);
+        self.move_count += 1;
+        true
+    }
+
+    fn to_grid(&self) -> Grid<char> {
+        let mut char_grid = self.grid.clone();
+        char_grid[self.robot] = '@';
+        char_grid
+    }
+
+    fn highlights(&self) -> Vec<Pos> {
+        vec![self.robot]
+    }
+
+    fn status(&self) -> String {
+        let next_move = self
+            .moves
+            .get(self.move_count)
+            .map_or(String::new(), |dir| {
+                format!(", next move {}", dir.to_arrow())
+            });
+        format!(
+            "GPS sum {}, {} of {} moves made{next_move}",
+            self.sum_of_gps_coordinates(),
+            self.move_count,
+            self.moves.len()
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SMALLER_EXAMPLE: &str = "\
+        ########\n\
+        #..O.O.#\n\
+        ##@.O..#\n\
+        #...O..#\n\
+        #.#.O..#\n\
+        #...O..#\n\
+        #......#\n\
+        ########\n\
+        \n\
+        <^^>>>vv<v>>v<<";
+
+    const WIDE_EXAMPLE: &str = "\
+        #######\n\
+        #...#.#\n\
+        #.....#\n\
+        #..OO@#\n\
+        #..O..#\n\
+        #.....#\n\
+        #######\n\
+        \n\
+        <vv<<^^<<^^";
+
+    fn run_to_end(warehouse: Warehouse) -> Player<Warehouse> {
+        let mut player = Player::new(warehouse);
+        while player.step_forward() {}
+        player
+    }
+
+    #[test]
+    fn test_smaller_example() {
+        let player = run_to_end(Warehouse::parse(SMALLER_EXAMPLE, false));
+        assert_eq!(player.simulation().sum_of_gps_coordinates(), 2028);
+        assert!(player
+            .frame_text()
+            .starts_with("Step 15: GPS sum 2028, 15 of 15 moves made\n"));
+        assert_eq!(
+            player.simulation().to_grid().render(|_, &ch| ch),
+            "########\n\
+             #....OO#\n\
+             ##.....#\n\
+             #.....O#\n\
+             #.#O@..#\n\
+             #...O..#\n\
+             #...O..#\n\
+             ########\n"
+        );
+    }
+
+    #[test]
+    fn test_wide_example() {
+        let player = run_to_end(Warehouse::parse(WIDE_EXAMPLE, true));
+        let frame = player.frame_text();
+        let lines: Vec<&str> = frame.lines().collect();
+        assert_eq!(lines[2], "##...[].##..##");
+        assert_eq!(lines[3], "##...@.[]...##");
+        assert_eq!(lines[4], "##....[]....##");
+    }
+
+    #[test]
+    fn test_dump_frames() {
+        let dir = std::env::temp_dir().join(format!("aoc2024_day15_frames_{}", std::process::id()));
+        let mut player = Player::new(Warehouse::parse(SMALLER_EXAMPLE, false));
+        let paths = dump_frames(&mut player, &dir, 4, MAX_STEPS_TO_DUMP).unwrap();
+        assert_eq!(paths.len(), 5);
+        assert!(std::fs::read_to_string(&paths[0])
+            .unwrap()
+            .starts_with("Step 0: GPS sum 1624, 0 of 15 moves made, next move <\n"));
+        assert!(std::fs::read_to_string(&paths[4])
+            .unwrap()
+            .starts_with("Step 15: GPS sum 2028"));
+        std::fs::remove_dir_all(dir).unwrap();
+    }
+}
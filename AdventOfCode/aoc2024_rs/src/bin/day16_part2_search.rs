@@ -0,0 +1,75 @@
+use aoc2024_rs::read_and_solve_and_time_more_runs;
+use aoc2024_rs::reindeer_maze::ReindeerMaze;
+use aoc_common::grid::Pos;
+use aoc_common::search::all_optimal_paths;
+use std::collections::HashSet;
+
+const INPUT_FILE_PATH: &str = "data/day16_input.txt";
+
+fn main() {
+    read_and_solve_and_time_more_runs(INPUT_FILE_PATH, "Day 16 part 2 (search)", solve, 1000);
+}
+
+fn solve(contents: &str) -> Option<usize> {
+    let maze = ReindeerMaze::parse(contents);
+    let paths = all_optimal_paths(&maze)?;
+    let cells: HashSet<Pos> = paths.distinct_on_any_path(|&(pos, _)| pos);
+    Some(cells.len())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_example_1() {
+        let contents = "###############\n\
+                        #.......#....E#\n\
+                        #.#.###.#.###.#\n\
+                        #.....#.#...#.#\n\
+                        #.###.#####.#.#\n\
+                        #.#.#.......#.#\n\
+                        #.#.#####.###.#\n\
+                        #...........#.#\n\
+                        ###.#.#####.#.#\n\
+                        #...#.....#.#.#\n\
+                        #.#.#.###.#.#.#\n\
+                        #.....#...#.#.#\n\
+                        #.###.#.#.#.#.#\n\
+                        #S..#.....#...#\n\
+                        ###############";
+
+        assert_eq!(solve(contents), Some(45));
+        assert_eq!(
+            all_optimal_paths(&ReindeerMaze::parse(contents)).map(|paths| paths.cost()),
+            Some(7036)
+        );
+    }
+
+    #[test]
+    fn test_example_2() {
+        let contents = "#################\n\
+                        #...#...#...#..E#\n\
+                        #.#.#.#.#.#.#.#.#\n\
+                        #.#.#.#...#...#.#\n\
+                        #.#.#.#.###.#.#.#\n\
+                        #...#.#.#.....#.#\n\
+                        #.#.#.#.#.#####.#\n\
+                        #.#...#.#.#.....#\n\
+                        #.#.#####.#.###.#\n\
+                        #.#.#.......#...#\n\
+                        #.#.###.#####.###\n\
+                        #.#.#...#.....#.#\n\
+                        #.#.#.#####.###.#\n\
+                        #.#.#.........#.#\n\
+                        #.#.#.#########.#\n\
+                        #S#.............#\n\
+                        #################";
+
+        assert_eq!(solve(contents), Some(64));
+        assert_eq!(
+            all_optimal_paths(&ReindeerMaze::parse(contents)).map(|paths| paths.cost()),
+            Some(11048)
+        );
+    }
+}
@@ -0,0 +1,174 @@
+//! Watch the reindeer follow a lowest scoring path through the day 16 maze in the terminal.
+//! Tiles on any of the best paths (part 2) are shown as `o`, and tiles walked as `X`.
+//! Pass a folder name to write the frames to text files instead.
+
+use aoc2024_rs::reindeer_maze::{ReindeerMaze, COST_TO_TURN};
+use aoc2024_rs::visualiser::{dump_frames, run_interactive, Player, Simulation};
+use aoc_common::grid::{Direction, Grid, Pos};
+use aoc_common::search::all_optimal_paths;
+use std::collections::HashSet;
+use std::path::Path;
+
+const INPUT_FILE_PATH: &str = "data/day16_input.txt";
+const MAX_STEPS_TO_DUMP: usize = 100_000;
+
+fn main() -> std::io::Result<()> {
+    let contents = std::fs::read_to_string(INPUT_FILE_PATH).expect("Input file not readable");
+    let mut player = Player::new(ReindeerWalk::new(&ReindeerMaze::parse(&contents)));
+    match std::env::args().nth(1) {
+        Some(frames_dir) => {
+            let frame_paths =
+                dump_frames(&mut player, Path::new(&frames_dir), 1, MAX_STEPS_TO_DUMP)?;
+            println!("Wrote {} frames to {frames_dir}", frame_paths.len());
+            Ok(())
+        }
+        None => run_interactive(&mut player),
+    }
+}
+
+#[derive(Clone)]
+struct ReindeerWalk {
+    grid: Grid<char>,
+    /// The states on one lowest scoring path, and the score on reaching each of them
+    path: Vec<((Pos, Direction), usize)>,
+    path_index: usize,
+    best_tile_count: usize,
+}
+
+impl ReindeerWalk {
+    fn new(maze: &ReindeerMaze) -> Self {
+        let paths = all_optimal_paths(maze).expect("There is no path to the end");
+        let best_tiles: HashSet<Pos> = paths.distinct_on_any_path(|&(pos, _)| pos);
+        let grid = maze.is_wall.map(|pos, &is_wall| {
+            if is_wall {
+                '#'
+            } else if pos == maze.end_pos {
+                'E'
+            } else if best_tiles.contains(&pos) {
+                'o'
+            } else {
+                '.'
+            }
+        });
+
+        // Follow any chain of predecessors back from a goal state to get one of the best paths
+        let mut states = vec![*paths.goal_states().next().unwrap()];
+        while let Some(&prev_state) = paths.predecessors(states.last().unwrap()).next() {
+            states.push(prev_state);
+        }
+        states.reverse();
+        let mut score = 0;
+        let path = states
+            .iter()
+            .enumerate()
+            .map(|(index, &(pos, dir))| {
+                if index > 0 {
+                    let (prev_pos, _) = states[index - 1];
+                    score += if prev_pos == pos { COST_TO_TURN } else { 1 };
+                }
+                ((pos, dir), score)
+            })
+            .collect();
+        Self {
+            grid,
+            path,
+            path_index: 0,
+            best_tile_count: best_tiles.len(),
+        }
+    }
+
+    fn reindeer(&self) -> (Pos, Direction) {
+        self.path[self.path_index].0
+    }
+}
+
+impl Simulation for ReindeerWalk {
+    fn title(&self) -> String {
+        "Advent of Code 2024: Day 16 reindeer maze".to_string()
+    }
+
+    fn step(&mut self) -> bool {
+        if self.path_index + 1 == self.path.len() {
+            return false;
+        }
+        let (pos, _) = self.reindeer();
+        self.grid[pos] = 'X';
+        self.path_index += 1;
+        true
+    }
+
+    fn to_grid(&self) -> Grid<char> {
+        let mut char_grid = self.grid.clone();
+        let (pos, dir) = self.reindeer();
+        char_grid[pos] = dir.to_arrow();
+        char_grid
+    }
+
+    fn highlights(&self) -> Vec<Pos> {
+        vec![self.reindeer().0]
+    }
+
+    fn status(&self) -> String {
+        let (_, score) = self.path[self.path_index];
+        let (_, final_score) = self.path[self.path.len() - 1];
+        format!(
+            "score {score} of {final_score}, {} tiles on the best paths",
+            self.best_tile_count
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const EXAMPLE: &str = "\
+        ###############\n\
+        #.......#....E#\n\
+        #.#.###.#.###.#\n\
+        #.....#.#...#.#\n\
+        #.###.#####.#.#\n\
+        #.#.#.......#.#\n\
+        #.#.#####.###.#\n\
+        #...........#.#\n\
+        ###.#.#####.#.#\n\
+        #...#.....#.#.#\n\
+        #.#.#.###.#.#.#\n\
+        #.....#...#.#.#\n\
+        #.###.#.#.#.#.#\n\
+        #S..#.....#...#\n\
+        ###############";
+
+    #[test]
+    fn test_walk_reaches_the_end_with_the_lowest_score() {
+        let mut player = Player::new(ReindeerWalk::new(&ReindeerMaze::parse(EXAMPLE)));
+        assert!(player
+            .frame_text()
+            .starts_with("Step 0: score 0 of 7036, 45 tiles on the best paths\n"));
+        while player.step_forward() {}
+        let frame = player.frame_text();
+        let lines: Vec<&str> = frame.lines().collect();
+        assert_eq!(
+            lines[0],
+            format!(
+                "Step {}: score 7036 of 7036, 45 tiles on the best paths",
+                player.step_count()
+            )
+        );
+        assert_eq!(lines[2], "#.......#....^#");
+        assert_eq!(lines[8], "#..ooXXXXXXX#X#");
+        assert_eq!(lines[14], "#X..#.....#XXX#");
+    }
+
+    #[test]
+    fn test_dump_frames() {
+        let dir = std::env::temp_dir().join(format!("aoc2024_day16_frames_{}", std::process::id()));
+        let mut player = Player::new(ReindeerWalk::new(&ReindeerMaze::parse(EXAMPLE)));
+        let paths = dump_frames(&mut player, &dir, 10, MAX_STEPS_TO_DUMP).unwrap();
+        assert_eq!(paths.len(), player.step_count().div_ceil(10) + 1);
+        let last_frame = std::fs::read_to_string(paths.last().unwrap()).unwrap();
+        assert_eq!(last_frame, player.frame_text());
+        assert!(last_frame.contains("score 7036 of 7036"));
+        std::fs::remove_dir_all(dir).unwrap();
+    }
+}
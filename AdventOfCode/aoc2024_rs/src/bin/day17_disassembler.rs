@@ -0,0 +1,21 @@
+//! Print the disassembly of the day 17 program, followed by a trace of running it
+
+use aoc2024_rs::three_bit_computer::{disassembly_listing, parse, Computer};
+
+const INPUT_FILE_PATH: &str = "data/day17_input.txt";
+
+fn main() {
+    let contents = std::fs::read_to_string(INPUT_FILE_PATH).expect("Input file not readable");
+    let (registers, program) = parse(&contents).unwrap_or_else(|err| panic!("{err}"));
+
+    println!("Program:");
+    print!("{}", disassembly_listing(&program));
+
+    println!();
+    println!("Trace (registers in octal):");
+    let mut computer = Computer::new(registers, &program);
+    let outputs = computer.run_traced(|entry| println!("{entry}"));
+    let output_strings: Vec<String> = outputs.iter().map(|output| output.to_string()).collect();
+    println!();
+    println!("Output: {}", output_strings.join(","));
+}
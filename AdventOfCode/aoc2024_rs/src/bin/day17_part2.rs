@@ -0,0 +1,44 @@
+use aoc2024_rs::read_and_try_solve_and_time_more_runs;
+use aoc2024_rs::three_bit_computer::{find_quine_register_a, parse, Word};
+use aoc_common::parse::ParseError;
+
+const INPUT_FILE_PATH: &str = "data/day17_input.txt";
+
+fn main() {
+    read_and_try_solve_and_time_more_runs(INPUT_FILE_PATH, "Day 17 part 2", solve, 1000);
+}
+
+fn solve(contents: &str) -> Result<Option<Word>, ParseError> {
+    let (registers, program) = parse(contents)?;
+    Ok(find_quine_register_a(&program, registers.b, registers.c))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use aoc2024_rs::three_bit_computer::{Computer, Registers};
+
+    #[test]
+    fn test_full_example() {
+        let contents = "Register A: 2024\n\
+                        Register B: 0\n\
+                        Register C: 0\n\
+                        \n\
+                        Program: 0,3,5,4,3,0\n";
+        assert_eq!(solve(contents), Ok(Some(117440)));
+    }
+
+    #[test]
+    fn test_typical_puzzle_program() {
+        // Puzzle inputs have this shape: a loop which outputs once for every 3 bits of A
+        let contents = "Register A: 0\n\
+                        Register B: 0\n\
+                        Register C: 0\n\
+                        \n\
+                        Program: 2,4,1,1,7,5,1,5,4,0,5,5,0,3,3,0\n";
+        let a = solve(contents).unwrap().unwrap();
+        let (registers, program) = parse(contents).unwrap();
+        let mut computer = Computer::new(Registers { a, ..registers }, &program);
+        assert_eq!(computer.run(), program);
+    }
+}
@@ -0,0 +1,65 @@
+use aoc2024_rs::read_and_solve_and_time_more_runs;
+use aoc_common::grid::{Direction, Grid};
+
+const INPUT_FILE_PATH: &str = "data/day6_input.txt";
+
+fn main() {
+    read_and_solve_and_time_more_runs(INPUT_FILE_PATH, "Day 6 part 1 (grid)", solve, 1000);
+}
+
+#[derive(Copy, Clone, PartialEq, Eq)]
+enum CellState {
+    Unvisited,
+    Visited,
+    Obstructed,
+}
+
+fn solve(contents: &str) -> usize {
+    let mut guard = None;
+    let mut grid = Grid::parse_with(contents, |pos, ch| match ch {
+        '.' => CellState::Unvisited,
+        '#' => CellState::Obstructed,
+        _ => {
+            guard = Some((pos, Direction::from_arrow(ch).unwrap()));
+            CellState::Visited
+        }
+    });
+    let (mut curr_pos, mut curr_dir) = guard.expect("The guard's starting position is missing");
+
+    // Patrol the grid until out-of-bounds
+    let mut visit_count: usize = 1;
+    while let Some(next_pos) = grid.step(curr_pos, curr_dir) {
+        match grid[next_pos] {
+            CellState::Unvisited => {
+                grid[next_pos] = CellState::Visited;
+                visit_count += 1;
+                curr_pos = next_pos;
+            }
+            CellState::Visited => curr_pos = next_pos,
+            CellState::Obstructed => curr_dir = curr_dir.turn_right(),
+        }
+    }
+    visit_count
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const EXAMPLE: &str = "\
+        ....#.....\n\
+        .........#\n\
+        ..........\n\
+        ..#.......\n\
+        .......#..\n\
+        ..........\n\
+        .#..^.....\n\
+        ........#.\n\
+        #.........\n\
+        ......#...";
+
+    #[test]
+    fn test_example() {
+        assert_eq!(solve(EXAMPLE), 41);
+    }
+}
@@ -0,0 +1,144 @@
+//! Watch the guard's patrol from day 6 in the terminal.
+//! Pass a folder name to write the frames to text files instead.
+
+use aoc2024_rs::visualiser::{dump_frames, run_interactive, Player, Simulation};
+use aoc_common::grid::{Direction, Grid, Pos};
+use std::path::Path;
+
+const INPUT_FILE_PATH: &str = "data/day6_input.txt";
+const MAX_STEPS_TO_DUMP: usize = 100_000;
+
+fn main() -> std::io::Result<()> {
+    let contents = std::fs::read_to_string(INPUT_FILE_PATH).expect("Input file not readable");
+    let mut player = Player::new(Patrol::parse(&contents));
+    match std::env::args().nth(1) {
+        Some(frames_dir) => {
+            let frame_paths =
+                dump_frames(&mut player, Path::new(&frames_dir), 1, MAX_STEPS_TO_DUMP)?;
+            println!("Wrote {} frames to {frames_dir}", frame_paths.len());
+            Ok(())
+        }
+        None => run_interactive(&mut player),
+    }
+}
+
+#[derive(Copy, Clone, PartialEq, Eq)]
+enum CellState {
+    Unvisited,
+    Visited,
+    Obstructed,
+}
+
+#[derive(Clone)]
+struct Patrol {
+    grid: Grid<CellState>,
+    /// The guard's position and direction, until the guard leaves the grid
+    guard: Option<(Pos, Direction)>,
+    visit_count: usize,
+}
+
+impl Patrol {
+    fn parse(contents: &str) -> Self {
+        let mut guard = None;
+        let grid = Grid::parse_with(contents, |pos, ch| match ch {
+            '.' => CellState::Unvisited,
+            '#' => CellState::Obstructed,
+            _ => {
+                guard = Some((pos, Direction::from_arrow(ch).unwrap()));
+                CellState::Visited
+            }
+        });
+        Self {
+            grid,
+            guard: Some(guard.expect("The guard's starting position is missing")),
+            visit_count: 1,
+        }
+    }
+}
+
+impl Simulation for Patrol {
+    fn title(&self) -> String {
+        "Advent of Code 2024: Day 6 guard patrol".to_string()
+    }
+
+    fn step(&mut self) -> bool {
+        let Some((pos, dir)) = self.guard else {
+            return false;
+        };
+        self.guard = match self.grid.step(pos, dir) {
+            None => None,
+            Some(next_pos) => match self.grid[next_pos] {
+                CellState::Obstructed => Some((pos, dir.turn_right())),
+                CellState::Visited => Some((next_pos, dir)),
+                CellState::Unvisited => {
+                    self.grid[next_pos] = CellState::Visited;
+                    self.visit_count += 1;
+                    Some((next_pos, dir))
+                }
+            },
+        };
+        true
+    }
+
+    fn to_grid(&self) -> Grid<char> {
+        let mut char_grid = self.grid.map(|_, cell| match cell {
+            CellState::Unvisited => '.',
+            CellState::Visited => 'X',
+            CellState::Obstructed => '#',
+        });
+        if let Some((pos, dir)) = self.guard {
+            char_grid[pos] = dir.to_arrow();
+        }
+        char_grid
+    }
+
+    fn highlights(&self) -> Vec<Pos> {
+        self.guard.map(|(pos, _)| pos).into_iter().collect()
+    }
+
+    fn status(&self) -> String {
+        format!("{} cells visited", self.visit_count)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const EXAMPLE: &str = "\
+        ....#.....\n\
+        .........#\n\
+        ..........\n\
+        ..#.......\n\
+        .......#..\n\
+        ..........\n\
+        .#..^.....\n\
+        ........#.\n\
+        #.........\n\
+        ......#...";
+
+    #[test]
+    fn test_patrol_visits_41_cells() {
+        let mut player = Player::new(Patrol::parse(EXAMPLE));
+        while player.step_forward() {}
+        assert_eq!(player.simulation().visit_count, 41);
+        assert!(player
+            .frame_text()
+            .starts_with("Step 55: 41 cells visited\n"));
+    }
+
+    #[test]
+    fn test_frame_after_first_turn() {
+        let mut player = Player::new(Patrol::parse(EXAMPLE));
+        for _ in 0..6 {
+            player.step_forward();
+        }
+        let frame = player.frame_text();
+        let lines: Vec<&str> = frame.lines().collect();
+        assert_eq!(lines[0], "Step 6: 6 cells visited");
+        assert_eq!(lines[2], "....>....#");
+        assert_eq!(lines[3], "....X.....");
+        player.step_back();
+        assert_eq!(player.frame_text().lines().nth(2), Some("....^....#"));
+    }
+}
@@ -0,0 +1,88 @@
+//! The reindeer maze from day 16, as a search problem over positions and facing directions
+
+use aoc_common::grid::{Direction, Grid, Pos};
+use aoc_common::search::SearchProblem;
+use std::cmp::Ordering;
+
+pub const COST_TO_TURN: usize = 1000;
+
+pub struct ReindeerMaze {
+    pub is_wall: Grid<bool>,
+    pub start_pos: Pos,
+    pub end_pos: Pos,
+}
+
+impl ReindeerMaze {
+    pub fn parse(contents: &str) -> Self {
+        let mut start_pos = None;
+        let mut end_pos = None;
+        let is_wall = Grid::parse_with(contents, |pos, ch| {
+            match ch {
+                'S' => start_pos = Some(pos),
+                'E' => end_pos = Some(pos),
+                _ => {}
+            }
+            ch == '#'
+        });
+        Self {
+            is_wall,
+            start_pos: start_pos.expect("The start position is missing"),
+            end_pos: end_pos.expect("The end position is missing"),
+        }
+    }
+}
+
+impl SearchProblem for ReindeerMaze {
+    type State = (Pos, Direction);
+    type Cost = usize;
+
+    fn start_states(&self) -> impl IntoIterator<Item = Self::State> {
+        [(self.start_pos, Direction::Right)]
+    }
+
+    fn successors(
+        &self,
+        &(pos, dir): &Self::State,
+    ) -> impl IntoIterator<Item = (Self::State, usize)> {
+        let forwards = self
+            .is_wall
+            .step(pos, dir)
+            .filter(|&next_pos| !self.is_wall[next_pos])
+            .map(|next_pos| ((next_pos, dir), 1));
+        forwards.into_iter().chain([
+            ((pos, dir.turn_left()), COST_TO_TURN),
+            ((pos, dir.turn_right()), COST_TO_TURN),
+        ])
+    }
+
+    fn is_goal(&self, (pos, _): &Self::State) -> bool {
+        *pos == self.end_pos
+    }
+
+    /// The cost of reaching the end if there were no walls in the way.
+    /// This is the exact cost in a relaxed problem, so it is consistent.
+    fn heuristic(&self, &(pos, dir): &Self::State) -> usize {
+        let vertical_dir = match pos.row.cmp(&self.end_pos.row) {
+            Ordering::Less => Some(Direction::Down),
+            Ordering::Equal => None,
+            Ordering::Greater => Some(Direction::Up),
+        };
+        let horizontal_dir = match pos.col.cmp(&self.end_pos.col) {
+            Ordering::Less => Some(Direction::Right),
+            Ordering::Equal => None,
+            Ordering::Greater => Some(Direction::Left),
+        };
+        let turns = match (vertical_dir, horizontal_dir) {
+            (None, None) => 0,
+            (Some(only_dir), None) | (None, Some(only_dir)) => dir.quarter_turns_to(only_dir),
+            (Some(vert_dir), Some(horiz_dir)) => {
+                if dir == vert_dir || dir == horiz_dir {
+                    1
+                } else {
+                    2
+                }
+            }
+        };
+        pos.manhattan_distance(self.end_pos) + turns * COST_TO_TURN
+    }
+}
@@ -0,0 +1,69 @@
+//! The robots patrolling the restroom in day 14, which wrap around at the edges of the area
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Robot {
+    pub p_x: i64,
+    pub p_y: i64,
+    pub v_x: i64,
+    pub v_y: i64,
+}
+
+impl Robot {
+    /// The robot's (x, y) position after the given number of steps
+    pub fn get_position(
+        &self,
+        step_count: usize,
+        area_width: usize,
+        area_height: usize,
+    ) -> (usize, usize) {
+        let n = step_count as i64;
+        let final_x = (self.p_x + self.v_x * n).rem_euclid(area_width as i64);
+        let final_y = (self.p_y + self.v_y * n).rem_euclid(area_height as i64);
+        (final_x as usize, final_y as usize)
+    }
+}
+
+/// Parse lines such as "p=0,4 v=3,-3"
+pub fn parse(contents: &str) -> Vec<Robot> {
+    contents.lines().map(parse_robot).collect()
+}
+
+fn parse_robot(line: &str) -> Robot {
+    let (left, right) = line.split_once(' ').unwrap();
+    let (p_x, p_y) = parse_pair(left);
+    let (v_x, v_y) = parse_pair(right);
+    Robot { p_x, p_y, v_x, v_y }
+}
+
+fn parse_pair(coordinates: &str) -> (i64, i64) {
+    let (left, right) = coordinates
+        .split_once('=')
+        .unwrap()
+        .1
+        .split_once(',')
+        .unwrap();
+    (left.parse::<i64>().unwrap(), right.parse::<i64>().unwrap())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_robot_wraps_around() {
+        let robots = parse("p=2,4 v=2,-3\np=0,0 v=1,3");
+        assert_eq!(
+            robots[0],
+            Robot {
+                p_x: 2,
+                p_y: 4,
+                v_x: 2,
+                v_y: -3
+            }
+        );
+        let positions: Vec<_> = (0..=5)
+            .map(|steps| robots[0].get_position(steps, 11, 7))
+            .collect();
+        assert_eq!(positions, [(2, 4), (4, 1), (6, 5), (8, 2), (10, 6), (1, 3)]);
+    }
+}
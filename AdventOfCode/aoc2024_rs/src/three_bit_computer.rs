@@ -0,0 +1,393 @@
+//! The 3-bit computer from day 17, with a disassembler, tracing,
+//! and a solver for register A values which make a program output a copy of itself.
+
+use aoc_common::parse::{Input, ParseError};
+use std::fmt::{Display, Formatter};
+
+pub type Word = u64;
+
+#[derive(Debug, Default, Copy, Clone, PartialEq, Eq)]
+pub struct Registers {
+    pub a: Word,
+    pub b: Word,
+    pub c: Word,
+}
+
+/// Registers are shown in octal, since the programs work on 3 bits at a time
+impl Display for Registers {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "a={:o} b={:o} c={:o}", self.a, self.b, self.c)
+    }
+}
+
+/// Parse the initial register values and the program
+pub fn parse(contents: &str) -> Result<(Registers, Vec<u8>), ParseError> {
+    let input = Input::new(contents);
+    let mut line_iter = input.lines();
+    let mut parse_register = |name: &str| {
+        let key = format!("Register {name}");
+        let line = input.next_line(input.text(), &mut line_iter, &key)?;
+        input.value_for_key(line, &key, ":")
+    };
+    let registers = Registers {
+        a: parse_register("A")?,
+        b: parse_register("B")?,
+        c: parse_register("C")?,
+    };
+    let program_line = input
+        .lines()
+        .skip(3)
+        .find(|line| !line.trim().is_empty())
+        .ok_or_else(|| input.error_at_end("expected the program, found end of input"))?;
+    let (_, program_text) = input.key_value(program_line, "Program:")?;
+    let program: Vec<u8> = input.numbers(program_text)?;
+    if let Some(&invalid) = program.iter().find(|&&num| num > 7) {
+        return Err(input.error_at(program_text, format!("{invalid} is not a 3-bit number")));
+    }
+    Ok((registers, program))
+}
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+#[repr(u8)]
+pub enum Opcode {
+    Adv,
+    Bxl,
+    Bst,
+    Jnz,
+    Bxc,
+    Out,
+    Bdv,
+    Cdv,
+}
+
+impl Opcode {
+    const ALL: [Opcode; 8] = [
+        Opcode::Adv,
+        Opcode::Bxl,
+        Opcode::Bst,
+        Opcode::Jnz,
+        Opcode::Bxc,
+        Opcode::Out,
+        Opcode::Bdv,
+        Opcode::Cdv,
+    ];
+
+    pub fn from_bits(bits: u8) -> Opcode {
+        Opcode::ALL[(bits & 7) as usize]
+    }
+
+    pub fn mnemonic(&self) -> &'static str {
+        match self {
+            Opcode::Adv => "adv",
+            Opcode::Bxl => "bxl",
+            Opcode::Bst => "bst",
+            Opcode::Jnz => "jnz",
+            Opcode::Bxc => "bxc",
+            Opcode::Out => "out",
+            Opcode::Bdv => "bdv",
+            Opcode::Cdv => "cdv",
+        }
+    }
+
+    fn has_combo_operand(&self) -> bool {
+        matches!(
+            self,
+            Opcode::Adv | Opcode::Bst | Opcode::Out | Opcode::Bdv | Opcode::Cdv
+        )
+    }
+}
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub struct Instruction {
+    pub opcode: Opcode,
+    pub operand: u8,
+}
+
+impl Display for Instruction {
+    /// Combo operands 4 to 6 are shown as the registers they refer to, and bxc's unused operand
+    /// is omitted, e.g. "bst a", "bxl 5", "bxc"
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        let mnemonic = self.opcode.mnemonic();
+        match (self.opcode, self.opcode.has_combo_operand(), self.operand) {
+            (Opcode::Bxc, _, _) => write!(f, "{mnemonic}"),
+            (_, true, 4) => write!(f, "{mnemonic} a"),
+            (_, true, 5) => write!(f, "{mnemonic} b"),
+            (_, true, 6) => write!(f, "{mnemonic} c"),
+            (_, true, 7) => write!(f, "{mnemonic} <invalid combo operand 7>"),
+            (_, _, operand) => write!(f, "{mnemonic} {operand}"),
+        }
+    }
+}
+
+pub fn disassemble(program: &[u8]) -> Vec<Instruction> {
+    program
+        .chunks_exact(2)
+        .map(|pair| Instruction {
+            opcode: Opcode::from_bits(pair[0]),
+            operand: pair[1],
+        })
+        .collect()
+}
+
+/// One line per instruction, prefixed by its address, e.g. "  2: bxl 5"
+pub fn disassembly_listing(program: &[u8]) -> String {
+    disassemble(program)
+        .iter()
+        .enumerate()
+        .map(|(index, instruction)| format!("{:>3}: {instruction}\n", 2 * index))
+        .collect()
+}
+
+/// The state of the computer before and after executing an instruction
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct TraceEntry {
+    pub ip: usize,
+    pub instruction: Instruction,
+    pub before: Registers,
+    pub after: Registers,
+    pub output: Option<u8>,
+}
+
+impl Display for TraceEntry {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        let instruction = self.instruction.to_string();
+        write!(f, "{:>3}: {instruction:<8} {}", self.ip, self.after)?;
+        if let Some(output) = self.output {
+            write!(f, " => {output}")?;
+        }
+        Ok(())
+    }
+}
+
+pub struct Computer<'p> {
+    pub registers: Registers,
+    pub ip: usize,
+    program: &'p [u8],
+}
+
+impl<'p> Computer<'p> {
+    pub fn new(registers: Registers, program: &'p [u8]) -> Self {
+        Self {
+            registers,
+            ip: 0,
+            program,
+        }
+    }
+
+    pub fn is_halted(&self) -> bool {
+        self.ip + 1 >= self.program.len()
+    }
+
+    /// Execute the next instruction, returning what was executed (or `None` if halted)
+    pub fn step(&mut self) -> Option<TraceEntry> {
+        if self.is_halted() {
+            return None;
+        }
+        let ip = self.ip;
+        let instruction = Instruction {
+            opcode: Opcode::from_bits(self.program[ip]),
+            operand: self.program[ip + 1],
+        };
+        let before = self.registers;
+        let mut output = None;
+        self.ip += 2;
+
+        let operand = instruction.operand;
+        let regs = &mut self.registers;
+        match instruction.opcode {
+            Opcode::Adv => regs.a >>= combo(&before, operand),
+            Opcode::Bxl => regs.b ^= operand as Word,
+            Opcode::Bst => regs.b = combo(&before, operand) % 8,
+            Opcode::Jnz => {
+                if regs.a != 0 {
+                    self.ip = operand as usize;
+                }
+            }
+            Opcode::Bxc => regs.b ^= regs.c,
+            Opcode::Out => output = Some((combo(&before, operand) % 8) as u8),
+            Opcode::Bdv => regs.b = regs.a >> combo(&before, operand),
+            Opcode::Cdv => regs.c = regs.a >> combo(&before, operand),
+        }
+
+        Some(TraceEntry {
+            ip,
+            instruction,
+            before,
+            after: self.registers,
+            output,
+        })
+    }
+
+    pub fn run(&mut self) -> Vec<u8> {
+        self.run_traced(|_| {})
+    }
+
+    /// Run until the program halts, passing each executed instruction to the tracer
+    pub fn run_traced<F: FnMut(&TraceEntry)>(&mut self, mut tracer: F) -> Vec<u8> {
+        let mut outputs = Vec::new();
+        while let Some(entry) = self.step() {
+            tracer(&entry);
+            outputs.extend(entry.output);
+        }
+        outputs
+    }
+
+    /// Run until the outputs stop matching the expected values, returning how many matched.
+    /// This will be one more than the number expected, if extra values are output.
+    pub fn count_matching_outputs(&mut self, expected: &[u8]) -> usize {
+        let mut match_count = 0;
+        while let Some(entry) = self.step() {
+            if let Some(output) = entry.output {
+                if expected.get(match_count) != Some(&output) {
+                    return match_count + usize::from(match_count == expected.len());
+                }
+                match_count += 1;
+            }
+        }
+        match_count
+    }
+}
+
+fn combo(registers: &Registers, operand: u8) -> Word {
+    match operand {
+        0..4 => operand as Word,
+        4 => registers.a,
+        5 => registers.b,
+        6 => registers.c,
+        _ => panic!("Invalid combo operand {operand}"),
+    }
+}
+
+/// Find the lowest value of register A which makes the program output a copy of itself.
+///
+/// This assumes the program is a loop which outputs one value, then shifts A right by 3 bits,
+/// until A is zero. So the last output depends only on the top 3 bits of A, the second last
+/// output on the top 6 bits, and so on. A is built up 3 bits at a time from the last output
+/// backwards, keeping every choice of 3 bits for which the outputs so far are correct.
+pub fn find_quine_register_a(program: &[u8], b: Word, c: Word) -> Option<Word> {
+    find_quine_suffix(program, b, c, program.len(), 0)
+}
+
+fn find_quine_suffix(
+    program: &[u8],
+    b: Word,
+    c: Word,
+    suffix_start: usize,
+    a: Word,
+) -> Option<Word> {
+    if suffix_start == 0 {
+        return Some(a);
+    }
+    let suffix_start = suffix_start - 1;
+    let expected = &program[suffix_start..];
+    (0..8).find_map(|bits| {
+        let next_a = (a << 3) | bits;
+        if next_a == 0 {
+            return None;
+        }
+        let mut computer = Computer::new(Registers { a: next_a, b, c }, program);
+        if computer.run() == expected {
+            find_quine_suffix(program, b, c, suffix_start, next_a)
+        } else {
+            None
+        }
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn run(a: Word, b: Word, c: Word, program: &[u8]) -> (Registers, Vec<u8>) {
+        let mut computer = Computer::new(Registers { a, b, c }, program);
+        let outputs = computer.run();
+        (computer.registers, outputs)
+    }
+
+    #[test]
+    fn test_small_examples() {
+        assert_eq!(run(0, 0, 9, &[2, 6]).0.b, 1);
+        assert_eq!(run(10, 0, 0, &[5, 0, 5, 1, 5, 4]).1, [0, 1, 2]);
+        let (registers, outputs) = run(2024, 0, 0, &[0, 1, 5, 4, 3, 0]);
+        assert_eq!(outputs, [4, 2, 5, 6, 7, 7, 7, 7, 3, 1, 0]);
+        assert_eq!(registers.a, 0);
+        assert_eq!(run(0, 29, 0, &[1, 7]).0.b, 26);
+        assert_eq!(run(0, 2024, 43690, &[4, 0]).0.b, 44354);
+    }
+
+    #[test]
+    fn test_parse_and_run() {
+        let contents = "Register A: 729\n\
+                        Register B: 0\n\
+                        Register C: 0\n\
+                        \n\
+                        Program: 0,1,5,4,3,0\n";
+        let (registers, program) = parse(contents).unwrap();
+        assert_eq!(registers.a, 729);
+        assert_eq!(program, [0, 1, 5, 4, 3, 0]);
+        let outputs = Computer::new(registers, &program).run();
+        assert_eq!(outputs, [4, 6, 3, 5, 6, 3, 5, 2, 1, 0]);
+    }
+
+    #[test]
+    fn test_parse_error_is_located() {
+        let err =
+            parse("Register A: 729\nRegister B: 0\nRegister C: 0\n\nProgram: 0,1,9\n").unwrap_err();
+        assert_eq!((err.line, err.column), (5, 10));
+    }
+
+    #[test]
+    fn test_disassembly() {
+        let program = [2, 4, 1, 5, 7, 5, 4, 3, 0, 3, 5, 5, 3, 0];
+        assert_eq!(
+            disassembly_listing(&program),
+            "  0: bst a\n  2: bxl 5\n  4: cdv b\n  6: bxc\n  8: adv 3\n 10: out b\n 12: jnz 0\n"
+        );
+    }
+
+    #[test]
+    fn test_trace() {
+        let program = [0, 1, 5, 4, 3, 0];
+        let mut computer = Computer::new(Registers { a: 9, b: 0, c: 0 }, &program);
+        let mut trace = Vec::new();
+        computer.run_traced(|entry| trace.push(entry.to_string()));
+        assert_eq!(
+            trace[..4],
+            [
+                "  0: adv 1    a=4 b=0 c=0",
+                "  2: out a    a=4 b=0 c=0 => 4",
+                "  4: jnz 0    a=4 b=0 c=0",
+                "  0: adv 1    a=2 b=0 c=0",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_count_matching_outputs() {
+        let program = [0, 1, 5, 4, 3, 0];
+        let mut computer = Computer::new(
+            Registers {
+                a: 2024,
+                b: 0,
+                c: 0,
+            },
+            &program,
+        );
+        assert_eq!(computer.count_matching_outputs(&[4, 2, 5, 9]), 3);
+        let mut computer = Computer::new(
+            Registers {
+                a: 2024,
+                b: 0,
+                c: 0,
+            },
+            &program,
+        );
+        assert_eq!(computer.count_matching_outputs(&[4, 2]), 3);
+    }
+
+    #[test]
+    fn test_find_quine_register_a() {
+        let program = [0, 3, 5, 4, 3, 0];
+        assert_eq!(find_quine_register_a(&program, 0, 0), Some(117440));
+    }
+}
@@ -0,0 +1,392 @@
+//! An interactive terminal viewer for any grid-based simulation, with a headless mode
+//! which writes frames to text files instead.
+
+use aoc_common::grid::{Grid, Pos};
+use crossterm::event::{self, Event, KeyCode, KeyEventKind};
+use ratatui::{
+    buffer::Buffer,
+    layout::Rect,
+    style::{Style, Stylize},
+    symbols::border,
+    text::{Line, Span, Text},
+    widgets::{Block, Paragraph, Widget},
+    DefaultTerminal,
+};
+use std::collections::HashSet;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+const TICK_DURATION: Duration = Duration::from_millis(50);
+const MAX_STEPS_PER_TICK: usize = 1024;
+const DEFAULT_SNAPSHOT_INTERVAL: usize = 100;
+
+pub trait Simulation {
+    fn title(&self) -> String;
+
+    /// Advance one step, returning false (and leaving the state unchanged) if it has finished
+    fn step(&mut self) -> bool;
+
+    fn to_grid(&self) -> Grid<char>;
+
+    /// Cells to draw attention to, such as the guard's position.
+    /// They are coloured in the terminal, but text frames just show the grid's own characters.
+    fn highlights(&self) -> Vec<Pos> {
+        Vec::new()
+    }
+
+    /// Extra information to show above the grid
+    fn status(&self) -> String {
+        String::new()
+    }
+}
+
+/// Controls the playback of a simulation.
+///
+/// Stepping back restores the latest snapshot before the previous step, then replays from there.
+/// Snapshots are taken every so many steps, to trade off memory against replay time.
+pub struct Player<S> {
+    simulation: S,
+    step_count: usize,
+    snapshots: Vec<S>,
+    snapshot_interval: usize,
+    is_finished: bool,
+    is_playing: bool,
+    steps_per_tick: usize,
+}
+
+impl<S: Simulation + Clone> Player<S> {
+    pub fn new(simulation: S) -> Self {
+        Self {
+            snapshots: vec![simulation.clone()],
+            simulation,
+            step_count: 0,
+            snapshot_interval: DEFAULT_SNAPSHOT_INTERVAL,
+            is_finished: false,
+            is_playing: false,
+            steps_per_tick: 1,
+        }
+    }
+
+    pub fn with_snapshot_interval(mut self, snapshot_interval: usize) -> Self {
+        self.snapshot_interval = snapshot_interval.max(1);
+        self
+    }
+
+    pub fn simulation(&self) -> &S {
+        &self.simulation
+    }
+
+    pub fn step_count(&self) -> usize {
+        self.step_count
+    }
+
+    pub fn is_finished(&self) -> bool {
+        self.is_finished
+    }
+
+    pub fn step_forward(&mut self) -> bool {
+        if self.is_finished || !self.simulation.step() {
+            self.is_finished = true;
+            return false;
+        }
+        self.step_count += 1;
+        if self.step_count.is_multiple_of(self.snapshot_interval)
+            && self.snapshots.len() == self.step_count / self.snapshot_interval
+        {
+            self.snapshots.push(self.simulation.clone());
+        }
+        true
+    }
+
+    pub fn step_back(&mut self) -> bool {
+        if self.step_count == 0 {
+            return false;
+        }
+        let target_step = self.step_count - 1;
+        let snapshot_index = target_step / self.snapshot_interval;
+        self.simulation = self.snapshots[snapshot_index].clone();
+        for _ in snapshot_index * self.snapshot_interval..target_step {
+            self.simulation.step();
+        }
+        self.step_count = target_step;
+        self.is_finished = false;
+        true
+    }
+
+    pub fn restart(&mut self) {
+        self.simulation = self.snapshots[0].clone();
+        self.step_count = 0;
+        self.is_finished = false;
+    }
+
+    /// The current state as text: a heading line, then the grid
+    pub fn frame_text(&self) -> String {
+        let grid = self.simulation.to_grid().render(|_, &ch| ch);
+        format!("{}\n{grid}", self.heading())
+    }
+
+    fn heading(&self) -> String {
+        let status = self.simulation.status();
+        if status.is_empty() {
+            format!("Step {}", self.step_count)
+        } else {
+            format!("Step {}: {status}", self.step_count)
+        }
+    }
+
+    fn toggle_playing(&mut self) {
+        self.is_playing = !self.is_playing && !self.is_finished;
+    }
+
+    fn on_tick(&mut self) {
+        if self.is_playing {
+            for _ in 0..self.steps_per_tick {
+                if !self.step_forward() {
+                    self.is_playing = false;
+                    break;
+                }
+            }
+        }
+    }
+}
+
+/// Run the simulation in the terminal until the user quits
+pub fn run_interactive<S: Simulation + Clone>(player: &mut Player<S>) -> io::Result<()> {
+    let mut terminal = ratatui::init();
+    let result = run_event_loop(&mut terminal, player);
+    ratatui::restore();
+    result
+}
+
+fn run_event_loop<S: Simulation + Clone>(
+    terminal: &mut DefaultTerminal,
+    player: &mut Player<S>,
+) -> io::Result<()> {
+    loop {
+        terminal.draw(|frame| frame.render_widget(&*player, frame.area()))?;
+        if !event::poll(TICK_DURATION)? {
+            player.on_tick();
+            continue;
+        }
+        match event::read()? {
+            // Crossterm also emits key release and repeat events on Windows, so check for presses
+            Event::Key(key_event) if key_event.kind == KeyEventKind::Press => {
+                match key_event.code {
+                    KeyCode::Char('q') => return Ok(()),
+                    KeyCode::Char(' ') => player.toggle_playing(),
+                    KeyCode::Right => {
+                        player.step_forward();
+                    }
+                    KeyCode::Left => {
+                        player.step_back();
+                    }
+                    KeyCode::Char('+') | KeyCode::Char('=') | KeyCode::Up => {
+                        player.steps_per_tick = (player.steps_per_tick * 2).min(MAX_STEPS_PER_TICK);
+                    }
+                    KeyCode::Char('-') | KeyCode::Down => {
+                        player.steps_per_tick = (player.steps_per_tick / 2).max(1);
+                    }
+                    KeyCode::Home => player.restart(),
+                    _ => {}
+                }
+            }
+            _ => {}
+        }
+    }
+}
+
+impl<S: Simulation + Clone> Widget for &Player<S> {
+    fn render(self, area: Rect, buf: &mut Buffer) {
+        let title = Line::from(format!(" {} ", self.simulation.title()).bold());
+        let instructions = Line::from(vec![
+            " Back ".into(),
+            "<Left>".blue().bold(),
+            " Step ".into(),
+            "<Right>".blue().bold(),
+            " Play/Pause ".into(),
+            "<Space>".blue().bold(),
+            " Speed ".into(),
+            "<+/->".blue().bold(),
+            " Restart ".into(),
+            "<Home>".blue().bold(),
+            " Quit ".into(),
+            "<Q> ".blue().bold(),
+        ]);
+        let block = Block::bordered()
+            .title(title.centered())
+            .title_bottom(instructions.centered())
+            .border_set(border::THICK);
+
+        let state = if self.is_finished {
+            "finished"
+        } else if self.is_playing {
+            "playing"
+        } else {
+            "paused"
+        };
+        let mut lines = vec![Line::from(vec![
+            self.heading().yellow(),
+            format!("  ({state}, {} steps per tick)", self.steps_per_tick).into(),
+        ])];
+
+        let highlights: HashSet<Pos> = self.simulation.highlights().into_iter().collect();
+        let grid = self.simulation.to_grid();
+        let cell_style = Style::new().green();
+        let highlight_style = Style::new().black().on_yellow().bold();
+        lines.extend((0..grid.height()).map(|row| {
+            let spans: Vec<Span> = grid
+                .row(row)
+                .iter()
+                .enumerate()
+                .map(|(col, ch)| {
+                    let style = if highlights.contains(&Pos::new(row, col)) {
+                        highlight_style
+                    } else {
+                        cell_style
+                    };
+                    Span::styled(ch.to_string(), style)
+                })
+                .collect();
+            Line::from(spans)
+        }));
+
+        Paragraph::new(Text::from(lines))
+            .centered()
+            .block(block)
+            .render(area, buf);
+    }
+}
+
+/// Write the initial frame and then every `frame_interval` steps to text files in the folder,
+/// until the simulation finishes or `max_steps` is reached.
+/// Returns the paths of the files written, which are named like "frame_000042.txt".
+pub fn dump_frames<S: Simulation + Clone>(
+    player: &mut Player<S>,
+    dir: &Path,
+    frame_interval: usize,
+    max_steps: usize,
+) -> io::Result<Vec<PathBuf>> {
+    std::fs::create_dir_all(dir)?;
+    let mut frame_paths = Vec::new();
+    let mut write_frame = |player: &Player<S>| {
+        let path = dir.join(format!("frame_{:06}.txt", player.step_count()));
+        std::fs::write(&path, player.frame_text())?;
+        frame_paths.push(path);
+        io::Result::Ok(())
+    };
+
+    write_frame(player)?;
+    let frame_interval = frame_interval.max(1);
+    while player.step_count() < max_steps && player.step_forward() {
+        if player.step_count().is_multiple_of(frame_interval) {
+            write_frame(player)?;
+        }
+    }
+    if !player.step_count().is_multiple_of(frame_interval) {
+        write_frame(player)?;
+    }
+    Ok(frame_paths)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ratatui::backend::TestBackend;
+    use ratatui::Terminal;
+
+    /// A dot moving right along a row until it reaches the end
+    #[derive(Clone)]
+    struct Dot {
+        col: usize,
+    }
+
+    impl Simulation for Dot {
+        fn title(&self) -> String {
+            "Dot".to_string()
+        }
+
+        fn step(&mut self) -> bool {
+            if self.col == 4 {
+                return false;
+            }
+            self.col += 1;
+            true
+        }
+
+        fn to_grid(&self) -> Grid<char> {
+            let mut grid = Grid::new(5, 1, '.');
+            grid[Pos::new(0, self.col)] = '>';
+            grid
+        }
+
+        fn highlights(&self) -> Vec<Pos> {
+            vec![Pos::new(0, 0)]
+        }
+
+        fn status(&self) -> String {
+            format!("col {}", self.col)
+        }
+    }
+
+    #[test]
+    fn test_stepping_forwards_and_back() {
+        let mut player = Player::new(Dot { col: 0 }).with_snapshot_interval(3);
+        while player.step_forward() {}
+        assert!(player.is_finished());
+        assert_eq!(player.step_count(), 4);
+        assert!(player.step_back());
+        assert!(player.step_back());
+        assert_eq!(player.simulation().col, 2);
+        assert!(!player.is_finished());
+        assert!(player.step_forward());
+        assert_eq!(player.simulation().col, 3);
+        player.restart();
+        assert!(!player.step_back());
+        assert_eq!(player.simulation().col, 0);
+    }
+
+    #[test]
+    fn test_frame_text() {
+        let mut player = Player::new(Dot { col: 0 });
+        player.step_forward();
+        assert_eq!(player.frame_text(), "Step 1: col 1\n.>...\n");
+    }
+
+    #[test]
+    fn test_dump_frames() {
+        let dir = std::env::temp_dir().join(format!("aoc2024_frames_{}", std::process::id()));
+        let mut player = Player::new(Dot { col: 0 });
+        let paths = dump_frames(&mut player, &dir, 3, 100).unwrap();
+        let file_names: Vec<_> = paths
+            .iter()
+            .map(|path| path.file_name().unwrap().to_str().unwrap())
+            .collect();
+        assert_eq!(
+            file_names,
+            ["frame_000000.txt", "frame_000003.txt", "frame_000004.txt"]
+        );
+        assert_eq!(
+            std::fs::read_to_string(&paths[2]).unwrap(),
+            "Step 4: col 4\n....>\n"
+        );
+        std::fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn test_render_widget() {
+        let mut terminal = Terminal::new(TestBackend::new(100, 5)).unwrap();
+        let player = Player::new(Dot { col: 2 });
+        terminal
+            .draw(|frame| frame.render_widget(&player, frame.area()))
+            .unwrap();
+        let buffer = terminal.backend().buffer();
+        let rows: Vec<String> = (0..5)
+            .map(|y| (0..100).map(|x| buffer[(x, y)].symbol()).collect())
+            .collect();
+        assert!(rows[0].contains(" Dot "));
+        assert!(rows[1].contains("Step 0: col 2  (paused, 1 steps per tick)"));
+        assert!(rows[2].contains("..>.."));
+        assert!(rows[4].contains("<Space>"));
+    }
+}
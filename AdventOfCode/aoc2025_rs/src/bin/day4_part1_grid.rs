@@ -0,0 +1,44 @@
+use aoc_common::grid::Grid;
+use aoc2025_rs::load_and_solve_and_benchmark;
+
+const INPUT_FILE_PATH: &str = "data/day4_input.txt";
+
+fn main() {
+    load_and_solve_and_benchmark(INPUT_FILE_PATH, "Day 4 part 1 (grid)", solve, 10_000);
+}
+
+fn solve(contents: &str) -> usize {
+    // A cell is true if there is a roll of paper in it.
+    // The grid checks bounds, so no border of empty cells is needed.
+    let grid = Grid::parse_with(contents, |_, ch| ch == '@');
+    grid.find_all(|&has_roll| has_roll)
+        .filter(|&pos| {
+            grid.neighbours_8(pos)
+                .filter(|&neighbour| grid[neighbour])
+                .count()
+                < 4
+        })
+        .count()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const EXAMPLE: &str = "\
+        ..@@.@@@@.\n\
+        @@@.@.@.@@\n\
+        @@@@@.@.@@\n\
+        @.@@@@..@.\n\
+        @@.@@@@.@@\n\
+        .@@@@@@@.@\n\
+        .@.@.@.@@@\n\
+        @.@@@.@@@@\n\
+        .@@@@@@@@.\n\
+        @.@.@@@.@.";
+
+    #[test]
+    fn test_example() {
+        assert_eq!(solve(EXAMPLE), 13);
+    }
+}
@@ -0,0 +1,46 @@
+use aoc_common::automaton::{Automaton, SparseGrid, Window};
+use aoc2025_rs::load_and_solve_and_benchmark;
+
+const INPUT_FILE_PATH: &str = "data/day4_input.txt";
+
+fn main() {
+    load_and_solve_and_benchmark(INPUT_FILE_PATH, "Day 4 part 2 (automaton)", solve, 100);
+}
+
+fn solve(contents: &str) -> usize {
+    let rolls = SparseGrid::parse_with(contents, false, |_, c| c == '@');
+    let initial_roll_count = rolls.len();
+
+    // A roll stays if it has more than 3 neighbours, i.e. more than 4 rolls in its window
+    let keep_roll = |window: &Window<bool>| {
+        window[4] && window.iter().filter(|&&has_roll| has_roll).count() > 4
+    };
+    let mut automaton = Automaton::new(rolls, keep_roll);
+    // Every generation before the grid stabilises removes at least one roll
+    automaton
+        .run_until_stable(initial_roll_count as u64)
+        .expect("The rolls should stop being removed once they run out");
+    initial_roll_count - automaton.grid().len()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const EXAMPLE: &str = "\
+        ..@@.@@@@.\n\
+        @@@.@.@.@@\n\
+        @@@@@.@.@@\n\
+        @.@@@@..@.\n\
+        @@.@@@@.@@\n\
+        .@@@@@@@.@\n\
+        .@.@.@.@@@\n\
+        @.@@@.@@@@\n\
+        .@@@@@@@@.\n\
+        @.@.@@@.@.";
+
+    #[test]
+    fn test_example() {
+        assert_eq!(solve(EXAMPLE), 43);
+    }
+}
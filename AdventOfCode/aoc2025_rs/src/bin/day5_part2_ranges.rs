@@ -0,0 +1,42 @@
+use aoc_common::ranges::RangeSet;
+use aoc2025_rs::load_and_solve_and_benchmark;
+
+const INPUT_FILE_PATH: &str = "data/day5_input.txt";
+
+fn main() {
+    load_and_solve_and_benchmark(INPUT_FILE_PATH, "Day 5 part 2 (ranges)", solve, 100_000);
+}
+
+fn solve(contents: &str) -> usize {
+    let fresh_ids: RangeSet<usize> = contents
+        .lines()
+        .take_while(|line| !line.is_empty())
+        .map(|line| {
+            let (min_str, max_str) = line.split_once('-').unwrap();
+            min_str.parse::<usize>().unwrap()..=max_str.parse::<usize>().unwrap()
+        })
+        .collect();
+    fresh_ids.count()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const EXAMPLE: &str = "\
+        3-5\n\
+        10-14\n\
+        16-20\n\
+        12-18\n\
+        \n\
+        1\n\
+        5\n\
+        8\n\
+        11\n\
+        17";
+
+    #[test]
+    fn test_example() {
+        assert_eq!(solve(EXAMPLE), 14);
+    }
+}
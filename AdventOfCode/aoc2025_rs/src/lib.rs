@@ -1,6 +1,9 @@
+use aoc_common::input::InputProvider;
 use std::hint::black_box;
 use std::time::Instant;
 
+const YEAR: u16 = 2025;
+
 pub fn load_and_solve_and_benchmark<S, T>(
     file_path: &str,
     problem_desc: &str,
@@ -13,8 +16,11 @@ pub fn load_and_solve_and_benchmark<S, T>(
     // The first timing includes file I/O
     let start_time = Instant::now();
 
-    // first read the file contents into memory so that benchmarks don't include file I/O
-    let contents = std::fs::read_to_string(file_path).expect("Input file not readable");
+    // first read the file contents into memory so that benchmarks don't include file I/O.
+    // A missing input file is downloaded, if the AOC_SESSION environment variable is set.
+    let contents = InputProvider::from_env(YEAR)
+        .load_file(file_path)
+        .unwrap_or_else(|err| panic!("Input file not readable: {err}"));
 
     // Solve once and print out the solution and its duration (including file I/O)
     let solution = solve(&contents);
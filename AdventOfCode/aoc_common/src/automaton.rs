@@ -0,0 +1,345 @@
+//! Cellular automata, where every cell's next state depends on the 3x3 window around it.
+//!
+//! The grid is unbounded: only cells which differ from the background are stored,
+//! and the background evolves by the same rule, so it may flip between generations.
+
+mod hashlife;
+
+pub use hashlife::HashLife;
+
+use crate::grid::{Grid, Pos};
+use std::collections::{BTreeMap, HashMap};
+use std::hash::Hash;
+
+/// A (row, column) position on an unbounded grid
+pub type Coords = (i64, i64);
+
+/// A cell and its 8 neighbours, row by row, so the cell itself is at index 4
+pub type Window<T> = [T; 9];
+
+/// An unbounded grid, which stores the cells that differ from the background
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct SparseGrid<T> {
+    background: T,
+    cells: BTreeMap<Coords, T>,
+}
+
+impl<T: Copy + Eq> SparseGrid<T> {
+    pub fn new(background: T) -> Self {
+        Self {
+            background,
+            cells: BTreeMap::new(),
+        }
+    }
+
+    /// Copy a grid, with its top left cell at (0, 0)
+    pub fn from_grid(grid: &Grid<T>, background: T) -> Self {
+        let mut sparse_grid = Self::new(background);
+        for (pos, &cell) in grid.iter() {
+            sparse_grid.set((pos.row as i64, pos.col as i64), cell);
+        }
+        sparse_grid
+    }
+
+    /// Parse a block of lines, as for [`Grid::parse_with`]
+    pub fn parse_with<F>(text: &str, background: T, to_cell: F) -> Self
+    where
+        F: FnMut(Pos, char) -> T,
+    {
+        Self::from_grid(&Grid::parse_with(text, to_cell), background)
+    }
+
+    pub fn background(&self) -> T {
+        self.background
+    }
+
+    pub fn get(&self, coords: Coords) -> T {
+        self.cells.get(&coords).copied().unwrap_or(self.background)
+    }
+
+    pub fn set(&mut self, coords: Coords, cell: T) {
+        if cell == self.background {
+            self.cells.remove(&coords);
+        } else {
+            self.cells.insert(coords, cell);
+        }
+    }
+
+    /// The cells which differ from the background, in row order
+    pub fn cells(&self) -> impl Iterator<Item = (Coords, T)> + '_ {
+        self.cells.iter().map(|(&coords, &cell)| (coords, cell))
+    }
+
+    /// The number of cells which differ from the background
+    pub fn len(&self) -> usize {
+        self.cells.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.cells.is_empty()
+    }
+
+    /// The top left and bottom right corners of the smallest rectangle
+    /// which contains every cell that differs from the background
+    pub fn bounds(&self) -> Option<(Coords, Coords)> {
+        let min_row = self.cells.keys().next()?.0;
+        let max_row = self.cells.keys().next_back()?.0;
+        let (min_col, max_col) = self
+            .cells
+            .keys()
+            .fold((i64::MAX, i64::MIN), |(min_col, max_col), &(_, col)| {
+                (min_col.min(col), max_col.max(col))
+            });
+        Some(((min_row, min_col), (max_row, max_col)))
+    }
+
+    pub fn window(&self, (row, col): Coords) -> Window<T> {
+        let mut window = [self.background; 9];
+        for (i, cell) in window.iter_mut().enumerate() {
+            *cell = self.get((row + i as i64 / 3 - 1, col + i as i64 % 3 - 1));
+        }
+        window
+    }
+
+    /// Apply the rule to every cell at once. The background is updated by applying the rule
+    /// to a window of background cells.
+    pub fn step<R>(&self, rule: R) -> Self
+    where
+        R: Fn(&Window<T>) -> T,
+    {
+        let mut next = Self::new(rule(&[self.background; 9]));
+        if let Some(((min_row, min_col), (max_row, max_col))) = self.bounds() {
+            for row in min_row - 1..=max_row + 1 {
+                for col in min_col - 1..=max_col + 1 {
+                    next.set((row, col), rule(&self.window((row, col))));
+                }
+            }
+        }
+        next
+    }
+
+    /// Copy the cells inside the bounds to a grid, or return None if every cell is background
+    pub fn to_grid(&self) -> Option<Grid<T>> {
+        let ((min_row, min_col), (max_row, max_col)) = self.bounds()?;
+        let width = (max_col - min_col + 1) as usize;
+        let height = (max_row - min_row + 1) as usize;
+        let mut grid = Grid::new(width, height, self.background);
+        for ((row, col), cell) in self.cells() {
+            grid[Pos::new((row - min_row) as usize, (col - min_col) as usize)] = cell;
+        }
+        Some(grid)
+    }
+
+    /// Render the cells inside the bounds, with a line feed after each row
+    pub fn render<F>(&self, mut to_char: F) -> String
+    where
+        F: FnMut(T) -> char,
+    {
+        self.to_grid()
+            .map_or_else(String::new, |grid| grid.render(|_, &cell| to_char(cell)))
+    }
+}
+
+/// A repeating sequence of states, which first occurs at generation `start`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Cycle {
+    pub start: u64,
+    pub length: u64,
+}
+
+impl Cycle {
+    /// The earliest generation with the same state as the given generation
+    pub fn equivalent_generation(&self, generation: u64) -> u64 {
+        if generation < self.start {
+            generation
+        } else {
+            self.start + (generation - self.start) % self.length
+        }
+    }
+}
+
+/// A sparse grid, with the rule for updating it
+pub struct Automaton<T, R> {
+    grid: SparseGrid<T>,
+    rule: R,
+    generation: u64,
+}
+
+impl<T, R> Automaton<T, R>
+where
+    T: Copy + Eq + Hash,
+    R: Fn(&Window<T>) -> T,
+{
+    pub fn new(grid: SparseGrid<T>, rule: R) -> Self {
+        Self {
+            grid,
+            rule,
+            generation: 0,
+        }
+    }
+
+    pub fn grid(&self) -> &SparseGrid<T> {
+        &self.grid
+    }
+
+    pub fn generation(&self) -> u64 {
+        self.generation
+    }
+
+    pub fn step(&mut self) {
+        self.grid = self.grid.step(&self.rule);
+        self.generation += 1;
+    }
+
+    pub fn run(&mut self, generations: u64) {
+        for _ in 0..generations {
+            self.step();
+        }
+    }
+
+    /// Step until the grid stops changing, and return the generation when that happened.
+    /// Returns `None` if the grid is still changing at the maximum generation,
+    /// as it always will be for patterns which oscillate or move, such as blinkers and gliders.
+    pub fn run_until_stable(&mut self, max_generation: u64) -> Option<u64> {
+        while self.generation <= max_generation {
+            let next = self.grid.step(&self.rule);
+            if next == self.grid {
+                return Some(self.generation);
+            }
+            self.grid = next;
+            self.generation += 1;
+        }
+        None
+    }
+
+    /// Step until a state repeats, or the maximum generation is reached.
+    /// The automaton is left in the first repeated state.
+    pub fn find_cycle(&mut self, max_generation: u64) -> Option<Cycle> {
+        let mut seen: HashMap<SparseGrid<T>, u64> = HashMap::new();
+        while self.generation <= max_generation {
+            if let Some(&start) = seen.get(&self.grid) {
+                return Some(Cycle {
+                    start,
+                    length: self.generation - start,
+                });
+            }
+            seen.insert(self.grid.clone(), self.generation);
+            self.step();
+        }
+        None
+    }
+}
+
+/// Trigger every cell for which `is_triggered` holds, then let each triggered cell affect its
+/// 8 neighbours, which may trigger them too. Each cell is triggered at most once.
+/// Returns the triggered positions, in the order they were triggered.
+pub fn cascade<T, P, A>(grid: &mut Grid<T>, is_triggered: P, mut affect: A) -> Vec<Pos>
+where
+    P: Fn(&T) -> bool,
+    A: FnMut(&mut T),
+{
+    let mut has_triggered = grid.map(|_, _| false);
+    let mut triggered: Vec<Pos> = grid.find_all(&is_triggered).collect();
+    for &pos in &triggered {
+        has_triggered[pos] = true;
+    }
+    let mut next_index = 0;
+    while let Some(&pos) = triggered.get(next_index) {
+        next_index += 1;
+        let neighbours: Vec<Pos> = grid.neighbours_8(pos).collect();
+        for neighbour in neighbours {
+            if has_triggered[neighbour] {
+                continue;
+            }
+            affect(&mut grid[neighbour]);
+            if is_triggered(&grid[neighbour]) {
+                has_triggered[neighbour] = true;
+                triggered.push(neighbour);
+            }
+        }
+    }
+    triggered
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    pub(super) fn life(window: &Window<bool>) -> bool {
+        let neighbour_count = window.iter().filter(|&&cell| cell).count() - window[4] as usize;
+        neighbour_count == 3 || (window[4] && neighbour_count == 2)
+    }
+
+    pub(super) const GLIDER: &str = ".#.\n..#\n###\n";
+
+    #[test]
+    fn test_glider_moves_diagonally() {
+        let grid = SparseGrid::parse_with(GLIDER, false, |_, ch| ch == '#');
+        let mut automaton = Automaton::new(grid.clone(), life);
+        automaton.run(4);
+        let moved: Vec<Coords> = grid
+            .cells()
+            .map(|((row, col), _)| (row + 1, col + 1))
+            .collect();
+        let actual: Vec<Coords> = automaton.grid().cells().map(|(coords, _)| coords).collect();
+        assert_eq!(actual, moved);
+        assert_eq!(
+            automaton.grid().render(|cell| if cell { '#' } else { '.' }),
+            GLIDER
+        );
+    }
+
+    #[test]
+    fn test_background_flips() {
+        let invert = |window: &Window<bool>| !window[4];
+        let grid = SparseGrid::parse_with("#.", false, |_, ch| ch == '#');
+        let next = grid.step(invert);
+        assert!(next.background());
+        assert!(!next.get((0, 0)));
+        assert!(next.get((0, 1)));
+        assert!(next.get((100, -100)));
+        assert_eq!(next.len(), 1);
+        assert_eq!(next.step(invert), grid);
+    }
+
+    #[test]
+    fn test_blinker_cycle() {
+        let grid = SparseGrid::parse_with("###", false, |_, ch| ch == '#');
+        let mut automaton = Automaton::new(grid, life);
+        let cycle = automaton.find_cycle(10).unwrap();
+        assert_eq!(
+            cycle,
+            Cycle {
+                start: 0,
+                length: 2
+            }
+        );
+        assert_eq!(cycle.equivalent_generation(1_000_001), 1);
+    }
+
+    #[test]
+    fn test_run_until_stable() {
+        // A block with one extra cell which dies, leaving the block
+        let grid = SparseGrid::parse_with("##.\n##.\n...\n...\n..#", false, |_, ch| ch == '#');
+        let mut automaton = Automaton::new(grid, life);
+        assert_eq!(automaton.run_until_stable(10), Some(1));
+        assert_eq!(automaton.grid().len(), 4);
+    }
+
+    #[test]
+    fn test_oscillators_never_stabilise() {
+        let grid = SparseGrid::parse_with("###", false, |_, ch| ch == '#');
+        let mut automaton = Automaton::new(grid, life);
+        assert_eq!(automaton.run_until_stable(10), None);
+        assert_eq!(automaton.generation(), 11);
+    }
+
+    #[test]
+    fn test_cascade() {
+        let mut grid = Grid::parse_with("900\n050\n008\n", |_, ch| ch.to_digit(10).unwrap());
+        let triggered = cascade(&mut grid, |&energy| energy > 8, |energy| *energy += 4);
+        assert_eq!(triggered, [Pos::new(0, 0), Pos::new(1, 1), Pos::new(2, 2)]);
+        let rendered = grid.render(|_, &energy| char::from_digit(energy.min(9), 10).unwrap());
+        assert_eq!(rendered, "984\n898\n489\n");
+    }
+}
@@ -0,0 +1,336 @@
+//! Gosper's Hashlife algorithm, for running an automaton with two states for a huge number of
+//! generations.
+//!
+//! The grid is a quadtree, in which identical subtrees are shared.
+//! The result of advancing each subtree is memoised, so repeating patterns are only calculated
+//! once, and a subtree of size 2^k can be advanced by up to 2^(k-2) generations in one go.
+
+use super::{Coords, SparseGrid, Window};
+use std::collections::HashMap;
+
+type NodeId = usize;
+
+const DEAD: NodeId = 0;
+const ALIVE: NodeId = 1;
+
+/// A square with sides of length 2^level, split into 4 quadrants: NW, NE, SW, SE
+struct Node {
+    level: u32,
+    children: [NodeId; 4],
+    population: u64,
+}
+
+/// Runs an automaton on a quadtree of cells which are alive or dead.
+///
+/// The rule must leave dead cells surrounded by dead cells dead,
+/// so that the background stays dead.
+pub struct HashLife<R> {
+    rule: R,
+    nodes: Vec<Node>,
+    node_ids: HashMap<[NodeId; 4], NodeId>,
+    empty_nodes: Vec<NodeId>,
+    results: HashMap<(NodeId, u32), NodeId>,
+    root: NodeId,
+    origin: Coords,
+    generation: u64,
+}
+
+impl<R: Fn(&Window<bool>) -> bool> HashLife<R> {
+    /// # Panics
+    ///
+    /// If the background of the grid is alive, or would come to life
+    pub fn new(grid: &SparseGrid<bool>, rule: R) -> Self {
+        assert!(!grid.background(), "The background must be dead");
+        assert!(!rule(&[false; 9]), "The rule must keep the background dead");
+        let leaf = |population| Node {
+            level: 0,
+            children: [DEAD; 4],
+            population,
+        };
+        let mut hash_life = Self {
+            rule,
+            nodes: vec![leaf(0), leaf(1)],
+            node_ids: HashMap::new(),
+            empty_nodes: vec![DEAD],
+            results: HashMap::new(),
+            root: DEAD,
+            origin: (0, 0),
+            generation: 0,
+        };
+
+        let ((min_row, min_col), (max_row, max_col)) = grid.bounds().unwrap_or(((0, 0), (0, 0)));
+        let size = (max_row - min_row).max(max_col - min_col) + 1;
+        let level = (size as u64).next_power_of_two().trailing_zeros().max(2);
+        hash_life.root = hash_life.empty(level);
+        hash_life.origin = (min_row, min_col);
+        for ((row, col), is_alive) in grid.cells() {
+            if is_alive {
+                let root = hash_life.root;
+                hash_life.root = hash_life.set_alive(root, row - min_row, col - min_col);
+            }
+        }
+        hash_life
+    }
+
+    pub fn generation(&self) -> u64 {
+        self.generation
+    }
+
+    pub fn population(&self) -> u64 {
+        self.nodes[self.root].population
+    }
+
+    /// The live cells, in row order
+    pub fn live_cells(&self) -> Vec<Coords> {
+        let mut cells = Vec::with_capacity(self.population() as usize);
+        self.collect_live_cells(self.root, self.origin, &mut cells);
+        cells.sort_unstable();
+        cells
+    }
+
+    pub fn to_sparse_grid(&self) -> SparseGrid<bool> {
+        let mut grid = SparseGrid::new(false);
+        for coords in self.live_cells() {
+            grid.set(coords, true);
+        }
+        grid
+    }
+
+    pub fn advance(&mut self, generations: u64) {
+        for power in 0..u64::BITS {
+            if generations & (1 << power) != 0 {
+                self.advance_by_power_of_two(power);
+            }
+        }
+        self.generation += generations;
+    }
+
+    fn advance_by_power_of_two(&mut self, power: u32) {
+        // Patterns spread by at most one cell per generation,
+        // so the live cells must stay inside the part of the root which is advanced
+        while self.level(self.root) < power + 2 || !self.is_padded(self.root) {
+            self.expand();
+        }
+        self.expand();
+        let level = self.level(self.root);
+        self.root = self.successor(self.root, power);
+        let offset = 1 << (level - 2);
+        self.origin = (self.origin.0 + offset, self.origin.1 + offset);
+    }
+
+    /// Put the root in the centre of a root twice the size
+    fn expand(&mut self) {
+        let level = self.level(self.root);
+        let empty = self.empty(level - 1);
+        let [nw, ne, sw, se] = self.nodes[self.root].children;
+        let new_nw = self.join([empty, empty, empty, nw]);
+        let new_ne = self.join([empty, empty, ne, empty]);
+        let new_sw = self.join([empty, sw, empty, empty]);
+        let new_se = self.join([se, empty, empty, empty]);
+        self.root = self.join([new_nw, new_ne, new_sw, new_se]);
+        let offset = 1 << (level - 1);
+        self.origin = (self.origin.0 - offset, self.origin.1 - offset);
+    }
+
+    /// Whether all the live cells are in the central square of half the size
+    fn is_padded(&mut self, id: NodeId) -> bool {
+        let centre = self.centre(id);
+        self.nodes[centre].population == self.nodes[id].population
+    }
+
+    /// The centre of the node advanced by 2^power generations,
+    /// where power is at most the node's level minus 2
+    fn successor(&mut self, id: NodeId, power: u32) -> NodeId {
+        let level = self.level(id);
+        debug_assert!(level >= 2 && power <= level - 2);
+        if self.nodes[id].population == 0 {
+            return self.empty(level - 1);
+        }
+        if let Some(&result) = self.results.get(&(id, power)) {
+            return result;
+        }
+
+        let result = if level == 2 {
+            self.step_4x4(id)
+        } else {
+            let [a, b, c, d] = self.nodes[id].children;
+            let [_, a_ne, a_sw, a_se] = self.nodes[a].children;
+            let [b_nw, _, b_sw, b_se] = self.nodes[b].children;
+            let [c_nw, c_ne, _, c_se] = self.nodes[c].children;
+            let [d_nw, d_ne, d_sw, _] = self.nodes[d].children;
+
+            // 9 overlapping squares of half the size, in rows of 3
+            let squares = [
+                a,
+                self.join([a_ne, b_nw, a_se, b_sw]),
+                b,
+                self.join([a_sw, a_se, c_nw, c_ne]),
+                self.join([a_se, b_sw, c_ne, d_nw]),
+                self.join([b_sw, b_se, d_nw, d_ne]),
+                c,
+                self.join([c_ne, d_nw, c_se, d_sw]),
+                d,
+            ];
+
+            // At full speed, both halves of the time are spent advancing;
+            // otherwise the first half just takes the centres of the squares
+            let is_full_speed = power == level - 2;
+            let inner = squares.map(|square| {
+                if is_full_speed {
+                    self.successor(square, power - 1)
+                } else {
+                    self.centre(square)
+                }
+            });
+            let outer_power = if is_full_speed { power - 1 } else { power };
+            let quadrants =
+                [[0, 1, 3, 4], [1, 2, 4, 5], [3, 4, 6, 7], [4, 5, 7, 8]].map(|[nw, ne, sw, se]| {
+                    let quadrant = self.join([inner[nw], inner[ne], inner[sw], inner[se]]);
+                    self.successor(quadrant, outer_power)
+                });
+            self.join(quadrants)
+        };
+        self.results.insert((id, power), result);
+        result
+    }
+
+    /// The central 2x2 square of a 4x4 square, after one generation
+    fn step_4x4(&mut self, id: NodeId) -> NodeId {
+        let mut cells = [[false; 4]; 4];
+        for (row, cells_in_row) in cells.iter_mut().enumerate() {
+            for (col, cell) in cells_in_row.iter_mut().enumerate() {
+                *cell = self.is_alive(id, row as i64, col as i64);
+            }
+        }
+        let next_cells = [(1, 1), (1, 2), (2, 1), (2, 2)].map(|(row, col)| {
+            let mut window = [false; 9];
+            for (i, cell) in window.iter_mut().enumerate() {
+                *cell = cells[row + i / 3 - 1][col + i % 3 - 1];
+            }
+            if (self.rule)(&window) {
+                ALIVE
+            } else {
+                DEAD
+            }
+        });
+        self.join(next_cells)
+    }
+
+    fn centre(&mut self, id: NodeId) -> NodeId {
+        let [nw, ne, sw, se] = self.nodes[id].children;
+        self.join([
+            self.nodes[nw].children[3],
+            self.nodes[ne].children[2],
+            self.nodes[sw].children[1],
+            self.nodes[se].children[0],
+        ])
+    }
+
+    fn join(&mut self, children: [NodeId; 4]) -> NodeId {
+        if let Some(&id) = self.node_ids.get(&children) {
+            return id;
+        }
+        let node = Node {
+            level: self.nodes[children[0]].level + 1,
+            children,
+            population: children
+                .iter()
+                .map(|&child| self.nodes[child].population)
+                .sum(),
+        };
+        let id = self.nodes.len();
+        self.nodes.push(node);
+        self.node_ids.insert(children, id);
+        id
+    }
+
+    fn empty(&mut self, level: u32) -> NodeId {
+        while self.empty_nodes.len() <= level as usize {
+            let empty = *self.empty_nodes.last().unwrap();
+            let bigger = self.join([empty; 4]);
+            self.empty_nodes.push(bigger);
+        }
+        self.empty_nodes[level as usize]
+    }
+
+    fn level(&self, id: NodeId) -> u32 {
+        self.nodes[id].level
+    }
+
+    /// Split coordinates relative to a node into the quadrant and coordinates within it
+    fn quadrant(&self, id: NodeId, row: i64, col: i64) -> (usize, i64, i64) {
+        let half = 1 << (self.level(id) - 1);
+        let index = 2 * (row >= half) as usize + (col >= half) as usize;
+        (index, row % half, col % half)
+    }
+
+    fn is_alive(&self, id: NodeId, row: i64, col: i64) -> bool {
+        if self.level(id) == 0 {
+            return id == ALIVE;
+        }
+        let (index, row, col) = self.quadrant(id, row, col);
+        self.is_alive(self.nodes[id].children[index], row, col)
+    }
+
+    fn set_alive(&mut self, id: NodeId, row: i64, col: i64) -> NodeId {
+        if self.level(id) == 0 {
+            return ALIVE;
+        }
+        let (index, row, col) = self.quadrant(id, row, col);
+        let mut children = self.nodes[id].children;
+        children[index] = self.set_alive(children[index], row, col);
+        self.join(children)
+    }
+
+    fn collect_live_cells(&self, id: NodeId, (row, col): Coords, cells: &mut Vec<Coords>) {
+        let node = &self.nodes[id];
+        if node.population == 0 {
+            return;
+        }
+        if node.level == 0 {
+            cells.push((row, col));
+            return;
+        }
+        let half = 1 << (node.level - 1);
+        let corners = [(0, 0), (0, half), (half, 0), (half, half)];
+        for (&child, (row_offset, col_offset)) in node.children.iter().zip(corners) {
+            self.collect_live_cells(child, (row + row_offset, col + col_offset), cells);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::tests::{life, GLIDER};
+    use super::super::Automaton;
+    use super::*;
+
+    #[test]
+    fn test_matches_step_by_step() {
+        // The R-pentomino grows chaotically for over 1000 generations
+        let grid = SparseGrid::parse_with(".##\n##.\n.#.\n", false, |_, ch| ch == '#');
+        let mut hash_life = HashLife::new(&grid, life);
+        let mut automaton = Automaton::new(grid, life);
+        for generations in [1, 2, 5, 24, 100] {
+            hash_life.advance(generations);
+            automaton.run(generations);
+            assert_eq!(hash_life.to_sparse_grid(), *automaton.grid());
+        }
+        assert_eq!(hash_life.generation(), 132);
+    }
+
+    #[test]
+    fn test_glider_after_a_trillion_generations() {
+        let grid = SparseGrid::parse_with(GLIDER, false, |_, ch| ch == '#');
+        let mut hash_life = HashLife::new(&grid, life);
+        let generations = 1_000_000_000_000;
+        hash_life.advance(generations);
+        let shift = generations as i64 / 4;
+        let expected: Vec<Coords> = grid
+            .cells()
+            .map(|((row, col), _)| (row + shift, col + shift))
+            .collect();
+        assert_eq!(hash_life.population(), 5);
+        assert_eq!(hash_life.live_cells(), expected);
+    }
+}
@@ -0,0 +1,53 @@
+//! Start a new puzzle part in the year's crate in the current folder, e.g. from aoc2025_rs:
+//!
+//! `cargo run --manifest-path ../aoc_common/Cargo.toml --bin new_day -- 8 1 40 example.txt`
+//!
+//! This creates `src/bin/day8_part1.rs`, with a test that the example gives an answer of 40,
+//! and copies the example into `data/day8_example.txt`.
+//! If no example file is given, the example is read from stdin.
+
+use aoc_common::scaffold::Scaffold;
+use std::io::Read;
+use std::path::Path;
+use std::process::ExitCode;
+
+const USAGE: &str = "Usage: new_day <day> <part> <example answer> [example file]";
+
+fn main() -> ExitCode {
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    let (Some(day), Some(part), Some(answer)) = (
+        args.first().and_then(|day| day.parse().ok()),
+        args.get(1).and_then(|part| part.parse().ok()),
+        args.get(2),
+    ) else {
+        eprintln!("{USAGE}");
+        return ExitCode::FAILURE;
+    };
+
+    let example = match args.get(3) {
+        Some(example_path) => std::fs::read_to_string(example_path),
+        None => {
+            let mut example = String::new();
+            std::io::stdin()
+                .read_to_string(&mut example)
+                .map(|_| example)
+        }
+    };
+    let result = example.map_err(Into::into).and_then(|example| {
+        let crate_dir = Path::new(".");
+        Scaffold::for_crate(crate_dir, day, part, answer)?.write(crate_dir, &example)
+    });
+
+    match result {
+        Ok(new_paths) => {
+            for path in new_paths {
+                println!("Created {}", path.display());
+            }
+            ExitCode::SUCCESS
+        }
+        Err(err) => {
+            eprintln!("{err}");
+            ExitCode::FAILURE
+        }
+    }
+}
@@ -0,0 +1,484 @@
+//! Evaluation of hands of cards, for Camel Cards (2023 day 7) and for poker (Project Euler problem 54).
+//!
+//! A `CardGame` holds the card ranks from lowest to highest, the symbols which are wildcards,
+//! and the scoring rules. A hand's `Evaluation` is its category, followed by the ranks which break ties,
+//! so stronger hands have greater evaluations.
+//!
+//! Camel Cards breaks ties on the ranks of the cards in the order they were dealt,
+//! and a wildcard joins the largest group of cards of the same rank, but keeps its own rank for tie-breaking.
+//! Poker breaks ties on the ranks of the groups of cards, from the largest group down,
+//! and a wildcard becomes whichever card gives the best hand.
+
+use std::cmp::Ordering;
+use thiserror::Error;
+
+#[derive(Debug, Error, PartialEq, Eq)]
+pub enum CardError {
+    #[error("the rank {0:?} appears more than once")]
+    DuplicateRank(char),
+    #[error("the wildcard {0:?} is not one of the ranks")]
+    UnknownWildcard(char),
+    #[error("unrecognised card {0:?}")]
+    UnknownCard(String),
+    #[error("a hand needs 5 cards, not {0}")]
+    WrongHandSize(usize),
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Scoring {
+    CamelCards,
+    Poker,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Category {
+    HighCard,
+    OnePair,
+    TwoPair,
+    ThreeOfAKind,
+    Straight,
+    Flush,
+    FullHouse,
+    FourOfAKind,
+    StraightFlush,
+    RoyalFlush,
+    FiveOfAKind,
+}
+
+/// The category of a hand, then the ranks which break ties between hands in the same category
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Evaluation {
+    pub category: Category,
+    pub tie_breakers: Vec<u8>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Card {
+    /// The index of the card's symbol in the ranks of the game, from 0 for the lowest
+    pub rank: u8,
+    pub suit: Option<char>,
+    pub is_wild: bool,
+}
+
+/// Exactly [`HAND_SIZE`] cards, as in both Camel Cards and poker
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Hand {
+    cards: Vec<Card>,
+}
+
+impl Hand {
+    pub fn new(cards: Vec<Card>) -> Result<Self, CardError> {
+        if cards.len() != HAND_SIZE {
+            return Err(CardError::WrongHandSize(cards.len()));
+        }
+        Ok(Hand { cards })
+    }
+
+    pub fn cards(&self) -> &[Card] {
+        &self.cards
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CardGame {
+    ranks: Vec<char>,
+    wildcards: Vec<char>,
+    scoring: Scoring,
+}
+
+const STANDARD_RANKS: &str = "23456789TJQKA";
+pub const HAND_SIZE: usize = 5;
+const STRAIGHT_LENGTH: usize = 5;
+
+impl CardGame {
+    /// ranks lists the symbols from the lowest rank to the highest
+    pub fn new(ranks: &str, wildcards: &str, scoring: Scoring) -> Result<Self, CardError> {
+        let ranks: Vec<char> = ranks.chars().collect();
+        if let Some((_, &symbol)) = ranks
+            .iter()
+            .enumerate()
+            .find(|&(index, symbol)| ranks[..index].contains(symbol))
+        {
+            return Err(CardError::DuplicateRank(symbol));
+        }
+        let wildcards: Vec<char> = wildcards.chars().collect();
+        if let Some(&symbol) = wildcards.iter().find(|symbol| !ranks.contains(symbol)) {
+            return Err(CardError::UnknownWildcard(symbol));
+        }
+        Ok(CardGame {
+            ranks,
+            wildcards,
+            scoring,
+        })
+    }
+
+    /// The rules for part 1 of 2023 day 7
+    pub fn camel_cards() -> Self {
+        Self::new(STANDARD_RANKS, "", Scoring::CamelCards).unwrap()
+    }
+
+    /// The rules for part 2 of 2023 day 7, where J is a joker with the lowest rank
+    pub fn camel_cards_with_jokers() -> Self {
+        Self::new("J23456789TQKA", "J", Scoring::CamelCards).unwrap()
+    }
+
+    /// Standard poker, with aces high (and also low in the straight A-2-3-4-5)
+    pub fn poker() -> Self {
+        Self::new(STANDARD_RANKS, "", Scoring::Poker).unwrap()
+    }
+
+    pub fn scoring(&self) -> Scoring {
+        self.scoring
+    }
+
+    /// Parse a hand of symbols without suits (such as "32T3K"),
+    /// or a hand of cards separated by spaces, each with its rank then its suit (such as "8C TS KC 9H 4S")
+    pub fn parse_hand(&self, hand_str: &str) -> Result<Hand, CardError> {
+        let cards: Vec<Card> = if hand_str.contains(' ') {
+            hand_str
+                .split_ascii_whitespace()
+                .map(|card_str| {
+                    let mut chars = card_str.chars();
+                    match (chars.next(), chars.next(), chars.next()) {
+                        (Some(symbol), Some(suit), None) => self.card(symbol, Some(suit)),
+                        _ => None,
+                    }
+                    .ok_or_else(|| CardError::UnknownCard(card_str.to_string()))
+                })
+                .collect::<Result<_, _>>()?
+        } else {
+            hand_str
+                .chars()
+                .map(|symbol| {
+                    self.card(symbol, None)
+                        .ok_or_else(|| CardError::UnknownCard(symbol.to_string()))
+                })
+                .collect::<Result<_, _>>()?
+        };
+        Hand::new(cards)
+    }
+
+    fn card(&self, symbol: char, suit: Option<char>) -> Option<Card> {
+        let rank = self.ranks.iter().position(|&rank| rank == symbol)?;
+        Some(Card {
+            rank: rank as u8,
+            suit,
+            is_wild: self.wildcards.contains(&symbol),
+        })
+    }
+
+    pub fn evaluate(&self, hand: &Hand) -> Evaluation {
+        match self.scoring {
+            Scoring::CamelCards => self.evaluate_camel_cards(hand),
+            Scoring::Poker => self.evaluate_poker(hand),
+        }
+    }
+
+    pub fn compare(&self, hand: &Hand, other_hand: &Hand) -> Ordering {
+        self.evaluate(hand).cmp(&self.evaluate(other_hand))
+    }
+
+    fn evaluate_camel_cards(&self, hand: &Hand) -> Evaluation {
+        let mut group_sizes: Vec<usize> =
+            rank_groups(hand.cards.iter().filter(|card| !card.is_wild))
+                .into_iter()
+                .map(|(size, _)| size)
+                .collect();
+        let wildcard_count = hand.cards.iter().filter(|card| card.is_wild).count();
+        match group_sizes.first_mut() {
+            Some(largest_group_size) => *largest_group_size += wildcard_count,
+            None => group_sizes.push(wildcard_count),
+        }
+        Evaluation {
+            category: category_of_groups(&group_sizes),
+            tie_breakers: hand.cards.iter().map(|card| card.rank).collect(),
+        }
+    }
+
+    /// Try every combination of ranks for the wildcards, and keep the best evaluation.
+    /// The wildcards can take the suit of the other cards, so the hand is a flush
+    /// if the other cards all have the same suit.
+    fn evaluate_poker(&self, hand: &Hand) -> Evaluation {
+        let mut cards: Vec<Card> = hand
+            .cards
+            .iter()
+            .filter(|card| !card.is_wild)
+            .copied()
+            .collect();
+        let mut suits = cards.iter().map(|card| card.suit);
+        let is_flush = hand.cards.len() == STRAIGHT_LENGTH
+            && match suits.next() {
+                Some(first_suit) => first_suit.is_some() && suits.all(|suit| suit == first_suit),
+                None => true,
+            };
+
+        let real_card_count = cards.len();
+        let mut best: Option<Evaluation> = None;
+        let mut wild_ranks = vec![0_u8; hand.cards.len() - real_card_count];
+        loop {
+            cards.truncate(real_card_count);
+            cards.extend(wild_ranks.iter().map(|&rank| Card {
+                rank,
+                suit: None,
+                is_wild: false,
+            }));
+            let evaluation = self.evaluate_poker_without_wildcards(&cards, is_flush);
+            if best.as_ref().is_none_or(|best| evaluation > *best) {
+                best = Some(evaluation);
+            }
+            // Move on to the next combination of wild ranks, in non-decreasing order
+            match wild_ranks
+                .iter()
+                .rposition(|&rank| (rank as usize) + 1 < self.ranks.len())
+            {
+                Some(index) => {
+                    let next_rank = wild_ranks[index] + 1;
+                    wild_ranks[index..].fill(next_rank);
+                }
+                None => break,
+            }
+        }
+        best.unwrap()
+    }
+
+    fn evaluate_poker_without_wildcards(&self, cards: &[Card], is_flush: bool) -> Evaluation {
+        let groups = rank_groups(cards.iter());
+        let group_sizes: Vec<usize> = groups.iter().map(|&(size, _)| size).collect();
+        let group_ranks: Vec<u8> = groups.iter().map(|&(_, rank)| rank).collect();
+        let straight_high_rank = self.straight_high_rank(&group_ranks);
+        let top_rank = (self.ranks.len() - 1) as u8;
+
+        let grouped_category = category_of_groups(&group_sizes);
+
+        let (category, tie_breakers) = match (straight_high_rank, is_flush) {
+            _ if grouped_category == Category::FiveOfAKind => (grouped_category, group_ranks),
+            (Some(top), true) if top == top_rank => (Category::RoyalFlush, vec![top]),
+            (Some(high), true) => (Category::StraightFlush, vec![high]),
+            _ if grouped_category >= Category::FullHouse => (grouped_category, group_ranks),
+            (_, true) => (Category::Flush, group_ranks),
+            (Some(high), false) => (Category::Straight, vec![high]),
+            _ => (grouped_category, group_ranks),
+        };
+        Evaluation {
+            category,
+            tie_breakers,
+        }
+    }
+
+    /// The highest rank, if the ranks (in descending order) are a straight of 5 cards.
+    /// The lowest rank can also follow the highest rank, as in A-2-3-4-5, where 5 is the high card.
+    fn straight_high_rank(&self, descending_ranks: &[u8]) -> Option<u8> {
+        if descending_ranks.len() != STRAIGHT_LENGTH || self.ranks.len() < STRAIGHT_LENGTH {
+            return None;
+        }
+        let is_run = |ranks: &[u8]| ranks.windows(2).all(|pair| pair[0] == pair[1] + 1);
+        let top_rank = (self.ranks.len() - 1) as u8;
+        if is_run(descending_ranks) {
+            Some(descending_ranks[0])
+        } else if descending_ranks[0] == top_rank
+            && descending_ranks[STRAIGHT_LENGTH - 1] == 0
+            && is_run(&descending_ranks[1..])
+        {
+            Some(descending_ranks[1])
+        } else {
+            None
+        }
+    }
+}
+
+/// The size and rank of each group of cards with the same rank, with the largest groups first,
+/// and higher ranks first within groups of the same size
+fn rank_groups<'a, I: Iterator<Item = &'a Card>>(cards: I) -> Vec<(usize, u8)> {
+    let mut groups: Vec<(usize, u8)> = Vec::new();
+    for card in cards {
+        match groups.iter_mut().find(|(_, rank)| *rank == card.rank) {
+            Some((size, _)) => *size += 1,
+            None => groups.push((1, card.rank)),
+        }
+    }
+    groups.sort_unstable_by(|a, b| b.cmp(a));
+    groups
+}
+
+/// The category of a hand from the sizes of its groups of the same rank, largest first
+/// (ignoring straights and flushes)
+fn category_of_groups(group_sizes: &[usize]) -> Category {
+    let second_size = group_sizes.get(1).copied().unwrap_or(0);
+    match (group_sizes[0], second_size) {
+        (5.., _) => Category::FiveOfAKind,
+        (4, _) => Category::FourOfAKind,
+        (3, 2..) => Category::FullHouse,
+        (3, _) => Category::ThreeOfAKind,
+        (2, 2..) => Category::TwoPair,
+        (2, _) => Category::OnePair,
+        _ => Category::HighCard,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const CAMEL_CARDS_EXAMPLE: &str = "32T3K 765\nT55J5 684\nKK677 28\nKTJJT 220\nQQQJA 483";
+
+    fn total_winnings(game: &CardGame, contents: &str) -> u32 {
+        let mut hands: Vec<(Evaluation, u32)> = contents
+            .lines()
+            .map(|line| {
+                let (hand_str, bid_str) = line.split_once(' ').unwrap();
+                let hand = game.parse_hand(hand_str).unwrap();
+                (game.evaluate(&hand), bid_str.parse().unwrap())
+            })
+            .collect();
+        hands.sort();
+        hands
+            .iter()
+            .enumerate()
+            .map(|(index, (_, bid))| bid * (index as u32 + 1))
+            .sum()
+    }
+
+    #[test]
+    fn test_camel_cards_example() {
+        assert_eq!(
+            total_winnings(&CardGame::camel_cards(), CAMEL_CARDS_EXAMPLE),
+            6440
+        );
+        assert_eq!(
+            total_winnings(&CardGame::camel_cards_with_jokers(), CAMEL_CARDS_EXAMPLE),
+            5905
+        );
+    }
+
+    #[test]
+    fn test_camel_cards_categories() {
+        let game = CardGame::camel_cards_with_jokers();
+        let category = |hand_str| game.evaluate(&game.parse_hand(hand_str).unwrap()).category;
+        assert_eq!(category("JJJJJ"), Category::FiveOfAKind);
+        assert_eq!(category("KTJJT"), Category::FourOfAKind);
+        assert_eq!(category("2233J"), Category::FullHouse);
+        assert_eq!(category("2345J"), Category::OnePair);
+        assert_eq!(category("23456"), Category::HighCard);
+        // A joker is the weakest card when breaking ties
+        assert_eq!(
+            game.compare(
+                &game.parse_hand("JKKK2").unwrap(),
+                &game.parse_hand("QQQQ2").unwrap()
+            ),
+            Ordering::Less
+        );
+    }
+
+    fn poker_winner(hands: &str) -> Ordering {
+        let game = CardGame::poker();
+        let (hand1, hand2) = hands.split_at(14);
+        game.compare(
+            &game.parse_hand(hand1.trim()).unwrap(),
+            &game.parse_hand(hand2.trim()).unwrap(),
+        )
+    }
+
+    #[test]
+    fn test_project_euler_examples() {
+        assert_eq!(
+            poker_winner("5H 5C 6S 7S KD 2C 3S 8S 8D TD"),
+            Ordering::Less
+        );
+        assert_eq!(
+            poker_winner("5D 8C 9S JS AC 2C 5C 7D 8S QH"),
+            Ordering::Greater
+        );
+        assert_eq!(
+            poker_winner("2D 9C AS AH AC 3D 6D 7D TD QD"),
+            Ordering::Less
+        );
+        assert_eq!(
+            poker_winner("4D 6S 9H QH QC 3D 6D 7H QD QS"),
+            Ordering::Greater
+        );
+        assert_eq!(
+            poker_winner("2H 2D 4C 4D 4S 3C 3D 3S 9S 9D"),
+            Ordering::Greater
+        );
+    }
+
+    #[test]
+    fn test_poker_categories() {
+        let game = CardGame::poker();
+        let evaluate = |hand_str| game.evaluate(&game.parse_hand(hand_str).unwrap());
+        assert_eq!(evaluate("TH JH QH KH AH").category, Category::RoyalFlush);
+        assert_eq!(
+            evaluate("AD 2C 3H 4S 5D"),
+            Evaluation {
+                category: Category::Straight,
+                tie_breakers: vec![3]
+            }
+        );
+        assert_eq!(
+            evaluate("9C 9D 4S 4H KD"),
+            Evaluation {
+                category: Category::TwoPair,
+                tie_breakers: vec![7, 2, 11]
+            }
+        );
+        assert_eq!(evaluate("2C 7C 9C JC KC").category, Category::Flush);
+        assert_eq!(evaluate("QS QD QH 2C 2D").category, Category::FullHouse);
+    }
+
+    #[test]
+    fn test_poker_with_wildcards() {
+        let game = CardGame::new(STANDARD_RANKS, "2", Scoring::Poker).unwrap();
+        let evaluate = |hand_str| game.evaluate(&game.parse_hand(hand_str).unwrap());
+        assert_eq!(
+            evaluate("2C 2D 2H 2S AS"),
+            Evaluation {
+                category: Category::FiveOfAKind,
+                tie_breakers: vec![12]
+            }
+        );
+        assert_eq!(evaluate("2C TH JH QH KH").category, Category::RoyalFlush);
+        assert_eq!(
+            evaluate("2C 5H 6D 7S 8S"),
+            Evaluation {
+                category: Category::Straight,
+                tie_breakers: vec![7]
+            }
+        );
+        assert_eq!(
+            evaluate("2C 2D 9H 9S 4D"),
+            Evaluation {
+                category: Category::FourOfAKind,
+                tie_breakers: vec![7, 2]
+            }
+        );
+    }
+
+    #[test]
+    fn test_invalid_rules_and_hands() {
+        assert_eq!(
+            CardGame::new("AKQA", "", Scoring::Poker),
+            Err(CardError::DuplicateRank('A'))
+        );
+        assert_eq!(
+            CardGame::new("AKQ", "J", Scoring::CamelCards),
+            Err(CardError::UnknownWildcard('J'))
+        );
+        let game = CardGame::poker();
+        assert_eq!(
+            game.parse_hand("1C 2C"),
+            Err(CardError::UnknownCard("1C".to_string()))
+        );
+        assert_eq!(game.parse_hand(""), Err(CardError::WrongHandSize(0)));
+        assert_eq!(
+            game.parse_hand("2C 3C 4C 5C"),
+            Err(CardError::WrongHandSize(4))
+        );
+        assert_eq!(
+            CardGame::camel_cards().parse_hand("32T3KK"),
+            Err(CardError::WrongHandSize(6))
+        );
+        let card = game.card('A', Some('S')).unwrap();
+        assert_eq!(Hand::new(Vec::new()), Err(CardError::WrongHandSize(0)));
+        assert_eq!(Hand::new(vec![card; 5]).unwrap().cards(), [card; 5]);
+    }
+}
@@ -0,0 +1,248 @@
+//! Axis-aligned boxes of integer points in N dimensions, and two ways of combining them:
+//! inclusion-exclusion with signed volumes, or splitting them into disjoint pieces.
+
+use std::collections::HashMap;
+use std::ops::RangeInclusive;
+
+/// The points from `min` to `max` inclusive in each dimension
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Cuboid<const N: usize> {
+    pub min: [i64; N],
+    pub max: [i64; N],
+}
+
+impl<const N: usize> Cuboid<N> {
+    /// Returns None if the cuboid would be empty
+    pub fn new(min: [i64; N], max: [i64; N]) -> Option<Self> {
+        (0..N)
+            .all(|dim| min[dim] <= max[dim])
+            .then_some(Self { min, max })
+    }
+
+    pub fn from_ranges(ranges: [RangeInclusive<i64>; N]) -> Option<Self> {
+        Self::new(
+            ranges.clone().map(|range| *range.start()),
+            ranges.map(|range| *range.end()),
+        )
+    }
+
+    pub fn range(&self, dim: usize) -> RangeInclusive<i64> {
+        self.min[dim]..=self.max[dim]
+    }
+
+    /// The number of points in the cuboid
+    pub fn volume(&self) -> i64 {
+        (0..N)
+            .map(|dim| self.max[dim] - self.min[dim] + 1)
+            .product()
+    }
+
+    pub fn contains_point(&self, point: [i64; N]) -> bool {
+        (0..N).all(|dim| self.range(dim).contains(&point[dim]))
+    }
+
+    pub fn contains(&self, other: &Self) -> bool {
+        (0..N).all(|dim| self.min[dim] <= other.min[dim] && other.max[dim] <= self.max[dim])
+    }
+
+    pub fn intersection(&self, other: &Self) -> Option<Self> {
+        Self::new(
+            std::array::from_fn(|dim| self.min[dim].max(other.min[dim])),
+            std::array::from_fn(|dim| self.max[dim].min(other.max[dim])),
+        )
+    }
+
+    /// Split the points which are not in the other cuboid into at most 2N disjoint cuboids
+    pub fn subtract(&self, other: &Self) -> Vec<Self> {
+        let Some(overlap) = self.intersection(other) else {
+            return vec![*self];
+        };
+        let mut pieces = Vec::with_capacity(2 * N);
+        let mut remainder = *self;
+        for dim in 0..N {
+            if remainder.min[dim] < overlap.min[dim] {
+                let mut below = remainder;
+                below.max[dim] = overlap.min[dim] - 1;
+                pieces.push(below);
+                remainder.min[dim] = overlap.min[dim];
+            }
+            if overlap.max[dim] < remainder.max[dim] {
+                let mut above = remainder;
+                above.min[dim] = overlap.max[dim] + 1;
+                pieces.push(above);
+                remainder.max[dim] = overlap.max[dim];
+            }
+        }
+        pieces
+    }
+}
+
+/// A union of cuboids, stored as cuboids with signed counts, using inclusion-exclusion.
+///
+/// Adding a cuboid cancels out its intersection with every cuboid already counted,
+/// then counts the new cuboid once. Removing it does the same, without counting it.
+#[derive(Debug, Clone, Default)]
+pub struct SignedCuboids<const N: usize> {
+    counts: HashMap<Cuboid<N>, i64>,
+}
+
+impl<const N: usize> SignedCuboids<N> {
+    pub fn new() -> Self {
+        Self {
+            counts: HashMap::new(),
+        }
+    }
+
+    pub fn add(&mut self, cuboid: Cuboid<N>) {
+        self.cancel_overlaps(&cuboid);
+        *self.counts.entry(cuboid).or_insert(0) += 1;
+    }
+
+    pub fn remove(&mut self, cuboid: Cuboid<N>) {
+        self.cancel_overlaps(&cuboid);
+    }
+
+    fn cancel_overlaps(&mut self, cuboid: &Cuboid<N>) {
+        let cancellations: Vec<(Cuboid<N>, i64)> = self
+            .counts
+            .iter()
+            .filter_map(|(other, &count)| Some((other.intersection(cuboid)?, count)))
+            .collect();
+        for (overlap, count) in cancellations {
+            *self.counts.entry(overlap).or_insert(0) -= count;
+        }
+        self.counts.retain(|_, count| *count != 0);
+    }
+
+    /// The number of points in the union
+    pub fn volume(&self) -> i64 {
+        self.counts
+            .iter()
+            .map(|(cuboid, count)| cuboid.volume() * count)
+            .sum()
+    }
+
+    /// The number of distinct cuboids with a non-zero count
+    pub fn len(&self) -> usize {
+        self.counts.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.counts.is_empty()
+    }
+}
+
+/// A union of cuboids, stored as disjoint pieces
+#[derive(Debug, Clone, Default)]
+pub struct DisjointCuboids<const N: usize> {
+    pieces: Vec<Cuboid<N>>,
+}
+
+impl<const N: usize> DisjointCuboids<N> {
+    pub fn new() -> Self {
+        Self { pieces: Vec::new() }
+    }
+
+    pub fn pieces(&self) -> &[Cuboid<N>] {
+        &self.pieces
+    }
+
+    pub fn add(&mut self, cuboid: Cuboid<N>) {
+        self.remove(cuboid);
+        self.pieces.push(cuboid);
+    }
+
+    pub fn remove(&mut self, cuboid: Cuboid<N>) {
+        self.pieces = self
+            .pieces
+            .iter()
+            .flat_map(|piece| piece.subtract(&cuboid))
+            .collect();
+    }
+
+    pub fn volume(&self) -> i64 {
+        self.pieces.iter().map(Cuboid::volume).sum()
+    }
+
+    pub fn len(&self) -> usize {
+        self.pieces.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.pieces.is_empty()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn cube(min: i64, max: i64) -> Cuboid<3> {
+        Cuboid::new([min; 3], [max; 3]).unwrap()
+    }
+
+    #[test]
+    fn test_intersection_and_volume() {
+        let a = cube(10, 12);
+        let b = cube(11, 13);
+        assert_eq!(a.volume(), 27);
+        assert_eq!(a.intersection(&b), Some(cube(11, 12)));
+        assert_eq!(a.intersection(&cube(13, 20)), None);
+        assert!(Cuboid::new([1, 1, 1], [0, 1, 1]).is_none());
+        assert_eq!(Cuboid::from_ranges([10..=12, 10..=12, 10..=12]), Some(a));
+        assert!(a.contains(&cube(11, 11)));
+        assert!(a.contains_point([10, 11, 12]));
+    }
+
+    #[test]
+    fn test_subtract_gives_disjoint_pieces() {
+        let a = cube(0, 9);
+        let hole = Cuboid::new([3, -5, 4], [5, 4, 4]).unwrap();
+        let pieces = a.subtract(&hole);
+        assert!(pieces.len() <= 6);
+        let overlap = a.intersection(&hole).unwrap();
+        let total: i64 = pieces.iter().map(Cuboid::volume).sum();
+        assert_eq!(total, a.volume() - overlap.volume());
+        for (i, piece) in pieces.iter().enumerate() {
+            assert!(a.contains(piece));
+            assert!(piece.intersection(&hole).is_none());
+            assert!(pieces[i + 1..]
+                .iter()
+                .all(|other| piece.intersection(other).is_none()));
+        }
+    }
+
+    #[test]
+    fn test_inclusion_exclusion_matches_splitting() {
+        // The small example from 2021 day 22
+        let steps = [
+            (true, cube(10, 12)),
+            (true, cube(11, 13)),
+            (false, cube(9, 11)),
+            (true, cube(10, 10)),
+        ];
+        let mut signed = SignedCuboids::new();
+        let mut disjoint = DisjointCuboids::new();
+        for (is_on, cuboid) in steps {
+            if is_on {
+                signed.add(cuboid);
+                disjoint.add(cuboid);
+            } else {
+                signed.remove(cuboid);
+                disjoint.remove(cuboid);
+            }
+        }
+        assert_eq!(signed.volume(), 39);
+        assert_eq!(disjoint.volume(), 39);
+    }
+
+    #[test]
+    fn test_two_dimensions() {
+        let mut squares = SignedCuboids::<2>::new();
+        squares.add(Cuboid::new([0, 0], [3, 3]).unwrap());
+        squares.add(Cuboid::new([2, 2], [5, 5]).unwrap());
+        squares.add(Cuboid::new([0, 0], [5, 5]).unwrap());
+        assert_eq!(squares.volume(), 36);
+        assert_eq!(squares.len(), 1);
+    }
+}
@@ -0,0 +1,386 @@
+mod direction;
+mod pos;
+
+pub use direction::Direction;
+pub use pos::{Pos, NEIGHBOUR_OFFSETS_8};
+
+use std::collections::VecDeque;
+use std::fmt::{Display, Formatter};
+use std::ops::{Index, IndexMut};
+
+/// A rectangular grid of cells, stored row by row in a single vector
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Grid<T> {
+    width: usize,
+    height: usize,
+    cells: Vec<T>,
+}
+
+impl<T> Grid<T> {
+    pub fn new(width: usize, height: usize, fill: T) -> Self
+    where
+        T: Clone,
+    {
+        Self {
+            width,
+            height,
+            cells: vec![fill; width * height],
+        }
+    }
+
+    /// Build a grid from its rows, or return None if the rows have different lengths
+    pub fn from_rows(rows: Vec<Vec<T>>) -> Option<Self> {
+        let height = rows.len();
+        let width = rows.first().map_or(0, |row| row.len());
+        if rows.iter().any(|row| row.len() != width) {
+            return None;
+        }
+        let cells = rows.into_iter().flatten().collect();
+        Some(Self {
+            width,
+            height,
+            cells,
+        })
+    }
+
+    /// Parse the non-empty lines of the text, converting each character to a cell.
+    /// Leading blank lines are skipped, and parsing stops at the next blank line,
+    /// so that a grid can be followed by another section of the puzzle input.
+    ///
+    /// # Panics
+    ///
+    /// If the lines have different lengths
+    pub fn parse_with<F>(text: &str, mut to_cell: F) -> Self
+    where
+        F: FnMut(Pos, char) -> T,
+    {
+        let rows = text
+            .lines()
+            .skip_while(|line| line.is_empty())
+            .take_while(|line| !line.is_empty())
+            .enumerate()
+            .map(|(row, line)| {
+                line.chars()
+                    .enumerate()
+                    .map(|(col, ch)| to_cell(Pos::new(row, col), ch))
+                    .collect::<Vec<T>>()
+            })
+            .collect::<Vec<Vec<T>>>();
+        Self::from_rows(rows).expect("All lines of a grid should have the same length")
+    }
+
+    #[inline(always)]
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    #[inline(always)]
+    pub fn height(&self) -> usize {
+        self.height
+    }
+
+    #[inline(always)]
+    pub fn contains(&self, pos: Pos) -> bool {
+        pos.row < self.height && pos.col < self.width
+    }
+
+    #[inline(always)]
+    pub fn get(&self, pos: Pos) -> Option<&T> {
+        if self.contains(pos) {
+            Some(&self.cells[pos.row * self.width + pos.col])
+        } else {
+            None
+        }
+    }
+
+    #[inline(always)]
+    pub fn get_mut(&mut self, pos: Pos) -> Option<&mut T> {
+        if self.contains(pos) {
+            Some(&mut self.cells[pos.row * self.width + pos.col])
+        } else {
+            None
+        }
+    }
+
+    /// Take a step in a direction, or return None if that would leave the grid
+    #[inline(always)]
+    pub fn step(&self, pos: Pos, dir: Direction) -> Option<Pos> {
+        pos.step(dir).filter(|&next_pos| self.contains(next_pos))
+    }
+
+    /// The up to 4 orthogonally adjacent positions that are inside the grid
+    pub fn neighbours_4(&self, pos: Pos) -> impl Iterator<Item = Pos> + '_ {
+        Direction::ALL
+            .into_iter()
+            .filter_map(move |dir| self.step(pos, dir))
+    }
+
+    /// The up to 8 orthogonally or diagonally adjacent positions that are inside the grid
+    pub fn neighbours_8(&self, pos: Pos) -> impl Iterator<Item = Pos> + '_ {
+        NEIGHBOUR_OFFSETS_8
+            .into_iter()
+            .filter_map(move |(row_offset, col_offset)| pos.offset_by(row_offset, col_offset))
+            .filter(|&next_pos| self.contains(next_pos))
+    }
+
+    pub fn positions(&self) -> impl Iterator<Item = Pos> {
+        let width = self.width;
+        (0..self.height).flat_map(move |row| (0..width).map(move |col| Pos::new(row, col)))
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = (Pos, &T)> {
+        self.positions().zip(self.cells.iter())
+    }
+
+    pub fn iter_mut(&mut self) -> impl Iterator<Item = (Pos, &mut T)> {
+        self.positions().zip(self.cells.iter_mut())
+    }
+
+    /// Find the first position (reading row by row) whose cell matches the predicate
+    pub fn find<P>(&self, mut predicate: P) -> Option<Pos>
+    where
+        P: FnMut(&T) -> bool,
+    {
+        self.iter()
+            .find(|(_, cell)| predicate(cell))
+            .map(|(pos, _)| pos)
+    }
+
+    pub fn find_all<'a, P>(&'a self, mut predicate: P) -> impl Iterator<Item = Pos> + 'a
+    where
+        P: FnMut(&T) -> bool + 'a,
+    {
+        self.iter()
+            .filter(move |(_, cell)| predicate(cell))
+            .map(|(pos, _)| pos)
+    }
+
+    #[inline(always)]
+    pub fn row(&self, row: usize) -> &[T] {
+        &self.cells[row * self.width..(row + 1) * self.width]
+    }
+
+    #[inline(always)]
+    pub fn row_mut(&mut self, row: usize) -> &mut [T] {
+        &mut self.cells[row * self.width..(row + 1) * self.width]
+    }
+
+    /// One slice per row, so a grid with no columns still has `height` (empty) rows
+    pub fn rows(&self) -> impl DoubleEndedIterator<Item = &[T]> {
+        (0..self.height).map(|row| self.row(row))
+    }
+
+    /// # Panics
+    ///
+    /// If the column is outside the grid
+    pub fn column(&self, col: usize) -> impl DoubleEndedIterator<Item = &T> {
+        assert!(col < self.width, "column {col} is outside the grid");
+        self.cells[col..].iter().step_by(self.width)
+    }
+
+    pub fn columns(&self) -> impl Iterator<Item = impl DoubleEndedIterator<Item = &T>> {
+        (0..self.width).map(|col| self.column(col))
+    }
+
+    /// Apply a function to every cell, keeping the shape of the grid
+    pub fn map<U, F>(&self, mut to_cell: F) -> Grid<U>
+    where
+        F: FnMut(Pos, &T) -> U,
+    {
+        Grid {
+            width: self.width,
+            height: self.height,
+            cells: self.iter().map(|(pos, cell)| to_cell(pos, cell)).collect(),
+        }
+    }
+
+    /// Breadth-first flood fill from the start position, through orthogonally adjacent cells.
+    /// `can_spread` is given the cell being spread from and the cell being spread to.
+    /// The positions are returned in the order they were reached, starting with `start`.
+    pub fn flood_fill<F>(&self, start: Pos, mut can_spread: F) -> Vec<Pos>
+    where
+        F: FnMut(&T, &T) -> bool,
+    {
+        if !self.contains(start) {
+            return Vec::new();
+        }
+        let mut is_filled = vec![false; self.cells.len()];
+        is_filled[start.row * self.width + start.col] = true;
+        let mut filled = vec![start];
+        let mut frontier = VecDeque::from([start]);
+        while let Some(pos) = frontier.pop_front() {
+            for next_pos in self.neighbours_4(pos) {
+                let index = next_pos.row * self.width + next_pos.col;
+                if !is_filled[index] && can_spread(&self[pos], &self[next_pos]) {
+                    is_filled[index] = true;
+                    filled.push(next_pos);
+                    frontier.push_back(next_pos);
+                }
+            }
+        }
+        filled
+    }
+
+    /// Render each cell as a character, with a line feed after each row
+    pub fn render<F>(&self, mut to_char: F) -> String
+    where
+        F: FnMut(Pos, &T) -> char,
+    {
+        let mut rendered = String::with_capacity((self.width + 1) * self.height);
+        for (pos, cell) in self.iter() {
+            rendered.push(to_char(pos, cell));
+            if pos.col + 1 == self.width {
+                rendered.push('\n');
+            }
+        }
+        rendered
+    }
+}
+
+impl Grid<char> {
+    pub fn parse_chars(text: &str) -> Self {
+        Self::parse_with(text, |_, ch| ch)
+    }
+
+    pub fn find_char(&self, ch: char) -> Option<Pos> {
+        self.find(|&cell| cell == ch)
+    }
+}
+
+impl<T> Index<Pos> for Grid<T> {
+    type Output = T;
+
+    #[inline(always)]
+    fn index(&self, pos: Pos) -> &Self::Output {
+        assert!(self.contains(pos), "{pos:?} is outside the grid");
+        &self.cells[pos.row * self.width + pos.col]
+    }
+}
+
+impl<T> IndexMut<Pos> for Grid<T> {
+    #[inline(always)]
+    fn index_mut(&mut self, pos: Pos) -> &mut Self::Output {
+        assert!(self.contains(pos), "{pos:?} is outside the grid");
+        &mut self.cells[pos.row * self.width + pos.col]
+    }
+}
+
+impl<T: Display> Display for Grid<T> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        for row in self.rows() {
+            for cell in row {
+                write!(f, "{cell}")?;
+            }
+            writeln!(f)?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const EXAMPLE: &str = "
+#.##
+#S.#
+##E#";
+
+    #[test]
+    fn test_parse_and_find_char() {
+        let grid = Grid::parse_chars(EXAMPLE);
+        assert_eq!((grid.width(), grid.height()), (4, 3));
+        assert_eq!(grid.find_char('S'), Some(Pos::new(1, 1)));
+        assert_eq!(grid.find_char('E'), Some(Pos::new(2, 2)));
+        assert_eq!(grid.find_char('X'), None);
+        assert_eq!(grid.to_string(), "#.##\n#S.#\n##E#\n");
+    }
+
+    #[test]
+    fn test_parsing_stops_at_blank_line() {
+        let grid = Grid::parse_chars("ab\ncd\n\n<>^v\n");
+        assert_eq!(grid.height(), 2);
+        assert_eq!(grid.row(1), ['c', 'd']);
+    }
+
+    #[test]
+    fn test_rows_and_columns() {
+        let grid = Grid::parse_with("123\n456", |_, ch| ch.to_digit(10).unwrap());
+        assert_eq!(grid.rows().collect::<Vec<_>>(), [[1, 2, 3], [4, 5, 6]]);
+        assert_eq!(grid.column(1).copied().collect::<Vec<_>>(), [2, 5]);
+        let column_sums: Vec<u32> = grid.columns().map(|col| col.sum()).collect();
+        assert_eq!(column_sums, [5, 7, 9]);
+        assert_eq!(grid.column(2).rev().copied().collect::<Vec<_>>(), [6, 3]);
+    }
+
+    #[test]
+    fn test_rows_of_a_grid_with_no_columns() {
+        let grid = Grid::new(0, 3, 'x');
+        assert_eq!(grid.rows().count(), 3);
+        assert!(grid.rows().all(|row| row.is_empty()));
+        assert_eq!(Grid::new(2, 0, 'x').rows().count(), 0);
+    }
+
+    #[test]
+    #[should_panic(expected = "column 3 is outside the grid")]
+    fn test_column_is_bounds_checked() {
+        let grid = Grid::parse_with("abc\ndef", |_, ch| ch);
+        let _ = grid.column(3);
+    }
+
+    #[test]
+    fn test_neighbours_are_bounds_checked() {
+        let grid = Grid::new(3, 3, 0);
+        let corner = Pos::new(0, 0);
+        let centre = Pos::new(1, 1);
+        assert_eq!(grid.neighbours_4(corner).count(), 2);
+        assert_eq!(grid.neighbours_8(corner).count(), 3);
+        assert_eq!(grid.neighbours_4(centre).count(), 4);
+        assert_eq!(grid.neighbours_8(centre).count(), 8);
+        assert_eq!(grid.step(corner, Direction::Up), None);
+        assert_eq!(grid.step(Pos::new(2, 2), Direction::Right), None);
+        assert_eq!(grid.step(corner, Direction::Down), Some(Pos::new(1, 0)));
+    }
+
+    #[test]
+    fn test_directions() {
+        assert_eq!(Direction::Up.turn_right(), Direction::Right);
+        assert_eq!(Direction::Up.turn_left(), Direction::Left);
+        assert_eq!(Direction::Left.reverse(), Direction::Right);
+        assert_eq!(Direction::Up.quarter_turns_to(Direction::Left), 1);
+        assert_eq!(Direction::Up.quarter_turns_to(Direction::Down), 2);
+        assert_eq!(Direction::from_arrow('v'), Some(Direction::Down));
+        assert_eq!(Direction::Down.to_arrow(), 'v');
+        for dir in Direction::ALL {
+            assert_eq!(dir.turn_right().turn_left(), dir);
+        }
+    }
+
+    #[test]
+    fn test_flood_fill() {
+        let grid = Grid::parse_chars("AAB\nABB\nCCB");
+        let region = grid.flood_fill(Pos::new(0, 2), |from, to| from == to);
+        assert_eq!(region.len(), 4);
+        assert_eq!(region[0], Pos::new(0, 2));
+        assert!(region.contains(&Pos::new(2, 2)));
+    }
+
+    #[test]
+    fn test_map_and_render() {
+        let grid = Grid::parse_with("..@\n@@.", |_, ch| ch == '@');
+        let counts = grid.map(|pos, _| {
+            grid.neighbours_8(pos)
+                .filter(|&neighbour| grid[neighbour])
+                .count()
+        });
+        assert_eq!(
+            counts.render(|_, count| char::from(b'0' + *count as u8)),
+            "231\n122\n"
+        );
+    }
+
+    #[test]
+    fn test_from_rows_rejects_ragged_rows() {
+        assert!(Grid::from_rows(vec![vec![1, 2], vec![3]]).is_none());
+    }
+}
@@ -0,0 +1,85 @@
+/// One of the 4 movement directions, in clockwise order
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash, PartialOrd, Ord)]
+#[repr(u8)]
+pub enum Direction {
+    Up,
+    Right,
+    Down,
+    Left,
+}
+
+impl Direction {
+    pub const ALL: [Direction; 4] = [
+        Direction::Up,
+        Direction::Right,
+        Direction::Down,
+        Direction::Left,
+    ];
+
+    /// The (row, col) offset of a single step in this direction
+    #[inline(always)]
+    pub fn offset(&self) -> (isize, isize) {
+        match self {
+            Direction::Up => (-1, 0),
+            Direction::Right => (0, 1),
+            Direction::Down => (1, 0),
+            Direction::Left => (0, -1),
+        }
+    }
+
+    #[inline(always)]
+    pub fn turn_right(&self) -> Direction {
+        Direction::ALL[(*self as usize + 1) % 4]
+    }
+
+    #[inline(always)]
+    pub fn turn_left(&self) -> Direction {
+        Direction::ALL[(*self as usize + 3) % 4]
+    }
+
+    #[inline(always)]
+    pub fn reverse(&self) -> Direction {
+        Direction::ALL[(*self as usize + 2) % 4]
+    }
+
+    /// The number of 90 degree turns needed to face the other direction (0, 1 or 2)
+    pub fn quarter_turns_to(&self, other: Direction) -> usize {
+        let clockwise_turns = (other as usize + 4 - *self as usize) % 4;
+        clockwise_turns.min(4 - clockwise_turns)
+    }
+
+    pub fn is_horizontal(&self) -> bool {
+        matches!(self, Direction::Left | Direction::Right)
+    }
+
+    /// Parse an arrow character (^, >, v or <), as used in many puzzle inputs
+    pub fn from_arrow(arrow: char) -> Option<Direction> {
+        match arrow {
+            '^' => Some(Direction::Up),
+            '>' => Some(Direction::Right),
+            'v' => Some(Direction::Down),
+            '<' => Some(Direction::Left),
+            _ => None,
+        }
+    }
+
+    pub fn to_arrow(&self) -> char {
+        match self {
+            Direction::Up => '^',
+            Direction::Right => '>',
+            Direction::Down => 'v',
+            Direction::Left => '<',
+        }
+    }
+
+    /// Parse a letter (U, R, D or L), as used in many lists of moves
+    pub fn from_letter(letter: char) -> Option<Direction> {
+        match letter {
+            'U' => Some(Direction::Up),
+            'R' => Some(Direction::Right),
+            'D' => Some(Direction::Down),
+            'L' => Some(Direction::Left),
+            _ => None,
+        }
+    }
+}
@@ -0,0 +1,46 @@
+use super::Direction;
+
+/// A cell position in a grid, with (0, 0) at the top left
+#[derive(Debug, Default, Copy, Clone, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct Pos {
+    pub row: usize,
+    pub col: usize,
+}
+
+/// Offsets (row, col) to the 8 neighbouring cells, clockwise from the top left
+pub const NEIGHBOUR_OFFSETS_8: [(isize, isize); 8] = [
+    (-1, -1),
+    (-1, 0),
+    (-1, 1),
+    (0, 1),
+    (1, 1),
+    (1, 0),
+    (1, -1),
+    (0, -1),
+];
+
+impl Pos {
+    pub const fn new(row: usize, col: usize) -> Self {
+        Self { row, col }
+    }
+
+    /// Move by a (row, col) offset, or return None if that would make a coordinate negative.
+    /// This does not check the far edges of a grid. Use [`super::Grid::step`] for that.
+    #[inline(always)]
+    pub fn offset_by(&self, row_offset: isize, col_offset: isize) -> Option<Pos> {
+        Some(Pos {
+            row: self.row.checked_add_signed(row_offset)?,
+            col: self.col.checked_add_signed(col_offset)?,
+        })
+    }
+
+    #[inline(always)]
+    pub fn step(&self, dir: Direction) -> Option<Pos> {
+        let (row_offset, col_offset) = dir.offset();
+        self.offset_by(row_offset, col_offset)
+    }
+
+    pub fn manhattan_distance(&self, other: Pos) -> usize {
+        self.row.abs_diff(other.row) + self.col.abs_diff(other.col)
+    }
+}
@@ -0,0 +1,324 @@
+mod http;
+
+pub use http::{CurlBackend, HttpBackend, HttpResponse, PlainHttpBackend};
+
+use std::path::{Path, PathBuf};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+use thiserror::Error;
+
+pub const DEFAULT_BASE_URL: &str = "https://adventofcode.com";
+pub const SESSION_ENV_VAR: &str = "AOC_SESSION";
+pub const BASE_URL_ENV_VAR: &str = "AOC_BASE_URL";
+pub const DEFAULT_DATA_DIR: &str = "data";
+
+/// Advent of Code asks automated tools to identify themselves
+const USER_AGENT: &str = "github.com/AndrewTweddle/CodingExercises aoc_common input provider";
+
+/// Holds the time of the last download, so that separate runs share the rate limit
+const LAST_DOWNLOAD_FILE_NAME: &str = ".last_download";
+
+#[derive(Debug, Error)]
+pub enum FetchError {
+    #[error(transparent)]
+    Io(#[from] std::io::Error),
+    #[error(
+        "{} is missing, and can't be downloaded without a session token in {SESSION_ENV_VAR}",
+        path.display()
+    )]
+    MissingSession { path: PathBuf },
+    #[error("{} is not named like a puzzle input, e.g. day1_input.txt", path.display())]
+    UnknownDay { path: PathBuf },
+    #[error("GET {url} returned status {status}: {body}")]
+    Status {
+        url: String,
+        status: u16,
+        body: String,
+    },
+    #[error("{0}")]
+    Backend(String),
+}
+
+/// Finds puzzle inputs in a local cache (the data folder), downloading any which are missing
+pub struct InputProvider<B = CurlBackend> {
+    year: u16,
+    data_dir: PathBuf,
+    base_url: String,
+    session: Option<String>,
+    min_interval: Duration,
+    backend: B,
+}
+
+impl InputProvider<CurlBackend> {
+    /// A provider for the data folder of a year's crate, configured from environment variables
+    pub fn from_env(year: u16) -> Self {
+        let mut provider = InputProvider::new(year, DEFAULT_DATA_DIR, CurlBackend);
+        provider.session = std::env::var(SESSION_ENV_VAR).ok();
+        if let Ok(base_url) = std::env::var(BASE_URL_ENV_VAR) {
+            provider.base_url = base_url;
+        }
+        provider
+    }
+}
+
+impl<B: HttpBackend> InputProvider<B> {
+    pub fn new(year: u16, data_dir: impl Into<PathBuf>, backend: B) -> Self {
+        Self {
+            year,
+            data_dir: data_dir.into(),
+            base_url: DEFAULT_BASE_URL.to_string(),
+            session: None,
+            min_interval: Duration::from_secs(5),
+            backend,
+        }
+    }
+
+    pub fn with_session(mut self, session: impl Into<String>) -> Self {
+        self.session = Some(session.into());
+        self
+    }
+
+    /// Download from somewhere other than the Advent of Code site, such as a local test server
+    pub fn with_base_url(mut self, base_url: impl Into<String>) -> Self {
+        self.base_url = base_url.into();
+        self
+    }
+
+    /// The minimum time between downloads
+    pub fn with_min_interval(mut self, min_interval: Duration) -> Self {
+        self.min_interval = min_interval;
+        self
+    }
+
+    pub fn input_path(&self, day: u8) -> PathBuf {
+        self.data_dir.join(format!("day{day}_input.txt"))
+    }
+
+    pub fn load_day(&self, day: u8) -> Result<String, FetchError> {
+        let path = self.input_path(day);
+        self.load_or_download(&path, day)
+    }
+
+    /// Read an input file, such as "data/day4_input.txt", downloading it if it is missing
+    pub fn load_file(&self, path: impl AsRef<Path>) -> Result<String, FetchError> {
+        let path = path.as_ref();
+        if path.exists() {
+            return Ok(std::fs::read_to_string(path)?);
+        }
+        let day = day_from_file_name(path).ok_or_else(|| FetchError::UnknownDay {
+            path: path.to_path_buf(),
+        })?;
+        self.load_or_download(path, day)
+    }
+
+    fn load_or_download(&self, path: &Path, day: u8) -> Result<String, FetchError> {
+        if path.exists() {
+            return Ok(std::fs::read_to_string(path)?);
+        }
+        let session = self
+            .session
+            .as_deref()
+            .ok_or_else(|| FetchError::MissingSession {
+                path: path.to_path_buf(),
+            })?;
+
+        let url = format!(
+            "{}/{}/day/{day}/input",
+            self.base_url.trim_end_matches('/'),
+            self.year
+        );
+        let cookie = format!("session={session}");
+        self.wait_for_rate_limit()?;
+        let response = self
+            .backend
+            .get(&url, &[("Cookie", &cookie), ("User-Agent", USER_AGENT)])?;
+        if response.status != 200 {
+            return Err(FetchError::Status {
+                url,
+                status: response.status,
+                body: response.body.trim().to_string(),
+            });
+        }
+
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        std::fs::write(path, &response.body)?;
+        Ok(response.body)
+    }
+
+    fn wait_for_rate_limit(&self) -> Result<(), FetchError> {
+        let last_download_path = self.data_dir.join(LAST_DOWNLOAD_FILE_NAME);
+        let last_download_millis = std::fs::read_to_string(&last_download_path)
+            .ok()
+            .and_then(|contents| contents.trim().parse::<u64>().ok());
+        if let Some(last_millis) = last_download_millis {
+            let next_allowed = UNIX_EPOCH + Duration::from_millis(last_millis) + self.min_interval;
+            if let Ok(wait_time) = next_allowed.duration_since(SystemTime::now()) {
+                std::thread::sleep(wait_time);
+            }
+        }
+
+        let now_millis = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map_or(0, |since_epoch| since_epoch.as_millis());
+        std::fs::create_dir_all(&self.data_dir)?;
+        std::fs::write(&last_download_path, now_millis.to_string())?;
+        Ok(())
+    }
+}
+
+/// The day of a puzzle input file named like "day12_input.txt"
+pub fn day_from_file_name(path: &Path) -> Option<u8> {
+    path.file_name()?
+        .to_str()?
+        .strip_prefix("day")?
+        .strip_suffix("_input.txt")?
+        .parse()
+        .ok()
+        .filter(|day| (1..=25).contains(day))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::{BufRead, BufReader, Write};
+    use std::net::TcpListener;
+    use std::thread::JoinHandle;
+    use std::time::Instant;
+
+    /// A local stand-in for the Advent of Code server, which answers a fixed number of requests
+    /// and returns the request lines it received
+    fn spawn_server(responses: Vec<(u16, &'static str)>) -> (String, JoinHandle<Vec<String>>) {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let base_url = format!("http://{}", listener.local_addr().unwrap());
+        let handle = std::thread::spawn(move || {
+            let mut requests = Vec::new();
+            for (status, body) in responses {
+                let (mut stream, _) = listener.accept().unwrap();
+                let mut reader = BufReader::new(stream.try_clone().unwrap());
+                let mut line = String::new();
+                while reader.read_line(&mut line).unwrap() > 2 {
+                    requests.push(line.trim_end().to_string());
+                    line.clear();
+                }
+                write!(
+                    stream,
+                    "HTTP/1.1 {status} Whatever\r\nContent-Length: {}\r\n\r\n{body}",
+                    body.len()
+                )
+                .unwrap();
+            }
+            requests
+        });
+        (base_url, handle)
+    }
+
+    fn temp_data_dir(test_name: &str) -> PathBuf {
+        let dir =
+            std::env::temp_dir().join(format!("aoc_common_{test_name}_{}", std::process::id()));
+        let _ = std::fs::remove_dir_all(&dir);
+        dir
+    }
+
+    #[test]
+    fn test_downloads_once_then_uses_the_cache() {
+        let (base_url, server) = spawn_server(vec![(200, "1 2 3\n")]);
+        let data_dir = temp_data_dir("cache");
+        let provider = InputProvider::new(2025, &data_dir, PlainHttpBackend)
+            .with_base_url(base_url)
+            .with_session("secret");
+
+        assert_eq!(provider.load_day(3).unwrap(), "1 2 3\n");
+        assert_eq!(provider.load_day(3).unwrap(), "1 2 3\n");
+        assert_eq!(
+            std::fs::read_to_string(data_dir.join("day3_input.txt")).unwrap(),
+            "1 2 3\n"
+        );
+
+        let requests = server.join().unwrap();
+        assert_eq!(requests[0], "GET /2025/day/3/input HTTP/1.1");
+        assert!(requests.contains(&"Cookie: session=secret".to_string()));
+        std::fs::remove_dir_all(data_dir).unwrap();
+    }
+
+    #[test]
+    fn test_errors_are_not_cached() {
+        let (base_url, server) = spawn_server(vec![(404, "Not unlocked yet")]);
+        let data_dir = temp_data_dir("status");
+        let provider = InputProvider::new(2025, &data_dir, PlainHttpBackend)
+            .with_base_url(base_url)
+            .with_session("secret");
+
+        let err = provider
+            .load_file(data_dir.join("day25_input.txt"))
+            .unwrap_err();
+        assert!(matches!(err, FetchError::Status { status: 404, .. }));
+        assert!(!provider.input_path(25).exists());
+        server.join().unwrap();
+        std::fs::remove_dir_all(data_dir).unwrap();
+    }
+
+    #[test]
+    fn test_missing_session_and_unknown_file_names() {
+        let data_dir = temp_data_dir("session");
+        let provider = InputProvider::new(2025, &data_dir, PlainHttpBackend);
+        assert!(matches!(
+            provider.load_day(1),
+            Err(FetchError::MissingSession { .. })
+        ));
+        assert!(matches!(
+            provider.load_file(data_dir.join("day1_example.txt")),
+            Err(FetchError::UnknownDay { .. })
+        ));
+    }
+
+    #[test]
+    fn test_rate_limit() {
+        let (base_url, server) = spawn_server(vec![(200, "first"), (200, "second")]);
+        let data_dir = temp_data_dir("rate_limit");
+        let provider = InputProvider::new(2025, &data_dir, PlainHttpBackend)
+            .with_base_url(base_url)
+            .with_session("secret")
+            .with_min_interval(Duration::from_millis(200));
+
+        let start_time = Instant::now();
+        assert_eq!(provider.load_day(1).unwrap(), "first");
+        assert_eq!(provider.load_day(2).unwrap(), "second");
+        assert!(start_time.elapsed() >= Duration::from_millis(200));
+        server.join().unwrap();
+        std::fs::remove_dir_all(data_dir).unwrap();
+    }
+
+    #[test]
+    fn test_curl_backend() {
+        if std::process::Command::new("curl")
+            .arg("--version")
+            .output()
+            .is_err()
+        {
+            return;
+        }
+        let (base_url, server) = spawn_server(vec![(200, "via curl\n")]);
+        let data_dir = temp_data_dir("curl");
+        let provider = InputProvider::new(2024, &data_dir, CurlBackend)
+            .with_base_url(base_url)
+            .with_session("secret");
+
+        assert_eq!(provider.load_day(7).unwrap(), "via curl\n");
+        let requests = server.join().unwrap();
+        assert!(requests[0].starts_with("GET /2024/day/7/input "));
+        assert!(requests.contains(&"Cookie: session=secret".to_string()));
+        std::fs::remove_dir_all(data_dir).unwrap();
+    }
+
+    #[test]
+    fn test_day_from_file_name() {
+        assert_eq!(
+            day_from_file_name(Path::new("data/day12_input.txt")),
+            Some(12)
+        );
+        assert_eq!(day_from_file_name(Path::new("day1_input.txt")), Some(1));
+        assert_eq!(day_from_file_name(Path::new("data/day26_input.txt")), None);
+        assert_eq!(day_from_file_name(Path::new("data/day3_example.txt")), None);
+    }
+}
@@ -0,0 +1,103 @@
+use super::FetchError;
+use std::io::{Read, Write};
+use std::net::TcpStream;
+use std::process::{Command, Stdio};
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct HttpResponse {
+    pub status: u16,
+    pub body: String,
+}
+
+/// A way of making HTTP GET requests, so that downloads can be redirected or faked in tests
+pub trait HttpBackend {
+    fn get(&self, url: &str, headers: &[(&str, &str)]) -> Result<HttpResponse, FetchError>;
+}
+
+/// Downloads using the `curl` command line tool, which supports HTTPS without extra crates.
+/// Headers are passed on stdin, so that the session token is not visible in the process list.
+#[derive(Debug, Default, Clone, Copy)]
+pub struct CurlBackend;
+
+impl HttpBackend for CurlBackend {
+    fn get(&self, url: &str, headers: &[(&str, &str)]) -> Result<HttpResponse, FetchError> {
+        let mut child = Command::new("curl")
+            .args(["--silent", "--show-error", "--location"])
+            .args(["--header", "@-", "--write-out", "\n%{http_code}", url])
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped())
+            .spawn()
+            .map_err(|err| FetchError::Backend(format!("could not run curl: {err}")))?;
+
+        let header_lines: String = headers
+            .iter()
+            .map(|(name, value)| format!("{name}: {value}\n"))
+            .collect();
+        child
+            .stdin
+            .take()
+            .expect("curl's stdin is piped")
+            .write_all(header_lines.as_bytes())?;
+
+        let output = child.wait_with_output()?;
+        if !output.status.success() {
+            let stderr = String::from_utf8_lossy(&output.stderr);
+            return Err(FetchError::Backend(format!(
+                "curl failed: {}",
+                stderr.trim()
+            )));
+        }
+        let stdout = String::from_utf8_lossy(&output.stdout);
+        let (body, status) = stdout
+            .rsplit_once('\n')
+            .ok_or_else(|| FetchError::Backend("curl gave no status code".to_string()))?;
+        let status = status
+            .trim()
+            .parse()
+            .map_err(|_| FetchError::Backend(format!("curl gave an invalid status {status:?}")))?;
+        Ok(HttpResponse {
+            status,
+            body: body.to_string(),
+        })
+    }
+}
+
+/// A minimal HTTP/1.1 client for plain `http://` URLs, such as a local stand-in server.
+/// It does not support HTTPS or chunked transfer encoding.
+#[derive(Debug, Default, Clone, Copy)]
+pub struct PlainHttpBackend;
+
+impl HttpBackend for PlainHttpBackend {
+    fn get(&self, url: &str, headers: &[(&str, &str)]) -> Result<HttpResponse, FetchError> {
+        let unsupported = || FetchError::Backend(format!("not a plain http URL: {url}"));
+        let host_and_path = url.strip_prefix("http://").ok_or_else(unsupported)?;
+        let (host, path) = match host_and_path.find('/') {
+            Some(index) => host_and_path.split_at(index),
+            None => (host_and_path, "/"),
+        };
+
+        let mut request = format!("GET {path} HTTP/1.1\r\nHost: {host}\r\nConnection: close\r\n");
+        for (name, value) in headers {
+            request.push_str(&format!("{name}: {value}\r\n"));
+        }
+        request.push_str("\r\n");
+
+        let mut stream = TcpStream::connect(host)?;
+        stream.write_all(request.as_bytes())?;
+        let mut response = String::new();
+        stream.read_to_string(&mut response)?;
+
+        let invalid = || FetchError::Backend(format!("invalid HTTP response from {host}"));
+        let (head, body) = response.split_once("\r\n\r\n").ok_or_else(invalid)?;
+        let status = head
+            .split_whitespace()
+            .nth(1)
+            .and_then(|status| status.parse().ok())
+            .ok_or_else(invalid)?;
+        Ok(HttpResponse {
+            status,
+            body: body.to_string(),
+        })
+    }
+}
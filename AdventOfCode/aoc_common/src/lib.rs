@@ -0,0 +1,10 @@
+pub mod automaton;
+pub mod cards;
+pub mod cuboid;
+pub mod grid;
+pub mod input;
+pub mod ocr;
+pub mod parse;
+pub mod ranges;
+pub mod scaffold;
+pub mod search;
@@ -0,0 +1,180 @@
+//! Recognition of the 4x6 capital letters which some puzzles draw as their answer
+//! (such as 2021 day 13 and 2022 day 10).
+//!
+//! Each letter is 4 pixels wide and 6 pixels high, with a blank column after it.
+//! `#` is a lit pixel. Any other character is unlit, so either `.` or ` ` can be used.
+
+use thiserror::Error;
+
+pub const LETTER_WIDTH: usize = 4;
+pub const LETTER_HEIGHT: usize = 6;
+
+/// The width of a letter, including the blank column after it
+const LETTER_SPACING: usize = LETTER_WIDTH + 1;
+
+const FONT: [(char, &str); 17] = [
+    ('A', ".##.#..##..######..##..#"),
+    ('B', "###.#..####.#..##..####."),
+    ('C', ".##.#..##...#...#..#.##."),
+    ('E', "#####...###.#...#...####"),
+    ('F', "#####...###.#...#...#..."),
+    ('G', ".##.#..##...#.###..#.###"),
+    ('H', "#..##..######..##..##..#"),
+    ('I', ".###..#...#...#...#..###"),
+    ('J', "..##...#...#...##..#.##."),
+    ('K', "#..##.#.##..#.#.#.#.#..#"),
+    ('L', "#...#...#...#...#...####"),
+    ('O', ".##.#..##..##..##..#.##."),
+    ('P', "###.#..##..####.#...#..."),
+    ('R', "###.#..##..####.#.#.#..#"),
+    ('S', ".####...#....##....####."),
+    ('U', "#..##..##..##..##..#.##."),
+    ('Z', "####...#..#..#..#...####"),
+];
+
+#[derive(Debug, Error, PartialEq, Eq)]
+pub enum OcrError {
+    #[error("expected an image {LETTER_HEIGHT} pixels high, but it is {0} pixels high")]
+    WrongHeight(usize),
+    #[error("letter {index} is not in the font:\n{glyph}")]
+    UnknownLetter { index: usize, glyph: String },
+}
+
+/// Read the letters in an image with one line of text per row of pixels.
+/// Blank lines before and after the image are ignored.
+pub fn read_letters(image: &str) -> Result<String, OcrError> {
+    let rows: Vec<&[u8]> = image
+        .trim_matches('\n')
+        .lines()
+        .map(str::as_bytes)
+        .collect();
+    if rows.len() != LETTER_HEIGHT {
+        return Err(OcrError::WrongHeight(rows.len()));
+    }
+    let width = rows.iter().map(|row| row.len()).max().unwrap_or(0);
+    read_letters_from_pixels(width, |x, y| rows[y].get(x) == Some(&b'#'))
+}
+
+/// Read the letters in the lit points, where y is the row (from 0 to 5) and x is the column
+pub fn read_letters_from_points<I>(points: I) -> Result<String, OcrError>
+where
+    I: IntoIterator<Item = (usize, usize)>,
+{
+    let mut rows: Vec<Vec<bool>> = Vec::new();
+    for (x, y) in points {
+        if y >= rows.len() {
+            rows.resize(y + 1, Vec::new());
+        }
+        if x >= rows[y].len() {
+            rows[y].resize(x + 1, false);
+        }
+        rows[y][x] = true;
+    }
+    if rows.len() > LETTER_HEIGHT {
+        return Err(OcrError::WrongHeight(rows.len()));
+    }
+    let width = rows.iter().map(Vec::len).max().unwrap_or(0);
+    read_letters_from_pixels(width, |x, y| {
+        rows.get(y).and_then(|row| row.get(x)) == Some(&true)
+    })
+}
+
+fn read_letters_from_pixels<F>(width: usize, is_lit: F) -> Result<String, OcrError>
+where
+    F: Fn(usize, usize) -> bool,
+{
+    let letter_count = width.div_ceil(LETTER_SPACING);
+    (0..letter_count)
+        .map(|index| {
+            let glyph: String = (0..LETTER_HEIGHT)
+                .flat_map(|y| (0..LETTER_WIDTH).map(move |dx| (index * LETTER_SPACING + dx, y)))
+                .map(|(x, y)| if is_lit(x, y) { '#' } else { '.' })
+                .collect();
+            FONT.iter()
+                .find(|(_, font_glyph)| *font_glyph == glyph)
+                .map(|&(letter, _)| letter)
+                .ok_or_else(|| OcrError::UnknownLetter {
+                    index,
+                    glyph: glyph
+                        .as_bytes()
+                        .chunks(LETTER_WIDTH)
+                        .map(|row| String::from_utf8_lossy(row).into_owned())
+                        .collect::<Vec<_>>()
+                        .join("\n"),
+                })
+        })
+        .collect()
+}
+
+/// Draw the letters with `#` for lit pixels and `.` for unlit pixels (the inverse of read_letters)
+pub fn render_letters(letters: &str) -> Option<String> {
+    let glyphs: Vec<&str> = letters
+        .chars()
+        .map(|letter| {
+            FONT.iter()
+                .find(|&&(font_letter, _)| font_letter == letter)
+                .map(|&(_, glyph)| glyph)
+        })
+        .collect::<Option<_>>()?;
+    let rows: Vec<String> = (0..LETTER_HEIGHT)
+        .map(|y| {
+            glyphs
+                .iter()
+                .map(|glyph| &glyph[y * LETTER_WIDTH..(y + 1) * LETTER_WIDTH])
+                .collect::<Vec<_>>()
+                .join(".")
+        })
+        .collect();
+    Some(rows.join("\n"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_read_letters() {
+        let image = "\
+####.#..#.####.####.####.#..#..##..####
+#....#..#....#.#.......#.#..#.#..#....#
+###..####...#..###....#..####.#......#.
+#....#..#..#...#.....#...#..#.#.....#..
+#....#..#.#....#....#....#..#.#..#.#...
+####.#..#.####.#....####.#..#..##..####";
+        assert_eq!(read_letters(image), Ok("EHZFZHCZ".to_string()));
+        let spaced_image = image.replace('.', " ");
+        assert_eq!(read_letters(&spaced_image), Ok("EHZFZHCZ".to_string()));
+    }
+
+    #[test]
+    fn test_whole_font_round_trip() {
+        let alphabet: String = FONT.iter().map(|&(letter, _)| letter).collect();
+        let image = render_letters(&alphabet).unwrap();
+        assert_eq!(read_letters(&image), Ok(alphabet));
+        assert_eq!(render_letters("abc"), None);
+    }
+
+    #[test]
+    fn test_read_letters_from_points() {
+        let image = render_letters("LOU").unwrap();
+        let points = image.lines().enumerate().flat_map(|(y, row)| {
+            row.char_indices()
+                .filter(|&(_, pixel)| pixel == '#')
+                .map(move |(x, _)| (x, y))
+        });
+        assert_eq!(read_letters_from_points(points), Ok("LOU".to_string()));
+    }
+
+    #[test]
+    fn test_unreadable_images() {
+        assert_eq!(read_letters("#\n#"), Err(OcrError::WrongHeight(2)));
+        let image = render_letters("AB").unwrap().replacen(".##.", "####", 1);
+        assert_eq!(
+            read_letters(&image),
+            Err(OcrError::UnknownLetter {
+                index: 0,
+                glyph: "####\n#..#\n#..#\n####\n#..#\n#..#".to_string()
+            })
+        );
+    }
+}
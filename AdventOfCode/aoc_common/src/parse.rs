@@ -0,0 +1,279 @@
+use std::fmt::Display;
+use std::str::FromStr;
+use thiserror::Error;
+
+/// An error in a puzzle input, with the (1-based) line and column where it was found
+#[derive(Debug, Clone, PartialEq, Eq, Error)]
+#[error(
+    "line {line}, column {column}: {message}\n{excerpt}\n{caret:>column$}",
+    caret = "^"
+)]
+pub struct ParseError {
+    pub line: usize,
+    pub column: usize,
+    /// The line of the input containing the error
+    pub excerpt: String,
+    pub message: String,
+}
+
+/// The full text of a puzzle input, used to locate errors in any fragment of it.
+///
+/// Every `&str` passed to its methods must be a slice of the input text,
+/// such as a line, section or token obtained by splitting it.
+#[derive(Debug, Clone, Copy)]
+pub struct Input<'a> {
+    text: &'a str,
+}
+
+impl<'a> Input<'a> {
+    pub fn new(text: &'a str) -> Self {
+        Self { text }
+    }
+
+    pub fn text(&self) -> &'a str {
+        self.text
+    }
+
+    pub fn lines(&self) -> std::str::Lines<'a> {
+        self.text.lines()
+    }
+
+    /// Create an error located at the start of the fragment.
+    /// If the fragment is not part of the input, the error is placed at the end of the input.
+    pub fn error_at(&self, fragment: &str, message: impl Display) -> ParseError {
+        self.error_at_offset(self.offset_of(fragment), message)
+    }
+
+    /// The byte offset of the fragment in the input, or the input's length if it is not part of it
+    fn offset_of(&self, fragment: &str) -> usize {
+        let text_start = self.text.as_ptr() as usize;
+        (fragment.as_ptr() as usize)
+            .checked_sub(text_start)
+            .filter(|&offset| offset <= self.text.len())
+            .unwrap_or(self.text.len())
+    }
+
+    /// Create an error located just after the last character of the input
+    pub fn error_at_end(&self, message: impl Display) -> ParseError {
+        self.error_at_offset(self.text.len(), message)
+    }
+
+    fn error_at_offset(&self, offset: usize, message: impl Display) -> ParseError {
+        let before = &self.text[..offset];
+        let line_start = before.rfind('\n').map_or(0, |index| index + 1);
+        let line_end = self.text[offset..]
+            .find('\n')
+            .map_or(self.text.len(), |index| offset + index);
+        ParseError {
+            line: before.matches('\n').count() + 1,
+            column: before[line_start..].chars().count() + 1,
+            excerpt: self.text[line_start..line_end]
+                .trim_end_matches('\r')
+                .to_string(),
+            message: message.to_string(),
+        }
+    }
+
+    /// The next line from an iterator over the lines of a fragment (such as a section),
+    /// or an error at the end of the fragment saying what was expected instead
+    pub fn next_line<I>(
+        &self,
+        fragment: &'a str,
+        line_iter: &mut I,
+        expected: &str,
+    ) -> Result<&'a str, ParseError>
+    where
+        I: Iterator<Item = &'a str>,
+    {
+        line_iter.next().ok_or_else(|| {
+            let end_of_fragment = &fragment[fragment.len()..];
+            let rest_of_input = self.text[self.offset_of(end_of_fragment)..].trim();
+            let found = if rest_of_input.is_empty() {
+                "end of input"
+            } else {
+                "end of section"
+            };
+            self.error_at(
+                end_of_fragment,
+                format!("expected {expected}, found {found}"),
+            )
+        })
+    }
+
+    /// Groups of lines separated by one or more blank lines, without their final line break
+    pub fn sections(&self) -> impl Iterator<Item = &'a str> {
+        let text = self.text;
+        let mut section_start: Option<usize> = None;
+        let mut offset = 0;
+        let mut lines = text.split_inclusive('\n');
+        std::iter::from_fn(move || {
+            for line in lines.by_ref() {
+                let line_start = offset;
+                offset += line.len();
+                match (line.trim().is_empty(), section_start) {
+                    (true, Some(start)) => {
+                        section_start = None;
+                        return Some(text[start..line_start].trim_end_matches(['\r', '\n']));
+                    }
+                    (false, None) => section_start = Some(line_start),
+                    _ => {}
+                }
+            }
+            section_start
+                .take()
+                .map(|start| text[start..].trim_end_matches(['\r', '\n']))
+        })
+    }
+
+    /// Parse a (trimmed) fragment of the input, such as a number
+    pub fn parse<T>(&self, fragment: &'a str) -> Result<T, ParseError>
+    where
+        T: FromStr,
+        T::Err: Display,
+    {
+        let token = fragment.trim();
+        token
+            .parse()
+            .map_err(|err| self.error_at(token, format!("could not parse {token:?}: {err}")))
+    }
+
+    /// Parse a list of numbers separated by commas and/or whitespace, such as "3, 4 5,6"
+    pub fn numbers<T>(&self, fragment: &'a str) -> Result<Vec<T>, ParseError>
+    where
+        T: FromStr,
+        T::Err: Display,
+    {
+        fragment
+            .split(|ch: char| ch == ',' || ch.is_whitespace())
+            .filter(|token| !token.is_empty())
+            .map(|token| self.parse(token))
+            .collect()
+    }
+
+    /// Remove a prefix which the fragment is required to start with
+    pub fn strip_prefix(&self, fragment: &'a str, prefix: &str) -> Result<&'a str, ParseError> {
+        fragment
+            .strip_prefix(prefix)
+            .ok_or_else(|| self.error_at(fragment, format!("expected {prefix:?}")))
+    }
+
+    /// Split a fragment around a separator which it is required to contain
+    pub fn split_once(
+        &self,
+        fragment: &'a str,
+        separator: &str,
+    ) -> Result<(&'a str, &'a str), ParseError> {
+        fragment
+            .split_once(separator)
+            .ok_or_else(|| self.error_at(fragment, format!("expected {separator:?}")))
+    }
+
+    /// Split a line such as "Register A: 729" into its (trimmed) key and value
+    pub fn key_value(
+        &self,
+        line: &'a str,
+        separator: &str,
+    ) -> Result<(&'a str, &'a str), ParseError> {
+        let (key, value) = self.split_once(line, separator)?;
+        Ok((key.trim(), value.trim()))
+    }
+
+    /// Parse the value on a line such as "Register A: 729", checking that the key is as expected
+    pub fn value_for_key<T>(
+        &self,
+        line: &'a str,
+        key: &str,
+        separator: &str,
+    ) -> Result<T, ParseError>
+    where
+        T: FromStr,
+        T::Err: Display,
+    {
+        let (actual_key, value) = self.key_value(line, separator)?;
+        if actual_key != key {
+            return Err(self.error_at(actual_key, format!("expected key {key:?}")));
+        }
+        self.parse(value)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const REGISTERS: &str = "Register A: 729
+Register B: 0x
+Register C: 0
+
+Program: 0,1,5,4,3,0
+";
+
+    #[test]
+    fn test_error_location_and_display() {
+        let input = Input::new(REGISTERS);
+        let line_2 = input.lines().nth(1).unwrap();
+        let err = input
+            .value_for_key::<u64>(line_2, "Register B", ":")
+            .unwrap_err();
+        assert_eq!((err.line, err.column), (2, 13));
+        assert_eq!(err.excerpt, "Register B: 0x");
+        assert_eq!(
+            err.to_string(),
+            "line 2, column 13: could not parse \"0x\": invalid digit found in string\n\
+             Register B: 0x\n            ^"
+        );
+    }
+
+    #[test]
+    fn test_key_values_and_numbers() {
+        let input = Input::new(REGISTERS);
+        let mut lines = input.lines();
+        let line_1 = input
+            .next_line(input.text(), &mut lines, "register A")
+            .unwrap();
+        assert_eq!(
+            input.value_for_key::<u64>(line_1, "Register A", ":"),
+            Ok(729)
+        );
+        let program_line = input.lines().nth(4).unwrap();
+        let (key, value) = input.key_value(program_line, ":").unwrap();
+        assert_eq!(key, "Program");
+        assert_eq!(input.numbers::<u8>(value), Ok(vec![0, 1, 5, 4, 3, 0]));
+
+        let err = input
+            .value_for_key::<u64>(line_1, "Register B", ":")
+            .unwrap_err();
+        assert_eq!((err.line, err.column), (1, 1));
+    }
+
+    #[test]
+    fn test_missing_lines_and_separators() {
+        let input = Input::new("a=1\nb 2");
+        let mut lines = input.lines().skip(2);
+        let err = input
+            .next_line(input.text(), &mut lines, "line c")
+            .unwrap_err();
+        assert_eq!((err.line, err.column), (2, 4));
+        assert_eq!(err.message, "expected line c, found end of input");
+
+        let err = input
+            .key_value(input.lines().nth(1).unwrap(), "=")
+            .unwrap_err();
+        assert_eq!((err.line, err.column, err.excerpt.as_str()), (2, 1, "b 2"));
+    }
+
+    #[test]
+    fn test_sections() {
+        let input = Input::new("\n1\n2\n\n\n3\r\n\r\n4\n5\n");
+        let sections: Vec<&str> = input.sections().collect();
+        assert_eq!(sections, ["1\n2", "3", "4\n5"]);
+        let err = input.parse::<u32>(sections[2]).unwrap_err();
+        assert_eq!((err.line, err.column), (8, 1));
+
+        let err = input
+            .next_line(sections[0], &mut sections[0].lines().skip(2), "line 3")
+            .unwrap_err();
+        assert_eq!((err.line, err.column, err.excerpt.as_str()), (3, 2, "2"));
+        assert_eq!(err.message, "expected line 3, found end of section");
+    }
+}
@@ -0,0 +1,297 @@
+//! Sets of integers stored as ranges, and maps which shift ranges of integers by different amounts.
+//!
+//! Ranges are half-open, as for `Range<T>`. Puzzles usually give inclusive ranges, so there are
+//! methods which accept those too.
+
+use std::fmt::Debug;
+use std::ops::{Add, Range, RangeInclusive, Sub};
+
+/// The integer types which ranges can hold
+pub trait RangeValue:
+    Copy + Ord + Debug + Add<Output = Self> + Sub<Output = Self> + From<u8>
+{
+}
+
+impl<T> RangeValue for T where
+    T: Copy + Ord + Debug + Add<Output = Self> + Sub<Output = Self> + From<u8>
+{
+}
+
+/// A set of integers, stored as sorted ranges which neither overlap nor touch
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct RangeSet<T> {
+    ranges: Vec<Range<T>>,
+}
+
+impl<T: RangeValue> RangeSet<T> {
+    pub fn new() -> Self {
+        Self { ranges: Vec::new() }
+    }
+
+    pub fn ranges(&self) -> &[Range<T>] {
+        &self.ranges
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.ranges.is_empty()
+    }
+
+    /// The number of integers in the set
+    pub fn count(&self) -> T {
+        self.ranges
+            .iter()
+            .fold(T::from(0), |count, range| count + (range.end - range.start))
+    }
+
+    pub fn min(&self) -> Option<T> {
+        self.ranges.first().map(|range| range.start)
+    }
+
+    pub fn max(&self) -> Option<T> {
+        self.ranges.last().map(|range| range.end - T::from(1))
+    }
+
+    pub fn contains(&self, value: T) -> bool {
+        let index = self.ranges.partition_point(|range| range.end <= value);
+        self.ranges
+            .get(index)
+            .is_some_and(|range| range.start <= value)
+    }
+
+    /// Add a range, merging it with any ranges which it overlaps or touches
+    pub fn insert(&mut self, range: Range<T>) {
+        if range.is_empty() {
+            return;
+        }
+        let first = self.ranges.partition_point(|r| r.end < range.start);
+        let last = self.ranges.partition_point(|r| r.start <= range.end);
+        let merged = if first < last {
+            range.start.min(self.ranges[first].start)..range.end.max(self.ranges[last - 1].end)
+        } else {
+            range
+        };
+        self.ranges.splice(first..last, [merged]);
+    }
+
+    pub fn insert_inclusive(&mut self, range: RangeInclusive<T>) {
+        let (start, end) = range.into_inner();
+        if start <= end {
+            self.insert(start..end + T::from(1));
+        }
+    }
+
+    /// Remove a range, splitting any range which it falls inside
+    pub fn remove(&mut self, range: Range<T>) {
+        if range.is_empty() {
+            return;
+        }
+        let first = self.ranges.partition_point(|r| r.end <= range.start);
+        let last = self.ranges.partition_point(|r| r.start < range.end);
+        if first == last {
+            return;
+        }
+        let mut remainders = Vec::with_capacity(2);
+        if self.ranges[first].start < range.start {
+            remainders.push(self.ranges[first].start..range.start);
+        }
+        if range.end < self.ranges[last - 1].end {
+            remainders.push(range.end..self.ranges[last - 1].end);
+        }
+        self.ranges.splice(first..last, remainders);
+    }
+
+    pub fn union(&self, other: &Self) -> Self {
+        let mut union = self.clone();
+        for range in &other.ranges {
+            union.insert(range.clone());
+        }
+        union
+    }
+
+    pub fn intersection(&self, other: &Self) -> Self {
+        let mut ranges = Vec::new();
+        let (mut i, mut j) = (0, 0);
+        while let (Some(a), Some(b)) = (self.ranges.get(i), other.ranges.get(j)) {
+            let start = a.start.max(b.start);
+            let end = a.end.min(b.end);
+            if start < end {
+                ranges.push(start..end);
+            }
+            if a.end < b.end {
+                i += 1;
+            } else {
+                j += 1;
+            }
+        }
+        Self { ranges }
+    }
+
+    /// The integers in this set which are not in the other set
+    pub fn difference(&self, other: &Self) -> Self {
+        let mut difference = self.clone();
+        for range in &other.ranges {
+            difference.remove(range.clone());
+        }
+        difference
+    }
+}
+
+impl<T: RangeValue> Default for RangeSet<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T: RangeValue> From<Range<T>> for RangeSet<T> {
+    fn from(range: Range<T>) -> Self {
+        let mut set = Self::new();
+        set.insert(range);
+        set
+    }
+}
+
+impl<T: RangeValue> FromIterator<Range<T>> for RangeSet<T> {
+    fn from_iter<I: IntoIterator<Item = Range<T>>>(ranges: I) -> Self {
+        let mut set = Self::new();
+        for range in ranges {
+            set.insert(range);
+        }
+        set
+    }
+}
+
+impl<T: RangeValue> FromIterator<RangeInclusive<T>> for RangeSet<T> {
+    fn from_iter<I: IntoIterator<Item = RangeInclusive<T>>>(ranges: I) -> Self {
+        let mut set = Self::new();
+        for range in ranges {
+            set.insert_inclusive(range);
+        }
+        set
+    }
+}
+
+/// Moves the integers in a source range to a destination range of the same length
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ShiftRule<T> {
+    pub source: Range<T>,
+    pub dest_start: T,
+}
+
+impl<T: RangeValue> ShiftRule<T> {
+    fn shift(&self, range: &Range<T>) -> Range<T> {
+        // Add before subtracting, in case the destination is below the source for unsigned types
+        let start = range.start + self.dest_start - self.source.start;
+        start..start + (range.end - range.start)
+    }
+}
+
+/// A function which shifts integers by the first rule which contains them,
+/// or leaves them unchanged if no rule does
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PiecewiseMap<T> {
+    rules: Vec<ShiftRule<T>>,
+}
+
+impl<T: RangeValue> PiecewiseMap<T> {
+    pub fn new() -> Self {
+        Self { rules: Vec::new() }
+    }
+
+    pub fn rules(&self) -> &[ShiftRule<T>] {
+        &self.rules
+    }
+
+    pub fn add_rule(&mut self, source: Range<T>, dest_start: T) {
+        self.rules.push(ShiftRule { source, dest_start });
+    }
+
+    pub fn map(&self, value: T) -> T {
+        self.rules
+            .iter()
+            .find(|rule| rule.source.contains(&value))
+            .map_or(value, |rule| rule.shift(&(value..value + T::from(1))).start)
+    }
+
+    /// Map every integer in the set, splitting ranges where they cross the edges of the rules
+    pub fn map_set(&self, set: &RangeSet<T>) -> RangeSet<T> {
+        let mut unmapped = set.clone();
+        let mut mapped = RangeSet::new();
+        for rule in &self.rules {
+            let source = RangeSet::from(rule.source.clone());
+            for range in &unmapped.intersection(&source).ranges {
+                mapped.insert(rule.shift(range));
+            }
+            unmapped = unmapped.difference(&source);
+        }
+        mapped.union(&unmapped)
+    }
+}
+
+impl<T: RangeValue> Default for PiecewiseMap<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T: RangeValue> FromIterator<(Range<T>, T)> for PiecewiseMap<T> {
+    fn from_iter<I: IntoIterator<Item = (Range<T>, T)>>(rules: I) -> Self {
+        let mut map = Self::new();
+        for (source, dest_start) in rules {
+            map.add_rule(source, dest_start);
+        }
+        map
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_insert_merges_overlapping_and_touching_ranges() {
+        let mut set: RangeSet<u32> = [10..15, 20..25, 30..35].into_iter().collect();
+        set.insert(15..20);
+        assert_eq!(set.ranges(), [10..25, 30..35]);
+        set.insert(0..5);
+        set.insert(22..31);
+        assert_eq!(set.ranges(), [0..5, 10..35]);
+        set.insert_inclusive(5..=9);
+        assert_eq!(set, RangeSet::from(0..35));
+        assert_eq!(set.count(), 35);
+    }
+
+    #[test]
+    fn test_contains_and_bounds() {
+        let set: RangeSet<i64> = [-5..=-1, 3..=7].into_iter().collect();
+        assert!(set.contains(-5));
+        assert!(!set.contains(0));
+        assert!(set.contains(7));
+        assert!(!set.contains(8));
+        assert_eq!((set.min(), set.max()), (Some(-5), Some(7)));
+    }
+
+    #[test]
+    fn test_set_operations() {
+        let a: RangeSet<u32> = [0..10, 20..30].into_iter().collect();
+        let b = RangeSet::from(5..25);
+        assert_eq!(a.union(&b), RangeSet::from(0..30));
+        assert_eq!(a.intersection(&b).ranges(), [5..10, 20..25]);
+        assert_eq!(a.difference(&b).ranges(), [0..5, 25..30]);
+        assert_eq!(b.difference(&a), RangeSet::from(10..20));
+        assert!(a.difference(&a).is_empty());
+    }
+
+    #[test]
+    fn test_piecewise_map() {
+        // The seed-to-soil map from 2023 day 5
+        let map: PiecewiseMap<u64> = [(98..100, 50), (50..98, 52)].into_iter().collect();
+        assert_eq!(map.map(99), 51);
+        assert_eq!(map.map(53), 55);
+        assert_eq!(map.map(10), 10);
+
+        let seeds: RangeSet<u64> = [79..93, 95..101].into_iter().collect();
+        let soil = map.map_set(&seeds);
+        assert_eq!(soil.ranges(), [50..52, 81..95, 97..101]);
+        assert_eq!(soil.count(), seeds.count());
+    }
+}
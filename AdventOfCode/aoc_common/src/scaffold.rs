@@ -0,0 +1,247 @@
+use std::path::{Path, PathBuf};
+use thiserror::Error;
+
+#[derive(Debug, Error)]
+pub enum ScaffoldError {
+    #[error(transparent)]
+    Io(#[from] std::io::Error),
+    #[error("{} already exists", path.display())]
+    AlreadyExists { path: PathBuf },
+    #[error("no package name was found in {}", path.display())]
+    MissingPackageName { path: PathBuf },
+    #[error("{} has no runner function which a new solution can call", path.display())]
+    UnknownRunner { path: PathBuf },
+}
+
+/// The shared runner in a year's crate, which a new solution's `main` calls
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Runner {
+    /// `read_and_solve_and_time_more_runs`, as used from 2022 to 2024
+    ReadAndTime,
+    /// `load_and_solve_and_benchmark`, as used from 2025
+    LoadAndBenchmark,
+}
+
+impl Runner {
+    pub fn function_name(&self) -> &'static str {
+        match self {
+            Runner::ReadAndTime => "read_and_solve_and_time_more_runs",
+            Runner::LoadAndBenchmark => "load_and_solve_and_benchmark",
+        }
+    }
+
+    fn detect(lib_source: &str) -> Option<Runner> {
+        [Runner::LoadAndBenchmark, Runner::ReadAndTime]
+            .into_iter()
+            .find(|runner| lib_source.contains(&format!("pub fn {}", runner.function_name())))
+    }
+}
+
+/// What to generate for a new puzzle part
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Scaffold {
+    pub crate_name: String,
+    pub runner: Runner,
+    pub day: u8,
+    pub part: u8,
+    /// The example's answer, which is treated as a number if it parses as one
+    pub example_answer: String,
+}
+
+impl Scaffold {
+    /// Configure a scaffold for the year's crate in the given folder
+    pub fn for_crate(
+        crate_dir: &Path,
+        day: u8,
+        part: u8,
+        example_answer: &str,
+    ) -> Result<Self, ScaffoldError> {
+        let manifest_path = crate_dir.join("Cargo.toml");
+        let manifest = std::fs::read_to_string(&manifest_path)?;
+        let crate_name = manifest
+            .lines()
+            .find_map(|line| {
+                let (key, value) = line.split_once('=')?;
+                (key.trim() == "name").then(|| value.trim().trim_matches('"').replace('-', "_"))
+            })
+            .ok_or(ScaffoldError::MissingPackageName {
+                path: manifest_path,
+            })?;
+
+        let lib_path = crate_dir.join("src").join("lib.rs");
+        let lib_source = std::fs::read_to_string(&lib_path)?;
+        let runner =
+            Runner::detect(&lib_source).ok_or(ScaffoldError::UnknownRunner { path: lib_path })?;
+
+        Ok(Scaffold {
+            crate_name,
+            runner,
+            day,
+            part,
+            example_answer: example_answer.trim().to_string(),
+        })
+    }
+
+    pub fn bin_file_name(&self) -> String {
+        format!("day{}_part{}.rs", self.day, self.part)
+    }
+
+    /// The return type of `solve`, and the answer as a Rust expression of that type
+    fn answer_type_and_literal(&self) -> (&'static str, String) {
+        let answer = &self.example_answer;
+        if answer.parse::<usize>().is_ok() {
+            ("usize", answer.clone())
+        } else if answer.parse::<i64>().is_ok() {
+            ("i64", answer.clone())
+        } else {
+            ("String", format!("{answer:?}"))
+        }
+    }
+
+    /// The source code of the new solution, with a test that `solve` gives the example's answer
+    pub fn render_solution(&self, example_file_name: &str) -> String {
+        let Scaffold {
+            crate_name,
+            runner,
+            day,
+            part,
+            ..
+        } = self;
+        let runner_fn = runner.function_name();
+        let (answer_type, answer) = self.answer_type_and_literal();
+        format!(
+            r#"use {crate_name}::{runner_fn};
+
+const INPUT_FILE_PATH: &str = "data/day{day}_input.txt";
+
+fn main() {{
+    {runner_fn}(INPUT_FILE_PATH, "Day {day} part {part}", solve, 1000);
+}}
+
+fn solve(contents: &str) -> {answer_type} {{
+    todo!("Solve day {day} part {part} for {{}} lines of input", contents.lines().count())
+}}
+
+#[cfg(test)]
+mod tests {{
+    use super::*;
+
+    const EXAMPLE_FILE_PATH: &str = "data/{example_file_name}";
+
+    #[test]
+    fn test_example() {{
+        let contents = std::fs::read_to_string(EXAMPLE_FILE_PATH).unwrap();
+        assert_eq!(solve(&contents), {answer});
+    }}
+}}
+"#
+        )
+    }
+
+    /// Write the solution to `src/bin` and the example to `data`, returning the new files' paths.
+    ///
+    /// The example goes in `dayN_example.txt`, unless that already holds a different example
+    /// (e.g. from part 1), in which case it goes in `dayN_partM_example.txt`.
+    pub fn write(&self, crate_dir: &Path, example: &str) -> Result<Vec<PathBuf>, ScaffoldError> {
+        let bin_path = crate_dir.join("src").join("bin").join(self.bin_file_name());
+        if bin_path.exists() {
+            return Err(ScaffoldError::AlreadyExists { path: bin_path });
+        }
+
+        let data_dir = crate_dir.join("data");
+        let mut example_file_name = format!("day{}_example.txt", self.day);
+        let mut example_path = data_dir.join(&example_file_name);
+        let mut new_paths = Vec::new();
+        if example_path.exists() && std::fs::read_to_string(&example_path)? != example {
+            example_file_name = format!("day{}_part{}_example.txt", self.day, self.part);
+            example_path = data_dir.join(&example_file_name);
+            if example_path.exists() {
+                return Err(ScaffoldError::AlreadyExists { path: example_path });
+            }
+        }
+        if !example_path.exists() {
+            std::fs::create_dir_all(&data_dir)?;
+            std::fs::write(&example_path, example)?;
+            new_paths.push(example_path);
+        }
+
+        std::fs::write(&bin_path, self.render_solution(&example_file_name))?;
+        new_paths.push(bin_path);
+        Ok(new_paths)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn scaffold(example_answer: &str) -> Scaffold {
+        Scaffold {
+            crate_name: "aoc2025_rs".to_string(),
+            runner: Runner::LoadAndBenchmark,
+            day: 7,
+            part: 2,
+            example_answer: example_answer.to_string(),
+        }
+    }
+
+    #[test]
+    fn test_render_solution_with_numeric_answer() {
+        let source = scaffold("40").render_solution("day7_example.txt");
+        assert!(source.starts_with("use aoc2025_rs::load_and_solve_and_benchmark;\n"));
+        assert!(source.contains(r#"const INPUT_FILE_PATH: &str = "data/day7_input.txt";"#));
+        assert!(source.contains(r#"(INPUT_FILE_PATH, "Day 7 part 2", solve, 1000);"#));
+        assert!(source.contains("fn solve(contents: &str) -> usize {"));
+        assert!(source.contains(r#"const EXAMPLE_FILE_PATH: &str = "data/day7_example.txt";"#));
+        assert!(source.contains("assert_eq!(solve(&contents), 40);"));
+    }
+
+    #[test]
+    fn test_render_solution_with_text_answer() {
+        let source = scaffold("CMZ").render_solution("day7_example.txt");
+        assert!(source.contains("fn solve(contents: &str) -> String {"));
+        assert!(source.contains(r#"assert_eq!(solve(&contents), "CMZ");"#));
+        let source = scaffold("-3").render_solution("day7_example.txt");
+        assert!(source.contains("fn solve(contents: &str) -> i64 {"));
+    }
+
+    #[test]
+    fn test_write_to_crate() {
+        let crate_dir =
+            std::env::temp_dir().join(format!("aoc_common_scaffold_{}", std::process::id()));
+        let _ = std::fs::remove_dir_all(&crate_dir);
+        std::fs::create_dir_all(crate_dir.join("src").join("bin")).unwrap();
+        std::fs::write(
+            crate_dir.join("Cargo.toml"),
+            "[package]\nname = \"aoc2024_rs\"\nedition = \"2021\"\n",
+        )
+        .unwrap();
+        std::fs::write(
+            crate_dir.join("src").join("lib.rs"),
+            "pub fn read_and_solve_and_time_more_runs() {}\n",
+        )
+        .unwrap();
+
+        let part_1 = Scaffold::for_crate(&crate_dir, 5, 1, "143\n").unwrap();
+        assert_eq!(part_1.crate_name, "aoc2024_rs");
+        assert_eq!(part_1.runner, Runner::ReadAndTime);
+        assert_eq!(part_1.write(&crate_dir, "1|2\n").unwrap().len(), 2);
+        assert!(matches!(
+            part_1.write(&crate_dir, "1|2\n"),
+            Err(ScaffoldError::AlreadyExists { .. })
+        ));
+
+        // Part 2 with the same example reuses the example file
+        let part_2 = Scaffold::for_crate(&crate_dir, 5, 2, "123").unwrap();
+        assert_eq!(part_2.write(&crate_dir, "1|2\n").unwrap().len(), 1);
+
+        // Part 3 isn't a thing, but it tests that a different example gets its own file
+        let part_3 = Scaffold::for_crate(&crate_dir, 5, 3, "0").unwrap();
+        let new_paths = part_3.write(&crate_dir, "3|4\n").unwrap();
+        assert!(new_paths[0].ends_with("data/day5_part3_example.txt"));
+        let source = std::fs::read_to_string(&new_paths[1]).unwrap();
+        assert!(source.contains("data/day5_part3_example.txt"));
+
+        std::fs::remove_dir_all(crate_dir).unwrap();
+    }
+}
@@ -0,0 +1,607 @@
+use std::cmp::Ordering;
+use std::collections::hash_map::Entry;
+use std::collections::{BinaryHeap, HashMap, HashSet, VecDeque};
+use std::hash::Hash;
+use std::ops::Add;
+
+/// A state space to search, such as positions in a maze, or positions and directions.
+///
+/// The cost type's `Default` value must be zero, and no move may have a negative cost.
+/// Moves which cost zero are allowed, even if they lead back to an earlier state.
+pub trait SearchProblem {
+    type State: Clone + Eq + Hash;
+    type Cost: Copy + Ord + Default + Add<Output = Self::Cost>;
+
+    fn start_states(&self) -> impl IntoIterator<Item = Self::State>;
+
+    /// The states which can be reached in one move from this state, with the cost of each move
+    fn successors(
+        &self,
+        state: &Self::State,
+    ) -> impl IntoIterator<Item = (Self::State, Self::Cost)>;
+
+    fn is_goal(&self, state: &Self::State) -> bool;
+
+    /// A lower bound on the cost of reaching a goal from this state.
+    ///
+    /// For [`astar`] to find the optimal path, this must never overestimate.
+    /// If it is not also consistent (i.e. it may decrease by more than the cost of a move),
+    /// states are expanded again when a cheaper path to them is found, which costs time.
+    /// For [`all_optimal_paths`] it must be consistent.
+    /// The default of zero turns A* into Dijkstra's algorithm.
+    fn heuristic(&self, _state: &Self::State) -> Self::Cost {
+        Self::Cost::default()
+    }
+}
+
+/// Find a path with the fewest moves (ignoring their costs), from a start state to a goal state
+pub fn bfs<P: SearchProblem>(problem: &P) -> Option<Vec<P::State>> {
+    let mut predecessors: HashMap<P::State, Option<P::State>> = HashMap::new();
+    let mut frontier = VecDeque::new();
+    for start in problem.start_states() {
+        if problem.is_goal(&start) {
+            return Some(vec![start]);
+        }
+        if let Entry::Vacant(entry) = predecessors.entry(start.clone()) {
+            entry.insert(None);
+            frontier.push_back(start);
+        }
+    }
+    while let Some(state) = frontier.pop_front() {
+        for (next_state, _) in problem.successors(&state) {
+            if let Entry::Vacant(entry) = predecessors.entry(next_state.clone()) {
+                entry.insert(Some(state.clone()));
+                if problem.is_goal(&next_state) {
+                    return Some(unwind_path(&predecessors, next_state));
+                }
+                frontier.push_back(next_state);
+            }
+        }
+    }
+    None
+}
+
+/// Find a minimum cost path from a start state to a goal state, and its cost
+pub fn dijkstra<P: SearchProblem>(problem: &P) -> Option<(Vec<P::State>, P::Cost)> {
+    best_first_search(problem, false)
+}
+
+/// Find a minimum cost path from a start state to a goal state, and its cost,
+/// using the problem's heuristic to guide the search
+pub fn astar<P: SearchProblem>(problem: &P) -> Option<(Vec<P::State>, P::Cost)> {
+    best_first_search(problem, true)
+}
+
+fn unwind_path<S: Clone + Eq + Hash>(predecessors: &HashMap<S, Option<S>>, goal: S) -> Vec<S> {
+    let mut path = vec![goal];
+    while let Some(Some(prev_state)) = predecessors.get(path.last().unwrap()) {
+        path.push(prev_state.clone());
+    }
+    path.reverse();
+    path
+}
+
+#[derive(Debug, PartialEq, Eq)]
+struct SearchNode<C> {
+    /// f = g + h is the estimated total cost of reaching a goal via this node
+    f: C,
+
+    /// g is the cost incurred thus far to reach this node's state
+    g: C,
+
+    state_index: usize,
+}
+
+impl<C: Ord> Ord for SearchNode<C> {
+    fn cmp(&self, other: &Self) -> Ordering {
+        // The nodes go in a max-heap, so smaller values of f should be treated as greater.
+        // When two search nodes are equal on f, choose the one with the higher value of g,
+        // to bias the search towards reaching a goal, rather than advancing all alternatives.
+        other
+            .f
+            .cmp(&self.f)
+            .then_with(|| self.g.cmp(&other.g))
+            .then_with(|| other.state_index.cmp(&self.state_index))
+    }
+}
+
+impl<C: Ord> PartialOrd for SearchNode<C> {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+/// The best cost found to reach each state, and the states it can be reached from at that cost
+struct StateTable<S, C> {
+    states: Vec<S>,
+    indices: HashMap<S, usize>,
+    min_costs: Vec<C>,
+    predecessors: Vec<Vec<usize>>,
+}
+
+impl<S: Clone + Eq + Hash, C: Copy + Ord> StateTable<S, C> {
+    fn new() -> Self {
+        Self {
+            states: Vec::new(),
+            indices: HashMap::new(),
+            min_costs: Vec::new(),
+            predecessors: Vec::new(),
+        }
+    }
+
+    /// Record a way of reaching the state. Returns the state's index if this is a new best cost,
+    /// or else whether it equals the best cost.
+    ///
+    /// A way of reaching a start state (which has no predecessors) at equal cost is not recorded,
+    /// and neither is one which would close a cycle of zero cost moves, so the predecessors form a DAG.
+    fn relax(&mut self, state: S, cost: C, prev_index: Option<usize>) -> Relaxation {
+        match self.indices.entry(state) {
+            Entry::Vacant(entry) => {
+                let index = self.states.len();
+                self.states.push(entry.key().clone());
+                entry.insert(index);
+                self.min_costs.push(cost);
+                self.predecessors.push(prev_index.into_iter().collect());
+                Relaxation::Improved(index)
+            }
+            Entry::Occupied(entry) => {
+                let index = *entry.get();
+                match cost.cmp(&self.min_costs[index]) {
+                    Ordering::Less => {
+                        self.min_costs[index] = cost;
+                        self.predecessors[index] = prev_index.into_iter().collect();
+                        Relaxation::Improved(index)
+                    }
+                    Ordering::Equal => {
+                        if let Some(prev_index) = prev_index {
+                            let is_start = self.predecessors[index].is_empty();
+                            if !is_start && !self.is_on_best_path_to(index, prev_index) {
+                                self.predecessors[index].push(prev_index);
+                            }
+                        }
+                        Relaxation::Equalled
+                    }
+                    Ordering::Greater => Relaxation::Worse,
+                }
+            }
+        }
+    }
+
+    /// Whether the state at `ancestor` is the state at `index`, or one of its chain of predecessors.
+    /// Costs never decrease along a path, so only predecessors at the ancestor's cost are followed.
+    fn is_on_best_path_to(&self, ancestor: usize, index: usize) -> bool {
+        let cost = self.min_costs[ancestor];
+        let mut is_visited = HashSet::new();
+        let mut to_visit = vec![index];
+        while let Some(index) = to_visit.pop() {
+            if index == ancestor {
+                return true;
+            }
+            if is_visited.insert(index) {
+                to_visit.extend(
+                    self.predecessors[index]
+                        .iter()
+                        .filter(|&&prev_index| self.min_costs[prev_index] == cost),
+                );
+            }
+        }
+        false
+    }
+}
+
+enum Relaxation {
+    Improved(usize),
+    Equalled,
+    Worse,
+}
+
+fn best_first_search<P: SearchProblem>(
+    problem: &P,
+    use_heuristic: bool,
+) -> Option<(Vec<P::State>, P::Cost)> {
+    let (table, goal_indices, cost) = explore(problem, use_heuristic, false)?;
+    let mut path_indices = vec![goal_indices[0]];
+    while let Some(&prev_index) = table.predecessors[*path_indices.last().unwrap()].first() {
+        path_indices.push(prev_index);
+    }
+    let path = path_indices
+        .into_iter()
+        .rev()
+        .map(|index| table.states[index].clone())
+        .collect();
+    Some((path, cost))
+}
+
+type Exploration<P> = (
+    StateTable<<P as SearchProblem>::State, <P as SearchProblem>::Cost>,
+    Vec<usize>,
+    <P as SearchProblem>::Cost,
+);
+
+/// Run A* (or Dijkstra's algorithm if `use_heuristic` is false), returning the state table,
+/// the indices of the goal states reached at minimum cost, and that minimum cost.
+/// If `find_all` is set, the search continues until every minimum cost path has been found.
+fn explore<P: SearchProblem>(
+    problem: &P,
+    use_heuristic: bool,
+    find_all: bool,
+) -> Option<Exploration<P>> {
+    let heuristic = |state: &P::State| {
+        if use_heuristic {
+            problem.heuristic(state)
+        } else {
+            P::Cost::default()
+        }
+    };
+
+    let mut table: StateTable<P::State, P::Cost> = StateTable::new();
+    let mut frontier = BinaryHeap::new();
+    for start in problem.start_states() {
+        let f = heuristic(&start);
+        if let Relaxation::Improved(state_index) = table.relax(start, P::Cost::default(), None) {
+            frontier.push(SearchNode {
+                f,
+                g: P::Cost::default(),
+                state_index,
+            });
+        }
+    }
+
+    let mut is_expanded: Vec<bool> = Vec::new();
+    let mut goal_indices = Vec::new();
+    let mut min_goal_cost: Option<P::Cost> = None;
+
+    while let Some(SearchNode { f, g, state_index }) = frontier.pop() {
+        if min_goal_cost.is_some_and(|min_cost| f > min_cost) {
+            break;
+        }
+        if g > table.min_costs[state_index] {
+            // A better path to this state was found after this node was added
+            continue;
+        }
+        if is_expanded.len() <= state_index {
+            is_expanded.resize(table.states.len(), false);
+        }
+        if is_expanded[state_index] {
+            continue;
+        }
+        is_expanded[state_index] = true;
+
+        let state = table.states[state_index].clone();
+        if problem.is_goal(&state) {
+            min_goal_cost = Some(g);
+            goal_indices.push(state_index);
+            if !find_all {
+                break;
+            }
+            continue;
+        }
+
+        for (next_state, move_cost) in problem.successors(&state) {
+            let next_g = g + move_cost;
+            let next_f = next_g + heuristic(&next_state);
+            if let Relaxation::Improved(next_index) =
+                table.relax(next_state, next_g, Some(state_index))
+            {
+                // Reopen the state if it was expanded at a higher cost,
+                // which can happen if the heuristic is admissible but not consistent
+                if let Some(was_expanded) = is_expanded.get_mut(next_index) {
+                    *was_expanded = false;
+                }
+                frontier.push(SearchNode {
+                    f: next_f,
+                    g: next_g,
+                    state_index: next_index,
+                });
+            }
+        }
+    }
+
+    min_goal_cost.map(|cost| (table, goal_indices, cost))
+}
+
+/// Every minimum cost path from a start state to a goal state, stored as a DAG of predecessors
+pub struct OptimalPaths<S, C> {
+    table: StateTable<S, C>,
+    goal_indices: Vec<usize>,
+    cost: C,
+}
+
+/// Find all the minimum cost paths, using A* with the problem's heuristic,
+/// which must be consistent (or zero, for Dijkstra's algorithm)
+pub fn all_optimal_paths<P: SearchProblem>(problem: &P) -> Option<OptimalPaths<P::State, P::Cost>> {
+    let (table, goal_indices, cost) = explore(problem, true, true)?;
+    Some(OptimalPaths {
+        table,
+        goal_indices,
+        cost,
+    })
+}
+
+impl<S: Clone + Eq + Hash, C: Copy> OptimalPaths<S, C> {
+    pub fn cost(&self) -> C {
+        self.cost
+    }
+
+    pub fn goal_states(&self) -> impl Iterator<Item = &S> {
+        self.goal_indices
+            .iter()
+            .map(|&index| &self.table.states[index])
+    }
+
+    /// The states which lie on at least one minimum cost path, in no particular order
+    pub fn states_on_any_path(&self) -> impl Iterator<Item = &S> {
+        let mut is_on_path = vec![false; self.table.states.len()];
+        let mut to_visit = self.goal_indices.clone();
+        while let Some(index) = to_visit.pop() {
+            if !is_on_path[index] {
+                is_on_path[index] = true;
+                to_visit.extend(&self.table.predecessors[index]);
+            }
+        }
+        self.table
+            .states
+            .iter()
+            .zip(is_on_path)
+            .filter_map(|(state, is_on_path)| is_on_path.then_some(state))
+    }
+
+    /// The states which can come immediately before this state on a minimum cost path
+    pub fn predecessors(&self, state: &S) -> impl Iterator<Item = &S> {
+        self.table
+            .indices
+            .get(state)
+            .map_or(&[][..], |&index| &self.table.predecessors[index])
+            .iter()
+            .map(|&index| &self.table.states[index])
+    }
+
+    /// The number of distinct minimum cost paths
+    pub fn count_paths(&self) -> u64 {
+        let mut path_counts: HashMap<usize, u64> = HashMap::new();
+        self.goal_indices
+            .iter()
+            .map(|&index| self.count_paths_to(index, &mut path_counts))
+            .sum()
+    }
+
+    fn count_paths_to(&self, index: usize, path_counts: &mut HashMap<usize, u64>) -> u64 {
+        if let Some(&count) = path_counts.get(&index) {
+            return count;
+        }
+        let predecessors = &self.table.predecessors[index];
+        let count = if predecessors.is_empty() {
+            1
+        } else {
+            predecessors
+                .iter()
+                .map(|&prev_index| self.count_paths_to(prev_index, path_counts))
+                .sum()
+        };
+        path_counts.insert(index, count);
+        count
+    }
+
+    /// The distinct values of some property (such as a position) over every state on any path
+    pub fn distinct_on_any_path<K, F>(&self, key: F) -> HashSet<K>
+    where
+        K: Eq + Hash,
+        F: FnMut(&S) -> K,
+    {
+        self.states_on_any_path().map(key).collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::grid::{Direction, Grid, Pos};
+
+    /// Moving through a maze, where every step costs 1
+    struct Maze {
+        grid: Grid<char>,
+        start: Pos,
+        end: Pos,
+    }
+
+    impl Maze {
+        fn parse(text: &str) -> Self {
+            let grid = Grid::parse_chars(text);
+            let start = grid.find_char('S').unwrap();
+            let end = grid.find_char('E').unwrap();
+            Self { grid, start, end }
+        }
+    }
+
+    impl SearchProblem for Maze {
+        type State = Pos;
+        type Cost = usize;
+
+        fn start_states(&self) -> impl IntoIterator<Item = Pos> {
+            [self.start]
+        }
+
+        fn successors(&self, &pos: &Pos) -> impl IntoIterator<Item = (Pos, usize)> {
+            self.grid
+                .neighbours_4(pos)
+                .filter(|&next_pos| self.grid[next_pos] != '#')
+                .map(|next_pos| (next_pos, 1))
+        }
+
+        fn is_goal(&self, pos: &Pos) -> bool {
+            *pos == self.end
+        }
+
+        fn heuristic(&self, pos: &Pos) -> usize {
+            pos.manhattan_distance(self.end)
+        }
+    }
+
+    const MAZE: &str = "
+S..#
+.#.#
+...E";
+
+    #[test]
+    fn test_bfs() {
+        let path = bfs(&Maze::parse(MAZE)).unwrap();
+        assert_eq!(path.len(), 6);
+        assert_eq!(path[0], Pos::new(0, 0));
+        assert_eq!(path[5], Pos::new(2, 3));
+    }
+
+    #[test]
+    fn test_dijkstra_and_astar_agree() {
+        let maze = Maze::parse(MAZE);
+        let (dijkstra_path, dijkstra_cost) = dijkstra(&maze).unwrap();
+        let (astar_path, astar_cost) = astar(&maze).unwrap();
+        assert_eq!(dijkstra_cost, 5);
+        assert_eq!(astar_cost, 5);
+        assert_eq!(dijkstra_path.len(), 6);
+        assert_eq!(astar_path.len(), 6);
+    }
+
+    /// A graph of states 0 to 4, with an admissible heuristic which is not consistent,
+    /// since it drops by 3 on the move from state 2 to state 3, which only costs 1
+    struct InconsistentGraph;
+
+    impl SearchProblem for InconsistentGraph {
+        type State = u8;
+        type Cost = u32;
+
+        fn start_states(&self) -> impl IntoIterator<Item = u8> {
+            [0]
+        }
+
+        fn successors(&self, &state: &u8) -> impl IntoIterator<Item = (u8, u32)> {
+            let moves: &[(u8, u32)] = match state {
+                0 => &[(1, 1), (2, 2)],
+                1 => &[(3, 3)],
+                2 => &[(3, 1)],
+                3 => &[(4, 3)],
+                _ => &[],
+            };
+            moves.to_vec()
+        }
+
+        fn is_goal(&self, &state: &u8) -> bool {
+            state == 4
+        }
+
+        fn heuristic(&self, &state: &u8) -> u32 {
+            if state == 2 {
+                3
+            } else {
+                0
+            }
+        }
+    }
+
+    #[test]
+    fn test_astar_reopens_states_with_inconsistent_heuristic() {
+        let (path, cost) = astar(&InconsistentGraph).unwrap();
+        assert_eq!(cost, 6);
+        assert_eq!(path, [0, 2, 3, 4]);
+    }
+
+    /// States 0 to 3, where moves between states 0, 1 and 3 cost nothing, and lead back and forth
+    struct ZeroCostCycles;
+
+    impl SearchProblem for ZeroCostCycles {
+        type State = u8;
+        type Cost = u32;
+
+        fn start_states(&self) -> impl IntoIterator<Item = u8> {
+            [0]
+        }
+
+        fn successors(&self, &state: &u8) -> impl IntoIterator<Item = (u8, u32)> {
+            let moves: &[(u8, u32)] = match state {
+                0 => &[(1, 0)],
+                1 => &[(0, 0), (3, 0), (2, 1)],
+                3 => &[(1, 0), (2, 1)],
+                _ => &[],
+            };
+            moves.to_vec()
+        }
+
+        fn is_goal(&self, &state: &u8) -> bool {
+            state == 2
+        }
+    }
+
+    #[test]
+    fn test_zero_cost_cycles() {
+        let (path, cost) = dijkstra(&ZeroCostCycles).unwrap();
+        assert_eq!(cost, 1);
+        assert_eq!(path, [0, 1, 2]);
+        let paths = all_optimal_paths(&ZeroCostCycles).unwrap();
+        assert_eq!(paths.count_paths(), 2);
+        assert_eq!(paths.states_on_any_path().count(), 4);
+        assert_eq!(paths.predecessors(&0).count(), 0);
+        assert_eq!(paths.predecessors(&1).collect::<Vec<_>>(), [&0]);
+    }
+
+    #[test]
+    fn test_no_path() {
+        let maze = Maze::parse("S#E");
+        assert!(bfs(&maze).is_none());
+        assert!(astar(&maze).is_none());
+        assert!(all_optimal_paths(&maze).is_none());
+    }
+
+    #[test]
+    fn test_all_optimal_paths() {
+        let maze = Maze::parse(MAZE);
+        let paths = all_optimal_paths(&maze).unwrap();
+        assert_eq!(paths.cost(), 5);
+        assert_eq!(paths.count_paths(), 2);
+        assert_eq!(paths.states_on_any_path().count(), 9);
+        assert_eq!(paths.goal_states().collect::<Vec<_>>(), [&Pos::new(2, 3)]);
+    }
+
+    /// Where turning is expensive, the cheapest path differs from the shortest
+    struct Reindeer(Maze);
+
+    impl SearchProblem for Reindeer {
+        type State = (Pos, Direction);
+        type Cost = u32;
+
+        fn start_states(&self) -> impl IntoIterator<Item = Self::State> {
+            [(self.0.start, Direction::Right)]
+        }
+
+        fn successors(
+            &self,
+            &(pos, dir): &Self::State,
+        ) -> impl IntoIterator<Item = (Self::State, u32)> {
+            let forwards = self
+                .0
+                .grid
+                .step(pos, dir)
+                .filter(|&next_pos| self.0.grid[next_pos] != '#')
+                .map(|next_pos| ((next_pos, dir), 1));
+            forwards.into_iter().chain([
+                ((pos, dir.turn_left()), 100),
+                ((pos, dir.turn_right()), 100),
+            ])
+        }
+
+        fn is_goal(&self, (pos, _): &Self::State) -> bool {
+            *pos == self.0.end
+        }
+    }
+
+    #[test]
+    fn test_expensive_turns() {
+        let maze = Maze::parse(
+            "
+S.....
+.####.
+.####.
+.....E",
+        );
+        let (_, cost) = dijkstra(&Reindeer(maze)).unwrap();
+        assert_eq!(cost, 8 + 100);
+    }
+}
@@ -0,0 +1,20 @@
+use crate::Notation;
+use thiserror::Error;
+
+#[derive(Error, Debug, PartialEq, Eq, Clone)]
+pub enum RomanError {
+    #[error("Zero cannot be converted to a Roman numeral")]
+    Zero,
+    #[error("Roman numerals above {max} are not supported in {notation} notation (got {value})")]
+    OutOfRange {
+        value: u64,
+        max: u32,
+        notation: Notation,
+    },
+    #[error("An empty string is not a Roman numeral")]
+    Empty,
+    #[error("Unrecognized symbol {symbol:?} at position {position}")]
+    UnrecognizedSymbol { symbol: char, position: usize },
+    #[error("Invalid Roman number format")]
+    InvalidFormat,
+}
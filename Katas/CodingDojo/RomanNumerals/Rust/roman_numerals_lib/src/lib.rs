@@ -0,0 +1,406 @@
+mod error;
+mod notation;
+mod numeral;
+mod parse;
+mod scanner;
+
+pub use error::RomanError;
+pub use notation::{Mode, Notation, APOSTROPHUS, VINCULUM};
+pub use numeral::RomanNumeral;
+pub use scanner::{
+    find_roman_numerals, replace_arabic_with_roman, replace_roman_with_arabic, RomanMatch,
+    ScanRules,
+};
+
+use notation::{append_decimal_places, append_with_vinculum};
+use parse::{evaluate, normalize_leniently, tokenize};
+
+/// The smallest number which is written with a vinculum in [`Notation::Vinculum`]
+const MIN_VINCULUM_VALUE: u32 = 4000;
+
+/// Convert a number to a Roman numeral in the given notation
+pub fn to_roman(num: u32, notation: Notation) -> Result<String, RomanError> {
+    if num == 0 {
+        return Err(RomanError::Zero);
+    }
+    check_range(num as u64, notation)?;
+
+    let mut roman = String::new();
+    if notation == Notation::Vinculum && num >= MIN_VINCULUM_VALUE {
+        append_with_vinculum(&mut roman, num / 1000);
+        append_decimal_places(&mut roman, num % 1000, notation);
+    } else {
+        append_decimal_places(&mut roman, num, notation);
+    }
+    Ok(roman)
+}
+
+/// Convert a Roman numeral in the given notation to a number.
+///
+/// In [`Mode::Strict`] the numeral must be exactly what [`to_roman`] would produce.
+/// In [`Mode::Lenient`] any sequence of symbols is accepted, and runs of equal symbols
+/// before a larger symbol are subtracted, so that IIII = 4 and IIX = 8.
+/// Either way the result must be within the range of the notation.
+pub fn from_roman(roman: &str, notation: Notation, mode: Mode) -> Result<u32, RomanError> {
+    if roman.is_empty() {
+        return Err(RomanError::Empty);
+    }
+
+    let value = match mode {
+        Mode::Strict => evaluate(&tokenize(roman)?)?,
+        Mode::Lenient => evaluate(&tokenize(&normalize_leniently(roman))?)?,
+    };
+    check_range(value, notation)?;
+
+    let num = value as u32;
+    if mode == Mode::Strict && to_roman(num, notation)? != roman {
+        return Err(RomanError::InvalidFormat);
+    }
+    Ok(num)
+}
+
+/// Convert a number from 1 to 3000 to a standard Roman numeral
+pub fn convert_to_roman(num: u16) -> Result<String, RomanError> {
+    to_roman(num as u32, Notation::Standard)
+}
+
+/// Convert a standard Roman numeral from I to MMM to a number, rejecting non-canonical forms
+pub fn convert_from_roman(roman: &str) -> Result<u16, RomanError> {
+    from_roman(roman, Notation::Standard, Mode::Strict).map(|num| num as u16)
+}
+
+pub fn is_roman(roman: &str) -> bool {
+    convert_from_roman(roman).is_ok()
+}
+
+fn check_range(value: u64, notation: Notation) -> Result<(), RomanError> {
+    let max = notation.max_value();
+    if value > max as u64 {
+        Err(RomanError::OutOfRange {
+            value,
+            max,
+            notation,
+        })
+    } else {
+        Ok(())
+    }
+}
+
+// -----------------------------------------------------------------------------------------------
+// The tests of the standard notation are adapted from the roman_numerals_v3 project
+
+#[cfg(test)]
+mod tests {
+    use super::{convert_from_roman, convert_to_roman, from_roman, is_roman, to_roman};
+    use super::{Mode, Notation, RomanError};
+
+    mod to_roman {
+        use super::{convert_to_roman, RomanError};
+
+        #[test]
+        fn test_below_four() {
+            assert_eq!(convert_to_roman(1).unwrap(), "I");
+            assert_eq!(convert_to_roman(2).unwrap(), "II");
+            assert_eq!(convert_to_roman(3).unwrap(), "III");
+        }
+
+        #[test]
+        fn test_four_to_nine() {
+            assert_eq!(convert_to_roman(4).unwrap(), "IV");
+            assert_eq!(convert_to_roman(5).unwrap(), "V");
+            assert_eq!(convert_to_roman(8).unwrap(), "VIII");
+            assert_eq!(convert_to_roman(9).unwrap(), "IX");
+        }
+
+        #[test]
+        fn test_multiples_of_tens() {
+            assert_eq!(convert_to_roman(10).unwrap(), "X");
+            assert_eq!(convert_to_roman(40).unwrap(), "XL");
+            assert_eq!(convert_to_roman(50).unwrap(), "L");
+            assert_eq!(convert_to_roman(80).unwrap(), "LXXX");
+            assert_eq!(convert_to_roman(90).unwrap(), "XC");
+        }
+
+        #[test]
+        fn test_hundreds_tens_and_units() {
+            assert_eq!(convert_to_roman(449).unwrap(), "CDXLIX");
+            assert_eq!(convert_to_roman(644).unwrap(), "DCXLIV");
+            assert_eq!(convert_to_roman(889).unwrap(), "DCCCLXXXIX");
+            assert_eq!(convert_to_roman(999).unwrap(), "CMXCIX");
+        }
+
+        #[test]
+        fn test_thousands_hundreds_tens_and_units() {
+            assert_eq!(convert_to_roman(1972).unwrap(), "MCMLXXII");
+            assert_eq!(convert_to_roman(2999).unwrap(), "MMCMXCIX");
+            assert_eq!(convert_to_roman(3000).unwrap(), "MMM");
+        }
+
+        #[test]
+        fn test_zero_not_supported() {
+            assert_eq!(convert_to_roman(0), Err(RomanError::Zero));
+        }
+
+        #[test]
+        fn test_above_3000_not_supported() {
+            assert!(matches!(
+                convert_to_roman(3001),
+                Err(RomanError::OutOfRange { max: 3000, .. })
+            ));
+        }
+    }
+
+    #[allow(non_snake_case)]
+    mod from_roman {
+        use super::{convert_from_roman, is_roman, RomanError};
+
+        #[test]
+        fn test_converting_from_valid_numerals() {
+            assert_eq!(convert_from_roman("III"), Ok(3));
+            assert_eq!(convert_from_roman("IV"), Ok(4));
+            assert_eq!(convert_from_roman("IX"), Ok(9));
+            assert_eq!(convert_from_roman("XXIX"), Ok(29));
+            assert_eq!(convert_from_roman("DCXLIV"), Ok(644));
+            assert_eq!(convert_from_roman("MMCMLXXIV"), Ok(2974));
+        }
+
+        #[test]
+        fn test_converting_from_IIII_fails() {
+            assert_eq!(convert_from_roman("IIII"), Err(RomanError::InvalidFormat));
+        }
+
+        #[test]
+        fn test_converting_from_VIIII_fails() {
+            assert_eq!(convert_from_roman("VIIII"), Err(RomanError::InvalidFormat));
+        }
+
+        #[test]
+        fn test_converting_from_empty_string() {
+            assert_eq!(convert_from_roman(""), Err(RomanError::Empty));
+        }
+
+        #[test]
+        fn test_converting_from_MMMI_fails() {
+            assert!(matches!(
+                convert_from_roman("MMMI"),
+                Err(RomanError::OutOfRange { value: 3001, .. })
+            ));
+        }
+
+        #[test]
+        fn test_converting_from_unrecognized_symbol() {
+            assert_eq!(
+                convert_from_roman("XIZ"),
+                Err(RomanError::UnrecognizedSymbol {
+                    symbol: 'Z',
+                    position: 2
+                })
+            );
+        }
+
+        #[test]
+        fn test_is_roman_numeral() {
+            assert!(is_roman("IV"));
+            assert!(is_roman("MMM"));
+            assert!(!is_roman(""));
+            assert!(!is_roman("IIII"));
+            assert!(!is_roman("IXCM"));
+            assert!(!is_roman("IVI"));
+            assert!(!is_roman("VIV"));
+            assert!(!is_roman("CDC"));
+        }
+    }
+
+    #[allow(non_snake_case)]
+    mod lenient {
+        use super::{from_roman, Mode, Notation, RomanError};
+
+        fn from_lenient_roman(roman: &str) -> Result<u32, RomanError> {
+            from_roman(roman, Notation::Standard, Mode::Lenient)
+        }
+
+        #[test]
+        fn test_canonical_numerals_are_accepted() {
+            assert_eq!(from_lenient_roman("MCMLXXII"), Ok(1972));
+        }
+
+        #[test]
+        fn test_additive_IIII_is_four() {
+            assert_eq!(from_lenient_roman("IIII"), Ok(4));
+            assert_eq!(from_lenient_roman("XIIII"), Ok(14));
+        }
+
+        #[test]
+        fn test_double_subtractive_IIX_is_eight() {
+            assert_eq!(from_lenient_roman("IIX"), Ok(8));
+            assert_eq!(from_lenient_roman("XIIX"), Ok(18));
+            assert_eq!(from_lenient_roman("XXC"), Ok(80));
+        }
+
+        #[test]
+        fn test_irregular_subtraction() {
+            assert_eq!(from_lenient_roman("IC"), Ok(99));
+            assert_eq!(from_lenient_roman("MIM"), Ok(1999));
+        }
+
+        #[test]
+        fn test_lower_case_and_final_j() {
+            assert_eq!(from_lenient_roman("xiv"), Ok(14));
+            assert_eq!(from_lenient_roman("viij"), Ok(8));
+        }
+
+        #[test]
+        fn test_unicode_ligatures() {
+            assert_eq!(from_lenient_roman("Ⅻ"), Ok(12));
+            assert_eq!(from_lenient_roman("ⅯⅭⅯⅬⅩⅩⅡ"), Ok(1972));
+            assert_eq!(from_lenient_roman("ⅹⅳ"), Ok(14));
+        }
+
+        #[test]
+        fn test_range_is_still_checked() {
+            assert!(matches!(
+                from_lenient_roman("MMMM"),
+                Err(RomanError::OutOfRange { value: 4000, .. })
+            ));
+        }
+
+        #[test]
+        fn test_non_positive_total_is_rejected() {
+            assert_eq!(from_lenient_roman("IIIIIV"), Err(RomanError::InvalidFormat));
+        }
+    }
+
+    mod extended_notations {
+        use super::{from_roman, to_roman, Mode, Notation, RomanError};
+
+        #[test]
+        fn test_vinculum_below_4000_is_standard() {
+            assert_eq!(to_roman(3999, Notation::Vinculum).unwrap(), "MMMCMXCIX");
+        }
+
+        #[test]
+        fn test_vinculum_thousands() {
+            assert_eq!(
+                to_roman(4000, Notation::Vinculum).unwrap(),
+                "I\u{305}V\u{305}"
+            );
+            assert_eq!(to_roman(5001, Notation::Vinculum).unwrap(), "V\u{305}I");
+            assert_eq!(to_roman(1_000_000, Notation::Vinculum).unwrap(), "M\u{305}");
+        }
+
+        #[test]
+        fn test_vinculum_maximum() {
+            let roman = to_roman(3_999_999, Notation::Vinculum).unwrap();
+            assert_eq!(roman, "M̅M̅M̅C̅M̅X̅C̅I̅X̅CMXCIX");
+            assert_eq!(
+                from_roman(&roman, Notation::Vinculum, Mode::Strict),
+                Ok(3_999_999)
+            );
+            assert!(to_roman(4_000_000, Notation::Vinculum).is_err());
+        }
+
+        #[test]
+        fn test_vinculum_is_not_strict_below_4000() {
+            assert_eq!(
+                from_roman("I\u{305}I\u{305}", Notation::Vinculum, Mode::Strict),
+                Err(RomanError::InvalidFormat)
+            );
+            assert_eq!(
+                from_roman("I\u{305}I\u{305}", Notation::Vinculum, Mode::Lenient),
+                Ok(2000)
+            );
+        }
+
+        #[test]
+        fn test_apostrophus() {
+            assert_eq!(to_roman(500, Notation::Apostrophus).unwrap(), "D");
+            assert_eq!(to_roman(1666, Notation::Apostrophus).unwrap(), "CIↃDCLXVI");
+            assert_eq!(to_roman(4000, Notation::Apostrophus).unwrap(), "CIↃIↃↃ");
+            assert_eq!(to_roman(100_000, Notation::Apostrophus).unwrap(), "CCCIↃↃↃ");
+            assert_eq!(
+                from_roman("CCIↃↃCIↃ", Notation::Apostrophus, Mode::Strict),
+                Ok(11_000)
+            );
+            assert_eq!(
+                from_roman("ci)ci)", Notation::Apostrophus, Mode::Lenient),
+                Ok(2000)
+            );
+        }
+
+        #[test]
+        fn test_unicode() {
+            assert_eq!(to_roman(1972, Notation::Unicode).unwrap(), "ⅯⅭⅯⅬⅩⅩⅠⅠ");
+            assert_eq!(to_roman(15_000, Notation::Unicode).unwrap(), "ↂↁ");
+            assert_eq!(
+                from_roman("ↈↈↇ", Notation::Unicode, Mode::Strict),
+                Ok(250_000)
+            );
+            assert_eq!(
+                from_roman("Ⅻ", Notation::Unicode, Mode::Strict),
+                Err(RomanError::InvalidFormat)
+            );
+        }
+
+        #[test]
+        fn test_notations_are_not_mixed_in_strict_mode() {
+            assert_eq!(
+                from_roman("XII", Notation::Unicode, Mode::Strict),
+                Err(RomanError::InvalidFormat)
+            );
+            assert_eq!(
+                from_roman("CIↃ", Notation::Standard, Mode::Strict),
+                Err(RomanError::InvalidFormat)
+            );
+        }
+    }
+
+    #[test]
+    fn test_converting_all_ints_to_roman_then_from_roman() {
+        for notation in Notation::ALL {
+            let max = notation.max_value().min(20_000);
+            (1..=max).for_each(|i| {
+                let roman = to_roman(i, notation).unwrap();
+                assert_eq!(from_roman(&roman, notation, Mode::Strict), Ok(i));
+                assert_eq!(from_roman(&roman, notation, Mode::Lenient), Ok(i));
+            });
+        }
+    }
+
+    mod proptest_property_based_tests {
+        use super::{convert_from_roman, convert_to_roman, from_roman, is_roman, to_roman};
+        use super::{Mode, Notation};
+        use proptest::prelude::*;
+
+        proptest! {
+            #[test]
+            fn test_convert_to_roman_is_left_inverse_of_convert_from_roman(
+                roman in "(V?I{0,3}|IV|IX|L?X{0,3}|XL|XC|D?C{0,3}|CD|CM|M{0,3}|Z)+"
+                    .prop_filter("ignore invalid Roman numerals", |r| is_roman(r.as_str())))
+            {
+                let reconverted_roman = convert_to_roman(
+                    convert_from_roman(roman.as_str()).unwrap()
+                ).unwrap();
+                assert_eq!(roman, reconverted_roman);
+            }
+
+            #[test]
+            fn test_lenient_mode_accepts_everything_strict_mode_accepts(
+                roman in "(V?I{0,4}|IV|IX|L?X{0,4}|XL|XC|D?C{0,4}|CD|CM|M{0,3})+")
+            {
+                if let Ok(num) = from_roman(&roman, Notation::Standard, Mode::Strict) {
+                    assert_eq!(from_roman(&roman, Notation::Standard, Mode::Lenient), Ok(num));
+                }
+            }
+
+            #[test]
+            fn test_large_numbers_round_trip(num in 1..=3_999_999_u32) {
+                for notation in Notation::ALL {
+                    if num <= notation.max_value() {
+                        let roman = to_roman(num, notation).unwrap();
+                        assert_eq!(from_roman(&roman, notation, Mode::Strict), Ok(num));
+                    }
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,136 @@
+use std::fmt::{Display, Formatter};
+
+/// A combining overline (U+0305). Placed after a letter, it multiplies its value by 1000.
+pub const VINCULUM: char = '\u{305}';
+
+/// The reversed C (U+2183) used to build apostrophus numerals such as CIↃ (1000).
+pub const APOSTROPHUS: char = 'Ↄ';
+
+/// The way in which a number is written as a Roman numeral.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Notation {
+    /// The letters I, V, X, L, C, D and M, up to the kata's limit of 3000.
+    #[default]
+    Standard,
+    /// Numbers from 4000 upwards have their thousands written with an overline (vinculum),
+    /// so that V̅ = 5000 and M̅ = 1,000,000.
+    Vinculum,
+    /// Thousands are built by enclosing I in C's and reversed C's,
+    /// e.g. CIↃ = 1000, IↃↃ = 5000 and CCIↃↃ = 10,000.
+    /// D is used for 500, since writing it as IↃ would make CIↃ ambiguous (400 or 1000).
+    Apostrophus,
+    /// The Unicode Roman numeral code points (U+2160 onwards),
+    /// including the ligatures ↁ = 5000, ↂ = 10,000, ↇ = 50,000 and ↈ = 100,000.
+    Unicode,
+}
+
+impl Notation {
+    pub const ALL: [Notation; 4] = [
+        Notation::Standard,
+        Notation::Vinculum,
+        Notation::Apostrophus,
+        Notation::Unicode,
+    ];
+
+    pub fn max_value(&self) -> u32 {
+        match self {
+            Notation::Standard => 3000,
+            Notation::Vinculum => 3_999_999,
+            Notation::Apostrophus | Notation::Unicode => 399_999,
+        }
+    }
+
+    /// The one, five and ten symbols for each decimal place, starting with the units.
+    /// An empty string means that the digit can't be written in this notation.
+    fn decimal_places(&self) -> &'static [[&'static str; 3]] {
+        match self {
+            Notation::Standard | Notation::Vinculum => &STANDARD_DECIMAL_PLACES,
+            Notation::Apostrophus => &APOSTROPHUS_DECIMAL_PLACES,
+            Notation::Unicode => &UNICODE_DECIMAL_PLACES,
+        }
+    }
+}
+
+impl Display for Notation {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        let name = match self {
+            Notation::Standard => "standard",
+            Notation::Vinculum => "vinculum",
+            Notation::Apostrophus => "apostrophus",
+            Notation::Unicode => "Unicode",
+        };
+        write!(f, "{}", name)
+    }
+}
+
+/// How strictly to interpret a Roman numeral when converting it to a number.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Mode {
+    /// Only accept the canonical form which `to_roman` would generate.
+    #[default]
+    Strict,
+    /// Also accept historical and informal forms, such as IIII, IIX, lower case letters,
+    /// Unicode ligatures like Ⅻ, J for a final I, and `)` in place of Ↄ.
+    Lenient,
+}
+
+const STANDARD_DECIMAL_PLACES: [[&str; 3]; 4] = [
+    ["I", "V", "X"],
+    ["X", "L", "C"],
+    ["C", "D", "M"],
+    ["M", "", ""],
+];
+
+const APOSTROPHUS_DECIMAL_PLACES: [[&str; 3]; 6] = [
+    ["I", "V", "X"],
+    ["X", "L", "C"],
+    ["C", "D", "CIↃ"],
+    ["CIↃ", "IↃↃ", "CCIↃↃ"],
+    ["CCIↃↃ", "IↃↃↃ", "CCCIↃↃↃ"],
+    ["CCCIↃↃↃ", "", ""],
+];
+
+const UNICODE_DECIMAL_PLACES: [[&str; 3]; 6] = [
+    ["Ⅰ", "Ⅴ", "Ⅹ"],
+    ["Ⅹ", "Ⅼ", "Ⅽ"],
+    ["Ⅽ", "Ⅾ", "Ⅿ"],
+    ["Ⅿ", "ↁ", "ↂ"],
+    ["ↂ", "ↇ", "ↈ"],
+    ["ↈ", "", ""],
+];
+
+/// For each decimal digit, the indices into the one, five and ten symbols of its decimal place
+const DIGIT_PATTERNS: [&[usize]; 10] = [
+    &[],
+    &[0],
+    &[0, 0],
+    &[0, 0, 0],
+    &[0, 1],
+    &[1],
+    &[1, 0],
+    &[1, 0, 0],
+    &[1, 0, 0, 0],
+    &[0, 2],
+];
+
+/// Write num in the notation's decimal places, without checking the range
+pub(crate) fn append_decimal_places(roman: &mut String, num: u32, notation: Notation) {
+    let places = notation.decimal_places();
+    let mut power_of_10 = 10_u32.pow(places.len() as u32 - 1);
+    for symbols in places.iter().rev() {
+        let digit = (num / power_of_10) % 10;
+        for &index in DIGIT_PATTERNS[digit as usize] {
+            roman.push_str(symbols[index]);
+        }
+        power_of_10 /= 10;
+    }
+}
+
+pub(crate) fn append_with_vinculum(roman: &mut String, thousands: u32) {
+    let mut overlined = String::new();
+    append_decimal_places(&mut overlined, thousands, Notation::Vinculum);
+    for ch in overlined.chars() {
+        roman.push(ch);
+        roman.push(VINCULUM);
+    }
+}
@@ -0,0 +1,203 @@
+use crate::{convert_from_roman, convert_to_roman, Notation, RomanError};
+use std::fmt::{Display, Formatter};
+use std::num::NonZeroU16;
+use std::ops::{Add, Mul, Sub};
+use std::str::FromStr;
+
+/// A number which can be written as a standard Roman numeral, i.e. from 1 to 3000
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct RomanNumeral(NonZeroU16);
+
+impl RomanNumeral {
+    pub const MIN: RomanNumeral = RomanNumeral(NonZeroU16::MIN);
+    pub const MAX: RomanNumeral = match NonZeroU16::new(3000) {
+        Some(max) => RomanNumeral(max),
+        None => unreachable!(),
+    };
+
+    pub fn new(num: u16) -> Result<Self, RomanError> {
+        let non_zero = NonZeroU16::new(num).ok_or(RomanError::Zero)?;
+        let max = Notation::Standard.max_value();
+        if num as u32 > max {
+            return Err(RomanError::OutOfRange {
+                value: num as u64,
+                max,
+                notation: Notation::Standard,
+            });
+        }
+        Ok(RomanNumeral(non_zero))
+    }
+
+    pub fn get(&self) -> u16 {
+        self.0.get()
+    }
+
+    pub fn checked_add(self, rhs: Self) -> Option<Self> {
+        RomanNumeral::new(self.get() + rhs.get()).ok()
+    }
+
+    /// Returns `None` if the result would be zero or negative, since neither has a Roman numeral
+    pub fn checked_sub(self, rhs: Self) -> Option<Self> {
+        self.get()
+            .checked_sub(rhs.get())
+            .and_then(|diff| RomanNumeral::new(diff).ok())
+    }
+
+    pub fn checked_mul(self, rhs: Self) -> Option<Self> {
+        self.get()
+            .checked_mul(rhs.get())
+            .and_then(|product| RomanNumeral::new(product).ok())
+    }
+}
+
+impl TryFrom<u16> for RomanNumeral {
+    type Error = RomanError;
+
+    fn try_from(num: u16) -> Result<Self, Self::Error> {
+        RomanNumeral::new(num)
+    }
+}
+
+impl From<RomanNumeral> for u16 {
+    fn from(numeral: RomanNumeral) -> Self {
+        numeral.get()
+    }
+}
+
+impl FromStr for RomanNumeral {
+    type Err = RomanError;
+
+    fn from_str(roman: &str) -> Result<Self, Self::Err> {
+        RomanNumeral::new(convert_from_roman(roman)?)
+    }
+}
+
+impl Display for RomanNumeral {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        let roman = convert_to_roman(self.get()).expect("A RomanNumeral is always in range");
+        f.pad(&roman)
+    }
+}
+
+impl Add for RomanNumeral {
+    type Output = RomanNumeral;
+
+    fn add(self, rhs: Self) -> Self::Output {
+        self.checked_add(rhs)
+            .expect("attempt to add Roman numerals with overflow")
+    }
+}
+
+impl Sub for RomanNumeral {
+    type Output = RomanNumeral;
+
+    fn sub(self, rhs: Self) -> Self::Output {
+        self.checked_sub(rhs)
+            .expect("attempt to subtract Roman numerals with overflow")
+    }
+}
+
+impl Mul for RomanNumeral {
+    type Output = RomanNumeral;
+
+    fn mul(self, rhs: Self) -> Self::Output {
+        self.checked_mul(rhs)
+            .expect("attempt to multiply Roman numerals with overflow")
+    }
+}
+
+#[cfg(feature = "serde")]
+mod serde_impls {
+    use super::RomanNumeral;
+    use serde::de::Error;
+    use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+    impl Serialize for RomanNumeral {
+        fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+            serializer.collect_str(self)
+        }
+    }
+
+    impl<'de> Deserialize<'de> for RomanNumeral {
+        fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+            let roman = String::deserialize(deserializer)?;
+            roman.parse().map_err(D::Error::custom)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::RomanNumeral;
+    use crate::RomanError;
+
+    fn numeral(num: u16) -> RomanNumeral {
+        RomanNumeral::new(num).unwrap()
+    }
+
+    #[test]
+    fn test_new_rejects_zero_and_values_above_3000() {
+        assert_eq!(RomanNumeral::new(0), Err(RomanError::Zero));
+        assert!(RomanNumeral::new(3001).is_err());
+        assert_eq!(RomanNumeral::new(3000), Ok(RomanNumeral::MAX));
+    }
+
+    #[test]
+    fn test_display_and_from_str() {
+        assert_eq!(numeral(1972).to_string(), "MCMLXXII");
+        assert_eq!(format!("[{:>6}]", numeral(14)), "[   XIV]");
+        assert_eq!("MMXXIV".parse::<RomanNumeral>(), Ok(numeral(2024)));
+        assert_eq!(
+            "IIII".parse::<RomanNumeral>(),
+            Err(RomanError::InvalidFormat)
+        );
+    }
+
+    #[test]
+    fn test_arithmetic() {
+        assert_eq!(numeral(12) + numeral(30), numeral(42));
+        assert_eq!(numeral(50) - numeral(8), numeral(42));
+        assert_eq!(numeral(6) * numeral(7), numeral(42));
+    }
+
+    #[test]
+    fn test_checked_arithmetic() {
+        assert_eq!(RomanNumeral::MAX.checked_add(RomanNumeral::MIN), None);
+        assert_eq!(numeral(5).checked_sub(numeral(5)), None);
+        assert_eq!(numeral(5).checked_sub(numeral(6)), None);
+        assert_eq!(numeral(300).checked_mul(numeral(300)), None);
+        assert_eq!(
+            numeral(2999).checked_add(numeral(1)),
+            Some(RomanNumeral::MAX)
+        );
+    }
+
+    #[test]
+    #[should_panic(expected = "overflow")]
+    fn test_add_panics_on_overflow() {
+        let _ = RomanNumeral::MAX + RomanNumeral::MIN;
+    }
+
+    #[test]
+    fn test_ordering() {
+        let mut numerals: Vec<RomanNumeral> = ["X", "IV", "MM", "IX"]
+            .iter()
+            .map(|roman| roman.parse().unwrap())
+            .collect();
+        numerals.sort();
+        assert_eq!(
+            numerals,
+            [numeral(4), numeral(9), numeral(10), numeral(2000)]
+        );
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn test_serde_uses_the_roman_string() {
+        let json = serde_json::to_string(&vec![numeral(4), numeral(1999)]).unwrap();
+        assert_eq!(json, r#"["IV","MCMXCIX"]"#);
+        let numerals: Vec<RomanNumeral> = serde_json::from_str(&json).unwrap();
+        assert_eq!(numerals, [numeral(4), numeral(1999)]);
+        assert!(serde_json::from_str::<RomanNumeral>(r#""MMMI""#).is_err());
+    }
+}
@@ -0,0 +1,110 @@
+use crate::notation::{APOSTROPHUS, VINCULUM};
+use crate::RomanError;
+
+/// Apostrophus symbols, longest first, so that a greedy match finds the largest symbol
+const APOSTROPHUS_SYMBOLS: [(&str, u32); 6] = [
+    ("CCCIↃↃↃ", 100_000),
+    ("CCIↃↃ", 10_000),
+    ("IↃↃↃ", 50_000),
+    ("CIↃ", 1000),
+    ("IↃↃ", 5000),
+    ("IↃ", 500),
+];
+
+/// The values that each character contributes, in order.
+/// Unicode ligatures such as Ⅻ are expanded into the separate letters they stand for.
+fn get_symbol_values(symbol: char) -> Option<&'static [u32]> {
+    let values: &[u32] = match symbol {
+        'I' | 'Ⅰ' => &[1],
+        'V' | 'Ⅴ' => &[5],
+        'X' | 'Ⅹ' => &[10],
+        'L' | 'Ⅼ' | 'ↆ' => &[50],
+        'C' | 'Ⅽ' => &[100],
+        'D' | 'Ⅾ' => &[500],
+        'M' | 'Ⅿ' | 'ↀ' => &[1000],
+        'Ⅱ' => &[1, 1],
+        'Ⅲ' => &[1, 1, 1],
+        'Ⅳ' => &[1, 5],
+        'Ⅵ' | 'ↅ' => &[5, 1],
+        'Ⅶ' => &[5, 1, 1],
+        'Ⅷ' => &[5, 1, 1, 1],
+        'Ⅸ' => &[1, 10],
+        'Ⅺ' => &[10, 1],
+        'Ⅻ' => &[10, 1, 1],
+        'ↁ' => &[5000],
+        'ↂ' => &[10_000],
+        'ↇ' => &[50_000],
+        'ↈ' => &[100_000],
+        _ => return None,
+    };
+    Some(values)
+}
+
+/// Map historical and informal variants onto the symbols which `tokenize` recognizes
+pub(crate) fn normalize_leniently(roman: &str) -> String {
+    roman
+        .chars()
+        .map(|ch| match ch.to_ascii_uppercase() {
+            'J' => 'I',
+            '\u{2170}'..='\u{217F}' => char::from_u32(ch as u32 - 0x10).unwrap(),
+            '\u{304}' => VINCULUM,
+            ')' | 'ↄ' => APOSTROPHUS,
+            upper_ch => upper_ch,
+        })
+        .collect()
+}
+
+/// Split a Roman numeral into the values of its symbols, applying any vinculum
+pub(crate) fn tokenize(roman: &str) -> Result<Vec<u32>, RomanError> {
+    let chars: Vec<char> = roman.chars().collect();
+    let mut values = Vec::with_capacity(chars.len());
+    let mut position = 0;
+    while position < chars.len() {
+        let rem_chars = &chars[position..];
+        let maybe_apostrophus = APOSTROPHUS_SYMBOLS.iter().find(|(symbol, _)| {
+            let symbol_len = symbol.chars().count();
+            rem_chars.len() >= symbol_len
+                && symbol.chars().eq(rem_chars[..symbol_len].iter().copied())
+        });
+        if let Some((symbol, value)) = maybe_apostrophus {
+            values.push(*value);
+            position += symbol.chars().count();
+            continue;
+        }
+
+        let symbol = chars[position];
+        let symbol_values =
+            get_symbol_values(symbol).ok_or(RomanError::UnrecognizedSymbol { symbol, position })?;
+        let multiplier = if chars.get(position + 1) == Some(&VINCULUM) {
+            position += 1;
+            1000
+        } else {
+            1
+        };
+        values.extend(symbol_values.iter().map(|value| value * multiplier));
+        position += 1;
+    }
+    Ok(values)
+}
+
+/// Add up the values of the symbols, subtracting any run of equal symbols
+/// which precedes a larger symbol. This gives IIX = 8, as well as IX = 9.
+pub(crate) fn evaluate(values: &[u32]) -> Result<u64, RomanError> {
+    let mut total: i64 = 0;
+    let mut index = 0;
+    while index < values.len() {
+        let value = values[index];
+        let run_len = values[index..].iter().take_while(|&&v| v == value).count();
+        let run_total = value as i64 * run_len as i64;
+        match values.get(index + run_len) {
+            Some(&next_value) if next_value > value => total -= run_total,
+            _ => total += run_total,
+        }
+        index += run_len;
+    }
+    if total <= 0 {
+        Err(RomanError::InvalidFormat)
+    } else {
+        Ok(total as u64)
+    }
+}
@@ -0,0 +1,273 @@
+use crate::{convert_to_roman, from_roman, Mode, Notation};
+use regex::Regex;
+use std::collections::HashSet;
+use std::sync::OnceLock;
+
+/// Rules for deciding which words in a piece of text are Roman numerals
+#[derive(Debug, Clone)]
+pub struct ScanRules {
+    /// Use [`Mode::Lenient`] to also find forms like IIII and Unicode numerals like Ⅻ
+    pub mode: Mode,
+    /// Whether lower case words such as "xiv" can be numerals
+    pub case_insensitive: bool,
+    /// Shorter numerals (such as "I" and "V") are only found after a context word or a name
+    pub min_len: usize,
+    /// Upper case words which are valid numerals, but more often mean something else
+    /// (e.g. "MIX", "CD" or "DC"). These are also only found after a context word or a name.
+    pub ambiguous_words: HashSet<String>,
+    /// Words such as "Chapter" after which any valid numeral is accepted
+    pub context_words: HashSet<String>,
+    /// Whether a title case word in the middle of a sentence, like "Henry" in "when Henry V",
+    /// makes a short or ambiguous numeral after it acceptable. This is never applied to "I",
+    /// since that is far more often the pronoun.
+    pub after_capitalized_word: bool,
+}
+
+const DEFAULT_AMBIGUOUS_WORDS: [&str; 15] = [
+    "CC", "CD", "CM", "CV", "DC", "DI", "DIV", "DL", "LI", "MC", "MD", "MI", "MIX", "MM", "XL",
+];
+
+const DEFAULT_CONTEXT_WORDS: [&str; 12] = [
+    "act", "appendix", "book", "chapter", "part", "scene", "section", "series", "vol.", "volume",
+    "war", "year",
+];
+
+impl Default for ScanRules {
+    fn default() -> Self {
+        ScanRules {
+            mode: Mode::Strict,
+            case_insensitive: false,
+            min_len: 2,
+            ambiguous_words: DEFAULT_AMBIGUOUS_WORDS.map(String::from).into(),
+            context_words: DEFAULT_CONTEXT_WORDS.map(String::from).into(),
+            after_capitalized_word: true,
+        }
+    }
+}
+
+/// A Roman numeral found in some text, with its byte range in the text
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RomanMatch<'t> {
+    pub start: usize,
+    pub end: usize,
+    pub roman: &'t str,
+    pub value: u32,
+}
+
+fn get_candidate_regex() -> &'static Regex {
+    static CANDIDATE_REGEX: OnceLock<Regex> = OnceLock::new();
+    CANDIDATE_REGEX.get_or_init(|| Regex::new(r"\b[IVXLCDMivxlcdmjJ\x{2160}-\x{2188}]+\b").unwrap())
+}
+
+fn get_arabic_number_regex() -> &'static Regex {
+    static ARABIC_NUMBER_REGEX: OnceLock<Regex> = OnceLock::new();
+    ARABIC_NUMBER_REGEX.get_or_init(|| Regex::new(r"\b\d+(?:[.,]\d+)*\b").unwrap())
+}
+
+/// Find the Roman numerals in a piece of text, such as "Louis XIV" or "Super Bowl LVIII"
+pub fn find_roman_numerals<'t>(text: &'t str, rules: &ScanRules) -> Vec<RomanMatch<'t>> {
+    get_candidate_regex()
+        .find_iter(text)
+        .filter_map(|candidate| {
+            let roman = candidate.as_str();
+            let normalized = if rules.case_insensitive {
+                roman.to_uppercase()
+            } else if roman.chars().any(char::is_lowercase) {
+                return None;
+            } else {
+                roman.to_string()
+            };
+            let value = from_roman(&normalized, Notation::Standard, rules.mode).ok()?;
+            if !is_unambiguous(&normalized, rules)
+                && !has_supporting_context(text, candidate.start(), &normalized, rules)
+            {
+                return None;
+            }
+            Some(RomanMatch {
+                start: candidate.start(),
+                end: candidate.end(),
+                roman,
+                value,
+            })
+        })
+        .collect()
+}
+
+/// Replace each Roman numeral found in the text with its value in Arabic numerals
+pub fn replace_roman_with_arabic(text: &str, rules: &ScanRules) -> String {
+    let mut rewritten = String::with_capacity(text.len());
+    let mut last_end = 0;
+    for roman_match in find_roman_numerals(text, rules) {
+        rewritten.push_str(&text[last_end..roman_match.start]);
+        rewritten.push_str(&roman_match.value.to_string());
+        last_end = roman_match.end;
+    }
+    rewritten.push_str(&text[last_end..]);
+    rewritten
+}
+
+/// Replace each whole number from 1 to 3000 in the text with a Roman numeral.
+/// Numbers with a decimal point or thousands separator, such as 3.14 or 1,000, are left alone.
+pub fn replace_arabic_with_roman(text: &str) -> String {
+    get_arabic_number_regex()
+        .replace_all(text, |caps: &regex::Captures| {
+            let number = &caps[0];
+            number
+                .parse::<u16>()
+                .ok()
+                .and_then(|num| convert_to_roman(num).ok())
+                .unwrap_or_else(|| number.to_string())
+        })
+        .into_owned()
+}
+
+fn is_unambiguous(roman: &str, rules: &ScanRules) -> bool {
+    roman.chars().count() >= rules.min_len && !rules.ambiguous_words.contains(roman)
+}
+
+fn has_supporting_context(text: &str, start: usize, roman: &str, rules: &ScanRules) -> bool {
+    let before = &text[..start];
+    let trimmed = before.trim_end_matches(' ');
+    if trimmed.len() == before.len() {
+        // The numeral isn't separated from the previous word by spaces
+        return false;
+    }
+    let word_start = trimmed
+        .char_indices()
+        .rev()
+        .find(|(_, ch)| !(ch.is_alphabetic() || *ch == '.'))
+        .map_or(0, |(index, ch)| index + ch.len_utf8());
+    let prev_word = &trimmed[word_start..];
+    if prev_word.is_empty() {
+        return false;
+    }
+    if rules.context_words.contains(&prev_word.to_lowercase()) {
+        return true;
+    }
+    rules.after_capitalized_word
+        && roman != "I"
+        && prev_word.chars().next().is_some_and(char::is_uppercase)
+        && prev_word.chars().skip(1).all(char::is_lowercase)
+        && !prev_word.ends_with('.')
+        && !is_start_of_sentence(&trimmed[..word_start])
+}
+
+fn is_start_of_sentence(text_before_word: &str) -> bool {
+    text_before_word
+        .trim_end()
+        .chars()
+        .next_back()
+        .is_none_or(|ch| matches!(ch, '.' | '!' | '?' | '\n' | '"' | '“'))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::ScanRules;
+    use super::{find_roman_numerals, replace_arabic_with_roman, replace_roman_with_arabic};
+    use crate::Mode;
+
+    fn find_values(text: &str, rules: &ScanRules) -> Vec<u32> {
+        find_roman_numerals(text, rules)
+            .iter()
+            .map(|roman_match| roman_match.value)
+            .collect()
+    }
+
+    #[test]
+    fn test_finding_numerals_in_prose() {
+        let rules = ScanRules::default();
+        let text = "Louis XIV was born in MDCXXXVIII. Super Bowl LVIII was in 2024.";
+        let matches = find_roman_numerals(text, &rules);
+        assert_eq!(matches.len(), 3);
+        assert_eq!(matches[0].roman, "XIV");
+        assert_eq!(&text[matches[0].start..matches[0].end], "XIV");
+        assert_eq!(find_values(text, &rules), [14, 1638, 58]);
+    }
+
+    #[test]
+    fn test_ordinary_words_are_ignored() {
+        let rules = ScanRules::default();
+        assert!(find_values("I think I will MIX the paint. I LIKE CD's.", &rules).is_empty());
+        assert!(find_values("mix it with civil dim light", &rules).is_empty());
+        assert!(find_values("Then I went home. IIII is not canonical.", &rules).is_empty());
+    }
+
+    #[test]
+    fn test_context_words_allow_short_and_ambiguous_numerals() {
+        let rules = ScanRules::default();
+        assert_eq!(
+            find_values("World War I ended. See chapter V.", &rules),
+            [1, 5]
+        );
+        assert_eq!(find_values("Act III, Scene I", &rules), [3, 1]);
+        assert_eq!(find_values("Part MIX", &rules), [1009]);
+    }
+
+    #[test]
+    fn test_capitalized_word_allows_short_numerals() {
+        let rules = ScanRules::default();
+        assert_eq!(find_values("The reign of Henry V began.", &rules), [5]);
+        assert!(find_values("Then V began.", &rules).is_empty());
+        assert!(find_values("Since Tuesday I have rested.", &rules).is_empty());
+
+        let no_names = ScanRules {
+            after_capitalized_word: false,
+            ..ScanRules::default()
+        };
+        assert!(find_values("The reign of Henry V began.", &no_names).is_empty());
+    }
+
+    #[test]
+    fn test_case_insensitive_and_lenient_rules() {
+        let rules = ScanRules {
+            mode: Mode::Lenient,
+            case_insensitive: true,
+            ..ScanRules::default()
+        };
+        assert_eq!(
+            find_values("clock faces show IIII; chapter xiv; Book Ⅻ", &rules),
+            [4, 14, 12]
+        );
+        assert!(find_values("chapter xiv", &ScanRules::default()).is_empty());
+    }
+
+    #[test]
+    fn test_case_insensitive_strict_rules() {
+        let rules = ScanRules {
+            case_insensitive: true,
+            ..ScanRules::default()
+        };
+        assert_eq!(find_values("chapter xiv and Chapter XIV", &rules), [14, 14]);
+        assert_eq!(find_values("Louis xiv and louis XIV", &rules), [14, 14]);
+        assert!(find_values("mix it with civil dim light", &rules).is_empty());
+        assert!(find_values("chapter iiii", &rules).is_empty());
+    }
+
+    #[test]
+    fn test_replacing_roman_with_arabic() {
+        let rules = ScanRules::default();
+        assert_eq!(
+            replace_roman_with_arabic("Chapter IV: Louis XIV and Super Bowl LVIII", &rules),
+            "Chapter 4: Louis 14 and Super Bowl 58"
+        );
+    }
+
+    #[test]
+    fn test_replacing_arabic_with_roman() {
+        assert_eq!(
+            replace_arabic_with_roman(
+                "Chapter 4 of 12, written in 1999. Pi is 3.14; 5000 is too big; 1,000 too."
+            ),
+            "Chapter IV of XII, written in MCMXCIX. Pi is 3.14; 5000 is too big; 1,000 too."
+        );
+    }
+
+    #[test]
+    fn test_round_trip() {
+        let rules = ScanRules::default();
+        let text = "Book 3, section 42 (revised 2021)";
+        let roman_text = replace_arabic_with_roman(text);
+        assert_eq!(roman_text, "Book III, section XLII (revised MMXXI)");
+        assert_eq!(replace_roman_with_arabic(&roman_text, &rules), text);
+    }
+}
@@ -0,0 +1,53 @@
+//! Problem 54 again, using the card-hand evaluation shared with Advent of Code 2023 day 7.
+
+use aoc_common::cards::CardGame;
+use std::cmp::Ordering;
+use std::fs;
+use std::time::Instant;
+
+const REPETITIONS: u32 = 1000;
+
+fn main() {
+    let contents = fs::read_to_string("data/0054_poker.txt").expect("Error loading input data");
+    let game = CardGame::poker();
+    let mut start_time = Instant::now();
+    for rep in 0..=REPETITIONS {
+        let solution = count_player1_wins(&game, &contents);
+        if rep == 0 {
+            println!("Solution: {solution}");
+            println!(
+                "Solved (including writing to terminal) in {:?}",
+                start_time.elapsed()
+            );
+
+            // Now restart the timer, so that the timings don't include I/O...
+            start_time = Instant::now();
+        }
+    }
+    let avg_duration = start_time.elapsed() / REPETITIONS;
+    println!("Average duration (excl I/O) over {REPETITIONS} further runs: {avg_duration:?}");
+}
+
+fn count_player1_wins(game: &CardGame, contents: &str) -> usize {
+    contents
+        .lines()
+        .filter(|line| {
+            let words: Vec<&str> = line.split_ascii_whitespace().collect();
+            let (player1_cards, player2_cards) = words.split_at(words.len() / 2);
+            let player1_hand = game.parse_hand(&player1_cards.join(" ")).unwrap();
+            let player2_hand = game.parse_hand(&player2_cards.join(" ")).unwrap();
+            game.compare(&player1_hand, &player2_hand) == Ordering::Greater
+        })
+        .count()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_full_dataset() {
+        let contents = fs::read_to_string("data/0054_poker.txt").unwrap();
+        assert_eq!(count_player1_wins(&CardGame::poker(), &contents), 376);
+    }
+}